@@ -1,28 +1,155 @@
-use crate::models::config::ServerProperties;
-use crate::services::config_parser;
+use crate::models::config::{
+    ConfigChange, ConfigFileInfo, ConfigKeyChange, ConfigNode, RconStatus, ServerProperties,
+};
+use crate::services::{config_editor, config_history, config_parser, global, path_guard, rcon};
 use std::collections::HashMap;
+use std::path::Path;
 
 #[tauri::command]
-pub fn read_config(path: String) -> Result<HashMap<String, String>, String> {
-    config_parser::read_properties(&path)
+pub fn read_config(server_id: String, file: String) -> Result<HashMap<String, String>, String> {
+    let path = path_guard::resolve(&server_id, &file)?;
+    config_parser::read_properties(&path.to_string_lossy())
 }
 
 #[tauri::command]
-pub fn write_config(path: String, values: HashMap<String, String>) -> Result<(), String> {
-    config_parser::write_properties(&path, &values)
+pub fn write_config(
+    server_id: String,
+    file: String,
+    values: HashMap<String, String>,
+) -> Result<(), String> {
+    let root = path_guard::server_root(&server_id)?;
+    let path = path_guard::properties_file(&root, &file)?;
+    // server.properties 需要经过字段校验
+    if path == root.join("server.properties") {
+        return write_server_properties(server_id, values);
+    }
+    journaled(&server_id, &root, &file, || {
+        config_parser::write_properties(&path.to_string_lossy(), &values)
+    })
 }
 
 #[tauri::command]
-pub fn read_server_properties(server_path: String) -> Result<ServerProperties, String> {
-    let props_path = format!("{}/server.properties", server_path);
-    config_parser::parse_server_properties(&props_path)
+pub fn read_server_properties(server_id: String) -> Result<ServerProperties, String> {
+    let props_path = path_guard::resolve(&server_id, "server.properties")?;
+    let locale = global::settings_manager().get().language;
+    config_parser::parse_server_properties(&props_path.to_string_lossy(), &locale)
 }
 
 #[tauri::command]
 pub fn write_server_properties(
-    server_path: String,
+    server_id: String,
     values: HashMap<String, String>,
 ) -> Result<(), String> {
-    let props_path = format!("{}/server.properties", server_path);
-    config_parser::write_properties(&props_path, &values)
+    let root = path_guard::server_root(&server_id)?;
+    let props_path = path_guard::resolve_in(&root, "server.properties")?;
+    journaled(&server_id, &root, "server.properties", || {
+        config_parser::write_server_properties(&props_path.to_string_lossy(), &values)
+    })
+}
+
+#[tauri::command]
+pub fn list_config_files(server_id: String) -> Result<Vec<ConfigFileInfo>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    Ok(config_editor::discover_config_files(&root))
+}
+
+#[tauri::command]
+pub fn read_config_file(server_id: String, file: String) -> Result<Vec<ConfigNode>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    path_guard::resolve_in(&root, &file)?;
+    config_editor::list_values(&root, &file)
+}
+
+#[tauri::command]
+pub fn get_config_value(
+    server_id: String,
+    file: String,
+    path: Vec<String>,
+) -> Result<serde_json::Value, String> {
+    let root = path_guard::server_root(&server_id)?;
+    path_guard::resolve_in(&root, &file)?;
+    config_editor::get_value(&root, &file, &path)
+}
+
+#[tauri::command]
+pub fn set_config_value(
+    server_id: String,
+    file: String,
+    path: Vec<String>,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let root = path_guard::server_root(&server_id)?;
+    path_guard::resolve_in(&root, &file)?;
+    journaled(&server_id, &root, &file, || {
+        config_editor::set_value(&root, &file, &path, &value)
+    })
+}
+
+#[tauri::command]
+pub fn list_config_history(server_id: String) -> Vec<ConfigChange> {
+    config_history::list(&config_history::history_dir(&server_id))
+}
+
+#[tauri::command]
+pub fn diff_config_history(
+    server_id: String,
+    entry_id: String,
+) -> Result<Vec<ConfigKeyChange>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    config_history::diff(&config_history::history_dir(&server_id), &root, &entry_id)
+}
+
+#[tauri::command]
+pub fn restore_config_history(
+    server_id: String,
+    entry_id: String,
+) -> Result<Option<ConfigChange>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    let restored =
+        config_history::restore(&config_history::history_dir(&server_id), &root, &entry_id)?;
+    global::server_manager().sync_port_from_properties(&server_id);
+    Ok(restored)
+}
+
+#[tauri::command]
+pub fn get_rcon_status(server_id: String) -> Result<RconStatus, String> {
+    let root = path_guard::server_root(&server_id)?;
+    let config = rcon::read_config(&root.to_string_lossy())
+        .ok_or_else(|| "未找到 server.properties".to_string())?;
+    Ok(RconStatus {
+        enabled: config.enabled,
+        port: config.port,
+        password_set: !config.password.is_empty(),
+    })
+}
+
+/// 生成并写入新的 RCON 密码，服务器重启后生效
+#[tauri::command]
+pub fn generate_rcon_password(server_id: String) -> Result<String, String> {
+    let root = path_guard::server_root(&server_id)?;
+    let props_path = path_guard::resolve_in(&root, "server.properties")?;
+    let password = rcon::generate_password();
+    let values = HashMap::from([("rcon.password".to_string(), password.clone())]);
+    journaled(&server_id, &root, "server.properties", || {
+        config_parser::write_properties(&props_path.to_string_lossy(), &values)
+    })?;
+    Ok(password)
+}
+
+/// 执行写入，并记录到服务器的配置历史；写入 server.properties 后同步端口
+fn journaled(
+    server_id: &str,
+    root: &Path,
+    file: &str,
+    write: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let pending = config_history::begin(&config_history::history_dir(server_id), root, file);
+    write()?;
+    if let Err(e) = pending.commit() {
+        println!("[配置历史] 记录失败: {}", e);
+    }
+    if file == "server.properties" {
+        global::server_manager().sync_port_from_properties(server_id);
+    }
+    Ok(())
 }
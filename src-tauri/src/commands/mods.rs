@@ -1,5 +1,10 @@
+use crate::models::server::{ModProfile, ServerInstance};
 use crate::services::global;
-use crate::services::mod_manager::ModInfo;
+use crate::services::mod_inventory::{self, InstalledModEntry, ModToggleResult};
+use crate::services::mod_manager::{
+    self, ClientOnlyMod, ModInfo, ModInstallPlan, ModUpdateInfo, ModUpdateReport, ModUpdateResult,
+};
+use crate::services::plugin_market;
 use std::path::PathBuf;
 use tauri::command;
 
@@ -15,25 +20,192 @@ pub async fn search_mods(
         .await
 }
 
+/// 根据服务端核心返回可安装的内容类型: "mods"、"plugins" 或 "none"
+#[command]
+pub fn get_server_content_kind(server_id: String) -> Result<String, String> {
+    let core = mod_manager::core_hint(&find_server(&server_id)?);
+    let kind = if mod_manager::loader_for_core_type(&core).is_some() {
+        "mods"
+    } else if plugin_market::plugin_loaders_for_core(&core).is_some() {
+        "plugins"
+    } else {
+        "none"
+    };
+    Ok(kind.to_string())
+}
+
+#[command]
+pub async fn search_plugins(server_id: String, query: String) -> Result<Vec<ModInfo>, String> {
+    let server = find_server(&server_id)?;
+    require_mc_version(&server)?;
+    let core = mod_manager::core_hint(&server);
+    let loaders = plugin_market::plugin_loaders_for_core(&core)
+        .ok_or_else(|| format!("核心类型 {} 不支持插件", server.core_type))?;
+    global::mod_manager()
+        .search_plugins(&query, &server.mc_version, loaders)
+        .await
+}
+
+#[command]
+pub async fn install_plugin(server_id: String, plugin: ModInfo) -> Result<String, String> {
+    let server = find_server(&server_id)?;
+    require_mc_version(&server)?;
+    let loaders = plugin_market::plugin_loaders_for_core(&mod_manager::core_hint(&server))
+        .ok_or_else(|| format!("核心类型 {} 不支持插件", server.core_type))?;
+    let plugins_dir = PathBuf::from(&server.path).join("plugins");
+    global::mod_manager()
+        .install_plugin(&plugins_dir, &plugin, &server.mc_version, loaders)
+        .await
+}
+
+#[command]
+pub async fn plan_mod_install(
+    server_id: String,
+    project_id: String,
+    version_id: Option<String>,
+    loader: Option<String>,
+) -> Result<ModInstallPlan, String> {
+    let server = find_server(&server_id)?;
+    let loader = resolve_loader(&server, loader)?;
+    require_mc_version(&server)?;
+
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    global::mod_manager()
+        .plan_install(&mods_dir, &project_id, version_id.as_deref(), &server.mc_version, &loader)
+        .await
+}
+
+/// 安装前在后端重新生成计划，不信任前端传回的下载地址与校验值
 #[command]
 pub async fn install_mod(
     server_id: String,
-    download_url: String,
+    project_id: String,
+    version_id: Option<String>,
+    loader: Option<String>,
+) -> Result<Vec<String>, String> {
+    let server = find_server(&server_id)?;
+    let loader = resolve_loader(&server, loader)?;
+    require_mc_version(&server)?;
+
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    let manager = global::mod_manager();
+    let plan = manager
+        .plan_install(&mods_dir, &project_id, version_id.as_deref(), &server.mc_version, &loader)
+        .await?;
+    manager.install_plan(&mods_dir, &plan).await
+}
+
+#[command]
+pub async fn check_mod_updates(
+    server_id: String,
+    loader: Option<String>,
+) -> Result<ModUpdateReport, String> {
+    let server = find_server(&server_id)?;
+    let loader = resolve_loader(&server, loader)?;
+    require_mc_version(&server)?;
+
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    global::mod_manager()
+        .check_updates(&mods_dir, &server.mc_version, &loader)
+        .await
+}
+
+#[command]
+pub async fn update_mods(
+    server_id: String,
+    updates: Vec<ModUpdateInfo>,
+) -> Result<ModUpdateResult, String> {
+    let server = find_server(&server_id)?;
+    if global::server_manager()
+        .get_server_status(&server_id)
+        .status
+        .is_active()
+    {
+        return Err("请先停止服务器再更新模组".to_string());
+    }
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    global::mod_manager()
+        .apply_updates(&mods_dir, &updates)
+        .await
+}
+
+/// 列出服务端不支持的模组，auto_disable 为 true 时同时禁用它们
+#[command]
+pub async fn scan_client_only_mods(
+    server_id: String,
+    auto_disable: bool,
+) -> Result<Vec<ClientOnlyMod>, String> {
+    let server = find_server(&server_id)?;
+    let mods_dir = PathBuf::from(&server.path).join("mods");
+    global::mod_manager()
+        .scan_client_only(&mods_dir, auto_disable)
+        .await
+}
+
+/// kind 为 "mods" 或 "plugins"
+#[command]
+pub fn list_installed_mods(
+    server_id: String,
+    kind: String,
+) -> Result<Vec<InstalledModEntry>, String> {
+    let server = find_server(&server_id)?;
+    mod_inventory::list_installed(&mod_inventory::content_dir(&server.path, &kind)?)
+}
+
+#[command]
+pub fn set_mod_enabled(
+    server_id: String,
+    kind: String,
     file_name: String,
-) -> Result<(), String> {
-    let server_path = {
-        let server_manager = global::server_manager();
-        let servers = server_manager.servers.lock().unwrap();
-        let server = servers
-            .iter()
-            .find(|s| s.id == server_id)
-            .ok_or("Server not found")?;
-        server.path.clone()
-    }; // MutexGuard 在这里被释放
-
-    let mods_dir = PathBuf::from(&server_path).join("mods");
-    let target_path = mods_dir.join(file_name);
+    enabled: bool,
+) -> Result<ModToggleResult, String> {
+    let server = find_server(&server_id)?;
+    let dir = mod_inventory::content_dir(&server.path, &kind)?;
+    mod_inventory::set_enabled(&dir, &file_name, enabled)
+}
 
-    let mod_manager = global::mod_manager();
-    mod_manager.download_mod(&download_url, &target_path).await
+#[command]
+pub fn list_mod_profiles(server_id: String) -> Result<Vec<ModProfile>, String> {
+    Ok(find_server(&server_id)?.mod_profiles)
+}
+
+#[command]
+pub fn save_mod_profile(server_id: String, name: String) -> Result<ModProfile, String> {
+    global::server_manager().save_mod_profile(&server_id, &name)
+}
+
+#[command]
+pub fn delete_mod_profile(server_id: String, profile_id: String) -> Result<(), String> {
+    global::server_manager().delete_mod_profile(&server_id, &profile_id)
+}
+
+#[command]
+pub fn apply_mod_profile(server_id: String, profile_id: String) -> Result<Vec<String>, String> {
+    global::server_manager().apply_mod_profile(&server_id, &profile_id)
+}
+
+fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| "Server not found".to_string())
+}
+
+fn require_mc_version(server: &ServerInstance) -> Result<(), String> {
+    if server.mc_version.is_empty() || server.mc_version == "unknown" {
+        return Err("服务器未设置 Minecraft 版本，请先在服务器设置中填写".to_string());
+    }
+    Ok(())
+}
+
+fn resolve_loader(server: &ServerInstance, loader: Option<String>) -> Result<String, String> {
+    match loader.filter(|l| !l.trim().is_empty()) {
+        Some(l) => Ok(l),
+        None => mod_manager::loader_for_core_type(&mod_manager::core_hint(server))
+            .map(|l| l.to_string())
+            .ok_or_else(|| {
+                format!("无法从核心类型 {} 推断模组加载器，请手动指定", server.core_type)
+            }),
+    }
 }
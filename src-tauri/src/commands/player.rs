@@ -1,6 +1,12 @@
+use crate::commands::server::blocking;
+use crate::models::server::ServerStatus;
+use crate::services::ban_manager::{self, BanOrigin, BanRecord};
+use crate::services::console_response;
 use crate::services::global;
+use crate::services::path_guard;
 use crate::services::player_manager;
-use crate::services::player_manager::{BanEntry, OpEntry, PlayerEntry};
+use crate::services::player_manager::{BanEntry, IpBanEntry, OpEntry, PlayerEntry};
+use std::time::Duration;
 
 fn manager() -> &'static crate::services::server_manager::ServerManager {
     global::server_manager()
@@ -9,110 +15,169 @@ fn manager() -> &'static crate::services::server_manager::ServerManager {
 // ---- Read lists from files ----
 
 #[tauri::command]
-pub fn get_whitelist(server_path: String) -> Result<Vec<PlayerEntry>, String> {
-    player_manager::read_whitelist(&server_path)
+pub fn get_whitelist(server_id: String) -> Result<Vec<PlayerEntry>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    player_manager::read_whitelist(&root.to_string_lossy())
 }
 
 #[tauri::command]
-pub fn get_banned_players(server_path: String) -> Result<Vec<BanEntry>, String> {
-    player_manager::read_banned_players(&server_path)
+pub fn get_banned_players(server_id: String) -> Result<Vec<BanEntry>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    player_manager::read_banned_players(&root.to_string_lossy())
 }
 
 #[tauri::command]
-pub fn get_ops(server_path: String) -> Result<Vec<OpEntry>, String> {
-    player_manager::read_ops(&server_path)
+pub fn get_banned_ips(server_id: String) -> Result<Vec<IpBanEntry>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    player_manager::read_banned_ips(&root.to_string_lossy())
 }
 
-// ---- Modify via server console commands ----
+#[tauri::command]
+pub fn get_ops(server_id: String) -> Result<Vec<OpEntry>, String> {
+    let root = path_guard::server_root(&server_id)?;
+    player_manager::read_ops(&root.to_string_lossy())
+}
 
 #[tauri::command]
-pub fn add_to_whitelist(server_id: String, name: String) -> Result<String, String> {
-    let cmd = format!("whitelist add {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    // Force save whitelist to file and reload
-    let _ = manager().send_command(&server_id, "whitelist reload");
-    // Give server time to write the file
-    std::thread::sleep(std::time::Duration::from_millis(500));
-    Ok(format!("Sent: {}", cmd))
+pub async fn resolve_player_uuid(server_id: String, name: String) -> Result<PlayerEntry, String> {
+    let root = path_guard::server_root(&server_id)?;
+    player_manager::resolve_player(&root.to_string_lossy(), &name).await
 }
 
+// ---- Modify: console commands with acknowledgement while running, list files otherwise ----
+
 #[tauri::command]
-pub fn remove_from_whitelist(server_id: String, name: String) -> Result<String, String> {
-    // First, try to remove via command
-    let cmd = format!("whitelist remove {}", name);
-    let _ = manager().send_command(&server_id, &cmd);
-
-    // Also manually remove from file to ensure it's gone
-    let servers = manager().get_server_list();
-    if let Some(server) = servers.iter().find(|s| s.id == server_id) {
-        let whitelist_path = std::path::Path::new(&server.path).join("whitelist.json");
-        if whitelist_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&whitelist_path) {
-                if let Ok(mut list) =
-                    serde_json::from_str::<Vec<player_manager::PlayerEntry>>(&content)
-                {
-                    // Remove player from list (case-insensitive comparison)
-                    list.retain(|p| !p.name.eq_ignore_ascii_case(&name));
-                    // Write back to file
-                    if let Ok(json) = serde_json::to_string_pretty(&list) {
-                        let _ = std::fs::write(&whitelist_path, json);
-                    }
-                }
-            }
-        }
+pub async fn add_to_whitelist(server_id: String, name: String) -> Result<String, String> {
+    let (path, running) = server_context(&server_id)?;
+    if running {
+        return console(server_id, format!("whitelist add {}", name)).await;
     }
+    let player = player_manager::resolve_player(&path, &name).await?;
+    let changed = player_manager::add_to_whitelist(&path, &player)?;
+    Ok(result_message(changed, "Whitelisted", &player.name))
+}
 
-    // Reload whitelist
-    let _ = manager().send_command(&server_id, "whitelist reload");
+#[tauri::command]
+pub async fn remove_from_whitelist(server_id: String, name: String) -> Result<String, String> {
+    let (path, running) = server_context(&server_id)?;
+    if running {
+        return console(server_id, format!("whitelist remove {}", name)).await;
+    }
+    let changed = player_manager::remove_from_whitelist(&path, &name)?;
+    Ok(result_message(changed, "Removed from whitelist", &name))
+}
 
-    Ok(format!("Removed: {}", name))
+/// duration_secs 为 None 时永久封禁
+#[tauri::command]
+pub async fn ban_player(
+    server_id: String,
+    name: String,
+    reason: String,
+    duration_secs: Option<u64>,
+) -> Result<BanRecord, String> {
+    let duration = duration_secs.map(Duration::from_secs);
+    ban_manager::ban_player(&server_id, &name, &reason, duration, &BanOrigin::local("ui")).await
 }
 
 #[tauri::command]
-pub fn ban_player(server_id: String, name: String, reason: String) -> Result<String, String> {
-    let cmd = if reason.is_empty() {
-        format!("ban {}", name)
-    } else {
-        format!("ban {} {}", name, reason)
-    };
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+pub async fn unban_player(server_id: String, name: String) -> Result<BanRecord, String> {
+    blocking(move || {
+        ban_manager::pardon(
+            &server_id,
+            ban_manager::KIND_PLAYER,
+            &name,
+            "",
+            &BanOrigin::local("ui"),
+        )
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn unban_player(server_id: String, name: String) -> Result<String, String> {
-    let cmd = format!("pardon {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+pub async fn ban_ip(
+    server_id: String,
+    ip: String,
+    reason: String,
+    duration_secs: Option<u64>,
+) -> Result<BanRecord, String> {
+    let duration = duration_secs.map(Duration::from_secs);
+    blocking(move || {
+        ban_manager::ban_ip(&server_id, &ip, &reason, duration, &BanOrigin::local("ui"))
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn add_op(server_id: String, name: String) -> Result<String, String> {
-    let cmd = format!("op {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+pub async fn unban_ip(server_id: String, ip: String) -> Result<BanRecord, String> {
+    blocking(move || {
+        ban_manager::pardon(&server_id, ban_manager::KIND_IP, &ip, "", &BanOrigin::local("ui"))
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn remove_op(server_id: String, name: String) -> Result<String, String> {
-    let cmd = format!("deop {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+pub fn get_ban_history(server_id: String) -> Vec<BanRecord> {
+    ban_manager::history(&server_id)
 }
 
 #[tauri::command]
-pub fn kick_player(server_id: String, name: String, reason: String) -> Result<String, String> {
+pub async fn add_op(server_id: String, name: String) -> Result<String, String> {
+    let (path, running) = server_context(&server_id)?;
+    if running {
+        return console(server_id, format!("op {}", name)).await;
+    }
+    let player = player_manager::resolve_player(&path, &name).await?;
+    let level = player_manager::default_op_level(&path);
+    let changed = player_manager::add_op(&path, &player, level)?;
+    Ok(result_message(changed, "Opped", &player.name))
+}
+
+#[tauri::command]
+pub async fn remove_op(server_id: String, name: String) -> Result<String, String> {
+    let (path, running) = server_context(&server_id)?;
+    if running {
+        return console(server_id, format!("deop {}", name)).await;
+    }
+    let changed = player_manager::remove_op(&path, &name)?;
+    Ok(result_message(changed, "De-opped", &name))
+}
+
+#[tauri::command]
+pub async fn kick_player(
+    server_id: String,
+    name: String,
+    reason: String,
+) -> Result<String, String> {
     let cmd = if reason.is_empty() {
         format!("kick {}", name)
     } else {
         format!("kick {} {}", name, reason)
     };
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+    console(server_id, cmd).await
 }
 
-#[tauri::command]
-pub fn export_logs(logs: Vec<String>, save_path: String) -> Result<(), String> {
-    let content = logs.join("\n");
-    std::fs::write(&save_path, content).map_err(|e| format!("保存失败: {}", e))
+/// 服务器目录与是否正在运行；启动或关闭过程中名单文件由服务器持有，此时拒绝修改
+fn server_context(server_id: &str) -> Result<(String, bool), String> {
+    let root = path_guard::server_root(server_id)?;
+    let running = match manager().get_server_status(server_id).status {
+        ServerStatus::Running => true,
+        ServerStatus::Starting | ServerStatus::Stopping => {
+            return Err("服务器正在启动或关闭，请稍后再试".to_string())
+        }
+        ServerStatus::Stopped | ServerStatus::Error => false,
+    };
+    Ok((root.to_string_lossy().to_string(), running))
+}
+
+fn result_message(changed: bool, action: &str, name: &str) -> String {
+    if changed {
+        format!("{}: {}", action, name)
+    } else {
+        format!("Nothing changed: {}", name)
+    }
+}
+
+/// 发送命令并等待服务器的响应，返回其输出
+async fn console(server_id: String, command: String) -> Result<String, String> {
+    blocking(move || console_response::run(&server_id, &command)).await
 }
@@ -1,5 +1,12 @@
 use crate::models::server::*;
-use crate::services::global;
+use crate::services::modpack_export::{self, ModpackExportResult};
+use crate::services::{
+    curseforge, global, java_detector, jvm_presets, path_guard, port_manager, rcon, server_ping,
+};
+use std::time::Duration;
+
+/// 状态探测的超时
+const PING_TIMEOUT: Duration = Duration::from_secs(3);
 
 fn manager() -> &'static crate::services::server_manager::ServerManager {
     global::server_manager()
@@ -58,13 +65,15 @@ pub fn import_server(
 }
 
 #[tauri::command]
-pub fn import_modpack(
+#[allow(clippy::too_many_arguments)]
+pub async fn import_modpack(
     name: String,
     modpack_path: String,
     java_path: String,
     max_memory: u32,
     min_memory: u32,
     port: u16,
+    disable_client_mods: Option<bool>,
 ) -> Result<ServerInstance, String> {
     let req = ImportModpackRequest {
         name,
@@ -74,7 +83,66 @@ pub fn import_modpack(
         min_memory,
         port,
     };
-    manager().import_modpack(req)
+    let lower_path = req.modpack_path.to_lowercase();
+    let server = if lower_path.ends_with(".mrpack") {
+        manager().import_mrpack(req).await?
+    } else if lower_path.ends_with(".zip")
+        && curseforge::is_curseforge_pack(std::path::Path::new(&req.modpack_path))
+    {
+        manager().import_curseforge(req).await?
+    } else {
+        tauri::async_runtime::spawn_blocking(move || manager().import_modpack(req))
+            .await
+            .map_err(|e| format!("导入整合包任务失败: {}", e))??
+    };
+    report_client_only_mods(&server, disable_client_mods.unwrap_or(false)).await;
+    Ok(server)
+}
+
+/// 导入整合包后扫描仅客户端模组，结果写入该服务器的控制台日志
+async fn report_client_only_mods(server: &ServerInstance, auto_disable: bool) {
+    let mods_dir = std::path::Path::new(&server.path).join("mods");
+    let found = match global::mod_manager()
+        .scan_client_only(&mods_dir, auto_disable)
+        .await
+    {
+        Ok(found) => found,
+        Err(e) => {
+            manager().append_log(&server.id, &format!("[Sea Lantern] 仅客户端模组扫描失败: {}", e));
+            return;
+        }
+    };
+    if found.is_empty() {
+        return;
+    }
+    let action = if auto_disable {
+        "已自动禁用"
+    } else {
+        "启动前请禁用"
+    };
+    manager().append_log(
+        &server.id,
+        &format!("[Sea Lantern] 检测到 {} 个仅客户端模组，{}:", found.len(), action),
+    );
+    for item in &found {
+        manager().append_log(&server.id, &format!("  - {} ({})", item.file_name, item.source));
+    }
+}
+
+/// 登记由 systemd、screen 或其他面板启动的外部服务器
+#[tauri::command]
+pub fn attach_server(
+    name: String,
+    path: String,
+    port: Option<u16>,
+    attach: AttachConfig,
+) -> Result<ServerInstance, String> {
+    manager().attach_server(AttachServerRequest { name, path, port, attach })
+}
+
+#[tauri::command]
+pub fn update_attach_config(id: String, attach: Option<AttachConfig>) -> Result<(), String> {
+    manager().update_attach_config(&id, attach)
 }
 
 #[tauri::command]
@@ -98,6 +166,38 @@ pub fn send_command(id: String, command: String) -> Result<(), String> {
     manager().send_command(&id, &command)
 }
 
+/// 通过 RCON 发送命令并返回服务器的响应
+#[tauri::command]
+pub async fn send_rcon_command(id: String, command: String) -> Result<String, String> {
+    let root = path_guard::server_root(&id)?;
+    tauri::async_runtime::spawn_blocking(move || rcon::exec(&root.to_string_lossy(), &command))
+        .await
+        .map_err(|e| format!("RCON 任务失败: {}", e))?
+}
+
+/// 通过 Server List Ping 检查本机服务器是否在响应，并获取 MOTD 与图标
+#[tauri::command]
+pub async fn ping_server(id: String) -> Result<ServerPingStatus, String> {
+    let server = find_server(&id)?;
+    blocking(move || server_ping::ping("127.0.0.1", server.port, PING_TIMEOUT)).await
+}
+
+/// 通过 Query 协议获取完整玩家列表和插件，需要 enable-query=true
+#[tauri::command]
+pub async fn query_server(id: String) -> Result<QueryStatus, String> {
+    let server = find_server(&id)?;
+    blocking(move || {
+        let port = server_ping::query_port(&server.path, server.port)?;
+        server_ping::query("127.0.0.1", port, PING_TIMEOUT)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn ping_address(host: String, port: u16) -> Result<ServerPingStatus, String> {
+    blocking(move || server_ping::ping(&host, port, PING_TIMEOUT)).await
+}
+
 #[tauri::command]
 pub fn get_server_list() -> Vec<ServerInstance> {
     manager().get_server_list()
@@ -138,7 +238,122 @@ pub fn delete_server_command(id: String, command_id: String) -> Result<(), Strin
     manager().delete_server_command(&id, &command_id)
 }
 
+#[tauri::command]
+pub fn list_jvm_presets() -> Vec<JvmPresetInfo> {
+    jvm_presets::list()
+}
+
+/// 预设在该服务器的 Java 与内存下实际使用的参数
+#[tauri::command]
+pub async fn preview_jvm_preset(id: String, preset: String) -> Result<Vec<String>, String> {
+    blocking(move || {
+        let server = find_server(&id)?;
+        let java = java_detector::validate_java(&server.java_path)?;
+        let flags = jvm_presets::flags(
+            &preset,
+            server.max_memory,
+            java.major_version,
+            java.vendor == "GraalVM",
+        )?;
+        Ok(jvm_presets::retain_supported(&server.java_path, flags).0)
+    })
+    .await
+}
+
+/// 修改内存、Java、JVM 参数与启动方式
+#[tauri::command]
+pub async fn update_server_settings(
+    id: String,
+    settings: ServerSettingsRequest,
+) -> Result<ServerUpdateResult, String> {
+    blocking(move || manager().update_server_settings(&id, settings)).await
+}
+
+/// 部分更新服务器，运行中时除名称外的修改在下次启动前应用
+#[tauri::command]
+pub async fn update_server(id: String, patch: ServerPatch) -> Result<ServerUpdateResult, String> {
+    blocking(move || manager().update_server(&id, patch)).await
+}
+
+/// 服务器异常退出后的诊断结果
+#[tauri::command]
+pub fn get_crash_diagnosis(id: String) -> Option<CrashDiagnosis> {
+    manager().get_crash_diagnosis(&id)
+}
+
+/// 分析最近一次运行的崩溃报告与控制台输出
+#[tauri::command]
+pub async fn analyze_crash(id: String) -> Result<CrashDiagnosis, String> {
+    blocking(move || manager().analyze_crash(&id)).await
+}
+
+/// 用户确认同意 Minecraft EULA
+#[tauri::command]
+pub fn accept_eula(id: String) -> Result<(), String> {
+    manager().accept_eula(&id)
+}
+
+/// 启动前检查，返回每一项的结果
+#[tauri::command]
+pub async fn preflight_check(id: String) -> Result<PreflightReport, String> {
+    blocking(move || manager().preflight(&id)).await
+}
+
+/// 建议一个未被占用的端口，默认从设置中的默认端口开始
+#[tauri::command]
+pub fn suggest_port(preferred: Option<u16>) -> Result<u16, String> {
+    let start = preferred.unwrap_or_else(|| global::settings_manager().get().default_port);
+    port_manager::next_free_port(start, &manager().get_server_list())
+        .ok_or_else(|| "没有可用的端口".to_string())
+}
+
+#[tauri::command]
+pub fn update_server_port(id: String, port: u16) -> Result<ServerUpdateResult, String> {
+    manager().update_server_port(&id, port)
+}
+
 #[tauri::command]
 pub fn update_server_name(id: String, name: String) -> Result<(), String> {
     manager().update_server_name(&id, &name)
 }
+
+#[tauri::command]
+pub async fn export_server_mrpack(
+    id: String,
+    target_path: String,
+    version_id: Option<String>,
+) -> Result<ModpackExportResult, String> {
+    let server = find_server(&id)?;
+    let target = path_guard::export_target(&id, &target_path, "mrpack")?;
+    let version_id = version_id.unwrap_or_else(|| "1.0.0".to_string());
+    modpack_export::export_mrpack(&server, &target, &version_id).await
+}
+
+#[tauri::command]
+pub async fn export_server_archive(id: String, target_path: String) -> Result<String, String> {
+    let server = find_server(&id)?;
+    if manager().get_server_status(&id).status.is_active() {
+        return Err("请先停止服务器再导出完整归档".to_string());
+    }
+    let target = path_guard::export_target(&id, &target_path, "zip")?;
+    tauri::async_runtime::spawn_blocking(move || modpack_export::export_archive(&server, &target))
+        .await
+        .map_err(|e| format!("导出任务失败: {}", e))?
+}
+
+/// 在阻塞线程中执行网络探测、文件读写、等待控制台响应等耗时操作，避免卡住界面
+pub(crate) async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("后台任务失败: {}", e))?
+}
+
+fn find_server(id: &str) -> Result<ServerInstance, String> {
+    manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| "未找到服务器".to_string())
+}
@@ -1,5 +1,8 @@
+use crate::models::server::ServerPingStatus;
 use crate::services::global::server_id_manager;
 use crate::services::server_id_manager::{CreateServerIdRequest, ServerIdEntry, ServerIdResponse};
+use crate::services::server_ping;
+use std::time::Duration;
 
 /// Create a new server ID
 #[tauri::command]
@@ -80,3 +83,14 @@ pub async fn delete_server_id(id: String) -> Result<(), String> {
 pub async fn search_server_ids(query: String) -> Result<Vec<ServerIdEntry>, String> {
     Ok(server_id_manager().search_ids(&query).await)
 }
+
+/// Ping the address stored in a server ID to check that it is reachable
+#[tauri::command]
+pub async fn check_server_id(id: String) -> Result<ServerPingStatus, String> {
+    let entry = server_id_manager().get_id(&id).await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        server_ping::ping(&entry.address, entry.port, Duration::from_secs(5))
+    })
+    .await
+    .map_err(|e| format!("Ping task failed: {}", e))?
+}
@@ -1,3 +1,4 @@
+use crate::services::path_guard;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 use sysinfo::{Disks, Networks, System};
@@ -262,14 +263,23 @@ pub async fn pick_image_file(app: tauri::AppHandle) -> Result<Option<String>, St
     rx.recv().map_err(|e| format!("Dialog error: {}", e))
 }
 
+/// 打开服务器目录或其中的子目录；path 为相对服务器根目录的路径
 #[tauri::command]
-pub fn open_folder(path: String) -> Result<(), String> {
+pub fn open_folder(server_id: String, path: Option<String>) -> Result<(), String> {
     use std::process::Command;
 
+    let path = match path.as_deref() {
+        Some(relative) if !relative.is_empty() => path_guard::resolve(&server_id, relative)?,
+        _ => path_guard::server_root(&server_id)?,
+    };
+    if !path.is_dir() {
+        return Err(format!("Folder does not exist: {}", path.display()));
+    }
+
     #[cfg(target_os = "windows")]
     {
         let status = Command::new("explorer")
-            .arg(path)
+            .arg(path_guard::display_path(&path))
             .status()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
         if status.success() {
@@ -282,7 +292,7 @@ pub fn open_folder(path: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         let status = Command::new("open")
-            .arg(path)
+            .arg(path_guard::display_path(&path))
             .status()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
         if status.success() {
@@ -295,7 +305,7 @@ pub fn open_folder(path: String) -> Result<(), String> {
     #[cfg(target_os = "linux")]
     {
         let status = Command::new("xdg-open")
-            .arg(path)
+            .arg(path_guard::display_path(&path))
             .status()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
         if status.success() {
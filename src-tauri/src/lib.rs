@@ -70,10 +70,16 @@ pub fn run() {
             server_commands::create_server,
             server_commands::import_server,
             server_commands::import_modpack,
+            server_commands::attach_server,
+            server_commands::update_attach_config,
             server_commands::start_server,
             server_commands::stop_server,
             server_commands::force_stop_all_servers,
             server_commands::send_command,
+            server_commands::send_rcon_command,
+            server_commands::ping_server,
+            server_commands::query_server,
+            server_commands::ping_address,
             server_commands::get_server_list,
             server_commands::get_server_status,
             server_commands::delete_server,
@@ -82,6 +88,18 @@ pub fn run() {
             server_commands::update_server_command,
             server_commands::delete_server_command,
             server_commands::update_server_name,
+            server_commands::suggest_port,
+            server_commands::preflight_check,
+            server_commands::accept_eula,
+            server_commands::get_crash_diagnosis,
+            server_commands::analyze_crash,
+            server_commands::list_jvm_presets,
+            server_commands::preview_jvm_preset,
+            server_commands::update_server_settings,
+            server_commands::update_server,
+            server_commands::update_server_port,
+            server_commands::export_server_mrpack,
+            server_commands::export_server_archive,
             java_commands::detect_java,
             java_commands::validate_java_path,
             java_commands::install_java,
@@ -90,6 +108,15 @@ pub fn run() {
             config_commands::write_config,
             config_commands::read_server_properties,
             config_commands::write_server_properties,
+            config_commands::list_config_files,
+            config_commands::read_config_file,
+            config_commands::get_config_value,
+            config_commands::set_config_value,
+            config_commands::list_config_history,
+            config_commands::diff_config_history,
+            config_commands::restore_config_history,
+            config_commands::get_rcon_status,
+            config_commands::generate_rcon_password,
             system_commands::get_system_info,
             system_commands::pick_jar_file,
             system_commands::pick_startup_file,
@@ -100,14 +127,18 @@ pub fn run() {
             player_commands::get_whitelist,
             player_commands::get_banned_players,
             player_commands::get_ops,
+            player_commands::resolve_player_uuid,
             player_commands::add_to_whitelist,
             player_commands::remove_from_whitelist,
             player_commands::ban_player,
             player_commands::unban_player,
+            player_commands::ban_ip,
+            player_commands::unban_ip,
+            player_commands::get_banned_ips,
+            player_commands::get_ban_history,
             player_commands::add_op,
             player_commands::remove_op,
             player_commands::kick_player,
-            player_commands::export_logs,
             settings_commands::get_settings,
             settings_commands::save_settings,
             settings_commands::reset_settings,
@@ -125,7 +156,20 @@ pub fn run() {
             update_commands::restart_and_install,
             update_commands::download_update_from_debug_url,
             mods_commands::search_mods,
+            mods_commands::get_server_content_kind,
+            mods_commands::search_plugins,
+            mods_commands::install_plugin,
+            mods_commands::plan_mod_install,
             mods_commands::install_mod,
+            mods_commands::check_mod_updates,
+            mods_commands::update_mods,
+            mods_commands::scan_client_only_mods,
+            mods_commands::list_installed_mods,
+            mods_commands::set_mod_enabled,
+            mods_commands::list_mod_profiles,
+            mods_commands::save_mod_profile,
+            mods_commands::delete_mod_profile,
+            mods_commands::apply_mod_profile,
             join_commands::resolve_join_server_id,
             join_commands::join_server_by_id,
             server_id_commands::create_server_id,
@@ -136,6 +180,7 @@ pub fn run() {
             server_id_commands::deactivate_server_id,
             server_id_commands::delete_server_id,
             server_id_commands::search_server_ids,
+            server_id_commands::check_server_id,
         ])
         .on_window_event(|_window, event| {
             if let tauri::WindowEvent::CloseRequested { api: _, .. } = event {
@@ -148,7 +193,12 @@ pub fn run() {
                 // 不阻止默认关闭，让前端的确认对话框处理
             }
         })
-        .setup(|_app| Ok(()))
+        .setup(|app| {
+            services::global::set_app_handle(app.handle().clone());
+            services::ban_manager::spawn_expiry_task();
+            services::attached_server::spawn_log_tails();
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running Sea Lantern");
 }
@@ -9,6 +9,13 @@ pub struct ConfigEntry {
     pub value_type: String,
     pub default_value: String,
     pub category: String,
+    /// select 类型的可选值
+    pub options: Vec<String>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    /// 引入该配置项的 Minecraft 版本，未知配置项为 None
+    pub since: Option<String>,
+    pub removed: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,3 +23,54 @@ pub struct ServerProperties {
     pub entries: Vec<ConfigEntry>,
     pub raw: HashMap<String, String>,
 }
+
+/// 服务器目录中可编辑的配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigFileInfo {
+    /// 相对服务器目录的路径，使用 / 分隔
+    pub path: String,
+    /// yaml、toml 或 json5
+    pub format: String,
+}
+
+/// 配置树中的一个叶子节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigNode {
+    pub path: Vec<String>,
+    pub value: serde_json::Value,
+    /// 键上方的注释
+    pub comment: Option<String>,
+}
+
+/// 配置历史中的一次写入记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub id: String,
+    /// 相对服务器目录的路径，使用 / 分隔
+    pub file: String,
+    /// RFC 3339 格式的写入时间
+    pub timestamp: String,
+    pub changes: Vec<ConfigKeyChange>,
+    /// 写入前文件不存在
+    #[serde(default)]
+    pub created: bool,
+    /// 由恢复操作产生时，记录被恢复的条目 ID
+    #[serde(default)]
+    pub restored_from: Option<String>,
+}
+
+/// 单个配置项的变化，None 表示该项不存在
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigKeyChange {
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// server.properties 中的 RCON 设置，不返回密码本身
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RconStatus {
+    pub enabled: bool,
+    pub port: u16,
+    pub password_set: bool,
+}
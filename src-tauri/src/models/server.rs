@@ -20,6 +20,28 @@ pub struct ServerCommand {
     pub command: String,
 }
 
+/// 模组配置档：一组启用的 jar 文件名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModProfile {
+    pub id: String,
+    pub name: String,
+    pub enabled_jars: Vec<String>,
+}
+
+/// 附加模式：服务器由 systemd、screen 或其他面板在外部启动，Sea Lantern 只负责监控与控制
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttachConfig {
+    /// pid 文件，相对路径基于服务器目录；未设置时按工作目录查找 Java 进程
+    #[serde(default)]
+    pub pid_file: Option<String>,
+    /// 启动命令，在服务器目录中通过系统 shell 执行
+    #[serde(default)]
+    pub start_command: Option<String>,
+    /// 停止命令；未设置时通过 RCON 发送 stop
+    #[serde(default)]
+    pub stop_command: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInstance {
     pub id: String,
@@ -39,6 +61,174 @@ pub struct ServerInstance {
     pub created_at: u64,
     pub last_started_at: Option<u64>,
     pub commands: Vec<ServerCommand>,
+    #[serde(default)]
+    pub mod_profiles: Vec<ModProfile>,
+    #[serde(default)]
+    pub active_mod_profile: Option<String>,
+    /// 设置后为附加模式，进程不由 Sea Lantern 启动
+    #[serde(default)]
+    pub attach: Option<AttachConfig>,
+    /// 用户同意 Minecraft EULA 的记录
+    #[serde(default)]
+    pub eula: Option<EulaAcceptance>,
+    /// 启动时附加的 JVM 参数预设
+    #[serde(default)]
+    pub jvm_preset: Option<String>,
+    /// 运行期间提交、下次启动前应用的修改
+    #[serde(default)]
+    pub pending_changes: Option<ServerPatch>,
+}
+
+#[cfg(test)]
+impl ServerInstance {
+    /// 测试用的原版服务器，其余字段用结构体更新语法覆盖
+    pub fn for_test(id: &str, path: &std::path::Path) -> Self {
+        ServerInstance {
+            id: id.into(),
+            name: id.into(),
+            core_type: "vanilla".into(),
+            core_version: String::new(),
+            mc_version: "1.21.1".into(),
+            path: path.to_string_lossy().to_string(),
+            jar_path: String::new(),
+            startup_mode: default_startup_mode(),
+            java_path: String::new(),
+            max_memory: 2048,
+            min_memory: 512,
+            jvm_args: Vec::new(),
+            port: 25565,
+            created_at: 0,
+            last_started_at: None,
+            commands: Vec::new(),
+            mod_profiles: Vec::new(),
+            active_mod_profile: None,
+            attach: None,
+            eula: None,
+            jvm_preset: None,
+            pending_changes: None,
+        }
+    }
+}
+
+/// ServerInstance 的部分更新，为 None 的字段保持不变。
+/// id、目录与时间等由 Sea Lantern 维护，模组配置、快捷命令与附加配置有各自的接口
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerPatch {
+    pub name: Option<String>,
+    pub core_type: Option<String>,
+    pub core_version: Option<String>,
+    pub mc_version: Option<String>,
+    pub jar_path: Option<String>,
+    pub startup_mode: Option<String>,
+    pub java_path: Option<String>,
+    pub max_memory: Option<u32>,
+    pub min_memory: Option<u32>,
+    pub jvm_args: Option<Vec<String>>,
+    /// 空字符串表示取消预设
+    pub jvm_preset: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl ServerPatch {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.core_type.is_none()
+            && self.core_version.is_none()
+            && self.mc_version.is_none()
+            && self.jar_path.is_none()
+            && self.startup_mode.is_none()
+            && self.java_path.is_none()
+            && self.max_memory.is_none()
+            && self.min_memory.is_none()
+            && self.jvm_args.is_none()
+            && self.jvm_preset.is_none()
+            && self.port.is_none()
+    }
+
+    /// 合并两次修改，later 中设置的字段优先
+    pub fn merge(self, later: ServerPatch) -> ServerPatch {
+        ServerPatch {
+            name: later.name.or(self.name),
+            core_type: later.core_type.or(self.core_type),
+            core_version: later.core_version.or(self.core_version),
+            mc_version: later.mc_version.or(self.mc_version),
+            jar_path: later.jar_path.or(self.jar_path),
+            startup_mode: later.startup_mode.or(self.startup_mode),
+            java_path: later.java_path.or(self.java_path),
+            max_memory: later.max_memory.or(self.max_memory),
+            min_memory: later.min_memory.or(self.min_memory),
+            jvm_args: later.jvm_args.or(self.jvm_args),
+            jvm_preset: later.jvm_preset.or(self.jvm_preset),
+            port: later.port.or(self.port),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerUpdateResult {
+    pub server: ServerInstance,
+    /// 服务器正在运行，部分修改已暂存到 pending_changes
+    pub deferred: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JvmPresetInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+/// 服务器创建后可修改的启动设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerSettingsRequest {
+    pub max_memory: u32,
+    pub min_memory: u32,
+    pub java_path: String,
+    pub jvm_args: Vec<String>,
+    pub jvm_preset: Option<String>,
+    pub startup_mode: String,
+    /// 切换启动方式时对应的启动文件，未指定时保持不变
+    #[serde(default)]
+    pub jar_path: Option<String>,
+}
+
+impl From<ServerSettingsRequest> for ServerPatch {
+    fn from(req: ServerSettingsRequest) -> Self {
+        ServerPatch {
+            max_memory: Some(req.max_memory),
+            min_memory: Some(req.min_memory),
+            java_path: Some(req.java_path),
+            jvm_args: Some(req.jvm_args),
+            jvm_preset: Some(req.jvm_preset.unwrap_or_default()),
+            startup_mode: Some(req.startup_mode),
+            jar_path: req.jar_path,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EulaAcceptance {
+    pub accepted_at: u64,
+    /// 同意时的系统用户名
+    pub accepted_by: String,
+    /// 由“自动同意 EULA”设置代为同意
+    pub automatic: bool,
+}
+
+/// server-eula-required 事件：服务器因未同意 EULA 而退出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EulaRequiredEvent {
+    pub server_id: String,
+    pub server_name: String,
+}
+
+impl ServerStatus {
+    /// 进程正在运行、启动或停止中
+    pub fn is_active(&self) -> bool {
+        !matches!(self, ServerStatus::Stopped | ServerStatus::Error)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +239,111 @@ pub struct ServerStatusInfo {
     pub uptime: Option<u64>,
 }
 
+/// 发送命令后从控制台收集到的响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandResponse {
+    /// 匹配到成功输出为 true，失败输出为 false，超时仍未匹配为 None
+    pub accepted: Option<bool>,
+    /// 发送命令后收到的控制台输出
+    pub lines: Vec<String>,
+}
+
+/// Server List Ping 返回的服务器状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerPingStatus {
+    pub version: String,
+    pub protocol: i32,
+    pub online: u32,
+    pub max: u32,
+    pub sample: Vec<PingPlayer>,
+    /// 原始 MOTD，可能是字符串或聊天组件
+    pub description: serde_json::Value,
+    /// 去掉格式代码的 MOTD 纯文本
+    pub motd: String,
+    /// data:image/png;base64 格式的服务器图标
+    pub favicon: Option<String>,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingPlayer {
+    pub name: String,
+    pub id: String,
+}
+
+/// GameSpy4 Query 返回的完整状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStatus {
+    pub motd: String,
+    pub game_type: String,
+    pub version: String,
+    /// 服务端名称，如 "Paper on Bukkit 1.21.1"；原版为空
+    pub server_mod: String,
+    pub plugins: Vec<String>,
+    pub map: String,
+    pub online: u32,
+    pub max: u32,
+    pub host_ip: String,
+    pub host_port: u16,
+    pub players: Vec<String>,
+}
+
+/// 崩溃分析命中的一条规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashFinding {
+    pub rule: String,
+    pub title: String,
+    pub detail: String,
+    pub suggestion: String,
+    /// 匹配到的来源：崩溃报告或 JVM 错误日志的文件名，或“控制台”
+    pub source: String,
+}
+
+/// 服务器异常退出后的诊断结果；存在时服务器状态为 Error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashDiagnosis {
+    pub server_id: String,
+    pub detected_at: u64,
+    pub exit_code: Option<i32>,
+    pub findings: Vec<CrashFinding>,
+    pub crash_report: Option<String>,
+    pub jvm_error_log: Option<String>,
+    pub log_tail: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckLevel {
+    Pass,
+    /// 可以启动，但可能出现问题
+    Warning,
+    /// 阻止启动
+    Error,
+}
+
+/// 单项启动前检查的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightCheck {
+    pub id: String,
+    pub name: String,
+    pub level: CheckLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    pub fn has_errors(&self) -> bool {
+        self.checks.iter().any(|c| c.level == CheckLevel::Error)
+    }
+
+    pub fn with_level(&self, level: CheckLevel) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(move |c| c.level == level)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateServerRequest {
     pub name: String,
@@ -85,3 +380,13 @@ pub struct ImportModpackRequest {
     pub min_memory: u32,
     pub port: u16,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachServerRequest {
+    pub name: String,
+    /// 外部服务器的目录
+    pub path: String,
+    /// 未指定时读取 server.properties 中的 server-port
+    pub port: Option<u16>,
+    pub attach: AttachConfig,
+}
@@ -155,6 +155,16 @@ pub struct AppSettings {
     // 关闭行为: "ask", "minimize", "close"，默认 "ask"
     #[serde(default = "default_close_action")]
     pub close_action: String,
+
+    // CurseForge API，可替换为兼容的镜像地址（镜像通常不需要 API Key）
+    #[serde(default = "default_curseforge_api_url")]
+    pub curseforge_api_url: String,
+    #[serde(default)]
+    pub curseforge_api_key: String,
+
+    // 正版玩家档案接口（Mojang 兼容），GET <url>/<name> 返回 {"id", "name"}
+    #[serde(default = "default_profile_api_url")]
+    pub profile_api_url: String,
 }
 
 fn default_true() -> bool {
@@ -221,6 +231,14 @@ fn default_close_action() -> String {
     "ask".to_string()
 }
 
+fn default_curseforge_api_url() -> String {
+    "https://api.curseforge.com/v1".to_string()
+}
+
+fn default_profile_api_url() -> String {
+    "https://api.mojang.com/users/profiles/minecraft".to_string()
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         AppSettings {
@@ -288,6 +306,9 @@ impl Default for AppSettings {
             language: "zh-CN".to_string(),
             developer_mode: false,
             close_action: "ask".to_string(),
+            curseforge_api_url: default_curseforge_api_url(),
+            curseforge_api_key: String::new(),
+            profile_api_url: default_profile_api_url(),
         }
     }
 }
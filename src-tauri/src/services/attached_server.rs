@@ -0,0 +1,391 @@
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::{global, rcon, server_ping};
+use crate::models::server::{AttachConfig, ServerInstance, ServerStatus, ServerStatusInfo};
+
+/// 未找到进程时，两次完整扫描进程列表的最小间隔
+const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+const PING_TIMEOUT: Duration = Duration::from_millis(800);
+/// 执行停止命令后等待服务器退出的时间
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 日志跟踪线程两次探测状态的间隔
+const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+/// 开始跟踪时从已有日志末尾载入的字节数
+const LOG_BACKLOG_BYTES: u64 = 64 * 1024;
+/// 用于识别日志轮转的文件头长度
+const LOG_HEAD_BYTES: usize = 64;
+
+/// 上次找到的进程，以及未找到时的扫描时间
+type CachedProcess = (Option<u32>, Instant);
+
+static SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
+static PROCESS_CACHE: Lazy<Mutex<HashMap<String, CachedProcess>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+/// 日志跟踪线程最近一次探测到的状态
+static STATUS_CACHE: Lazy<Mutex<HashMap<String, ServerStatusInfo>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+/// 正在跟踪日志的服务器
+static TAILING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 查找外部服务器的 Java 进程：优先使用 pid 文件，否则按工作目录匹配
+pub fn find_process(server: &ServerInstance, attach: &AttachConfig) -> Option<u32> {
+    let mut sys = SYSTEM.lock().unwrap();
+    if let Some(pid_file) = attach.pid_file.as_deref().filter(|p| !p.trim().is_empty()) {
+        let pid = fs::read_to_string(Path::new(&server.path).join(pid_file.trim()))
+            .ok()?
+            .trim()
+            .parse::<u32>()
+            .ok()?;
+        return refresh_one(&mut sys, pid).map(|_| pid);
+    }
+
+    let dir = fs::canonicalize(&server.path).ok()?;
+    let mut cache = PROCESS_CACHE.lock().unwrap();
+    match cache.get(&server.id) {
+        // pid 可能已被其他进程复用，重新确认工作目录
+        Some((Some(pid), _))
+            if refresh_one(&mut sys, *pid).is_some_and(|p| is_server_process(p, &dir)) =>
+        {
+            return Some(*pid);
+        }
+        Some((None, scanned_at)) if scanned_at.elapsed() < SCAN_INTERVAL => return None,
+        _ => {}
+    }
+
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind());
+    let found = sys
+        .processes()
+        .iter()
+        .filter(|(_, p)| is_server_process(p, &dir))
+        .map(|(pid, _)| pid.as_u32())
+        .min();
+    cache.insert(server.id.clone(), (found, Instant::now()));
+    found
+}
+
+/// 返回日志跟踪线程定期探测的状态，不等待 Ping；尚未探测过时只根据进程判断
+pub fn status(server: &ServerInstance, attach: &AttachConfig) -> ServerStatusInfo {
+    ensure_log_tail(&server.id);
+    if let Some(info) = STATUS_CACHE.lock().unwrap().get(&server.id) {
+        return info.clone();
+    }
+    let pid = find_process(server, attach);
+    ServerStatusInfo {
+        id: server.id.clone(),
+        status: if pid.is_some() {
+            ServerStatus::Starting
+        } else {
+            ServerStatus::Stopped
+        },
+        pid,
+        uptime: None,
+    }
+}
+
+/// 根据进程与 Server List Ping 判断状态并更新缓存：进程存在但尚未响应 Ping 视为启动中；
+/// 找不到进程（如运行在其他用户或容器中）但能 Ping 通时视为运行中
+pub fn probe(server: &ServerInstance, attach: &AttachConfig) -> ServerStatusInfo {
+    let pid = find_process(server, attach);
+    let reachable = server_ping::ping("127.0.0.1", server.port, PING_TIMEOUT).is_ok();
+    let uptime = pid.and_then(|pid| {
+        let sys = SYSTEM.lock().unwrap();
+        let started = sys.process(Pid::from_u32(pid))?.start_time();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(now.saturating_sub(started))
+    });
+    let info = ServerStatusInfo {
+        id: server.id.clone(),
+        status: match (pid, reachable) {
+            (_, true) => ServerStatus::Running,
+            (Some(_), false) => ServerStatus::Starting,
+            (None, false) => ServerStatus::Stopped,
+        },
+        pid,
+        uptime,
+    };
+    STATUS_CACHE
+        .lock()
+        .unwrap()
+        .insert(server.id.clone(), info.clone());
+    info
+}
+
+/// 执行用户定义的启动命令
+pub fn start(server: &ServerInstance, attach: &AttachConfig) -> Result<(), String> {
+    if !matches!(probe(server, attach).status, ServerStatus::Stopped) {
+        return Err("服务器已在运行中".to_string());
+    }
+    let command = non_empty(&attach.start_command).ok_or_else(|| "未配置启动命令".to_string())?;
+    ensure_log_tail(&server.id);
+    PROCESS_CACHE.lock().unwrap().remove(&server.id);
+    run_shell(server, command)
+}
+
+/// 执行用户定义的停止命令，未配置时通过 RCON 发送 stop，然后等待服务器退出
+pub fn stop(server: &ServerInstance, attach: &AttachConfig) -> Result<(), String> {
+    let manager = global::server_manager();
+    if matches!(probe(server, attach).status, ServerStatus::Stopped) {
+        manager.append_log(&server.id, "[Sea Lantern] 服务器未运行");
+        return Ok(());
+    }
+    match non_empty(&attach.stop_command) {
+        Some(command) => run_shell(server, command)?,
+        None => {
+            manager.append_log(&server.id, "[Sea Lantern] 正在通过 RCON 发送停止命令...");
+            rcon::exec(&server.path, "stop")
+                .map_err(|e| format!("未配置停止命令，且无法通过 RCON 停止: {}", e))?;
+        }
+    }
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while Instant::now() < deadline {
+        std::thread::sleep(Duration::from_secs(1));
+        if matches!(probe(server, attach).status, ServerStatus::Stopped) {
+            manager.append_log(&server.id, "[Sea Lantern] 服务器已停止");
+            return Ok(());
+        }
+    }
+    Err("等待服务器停止超时".to_string())
+}
+
+/// 通过 RCON 发送命令，并把响应写入控制台日志
+pub fn send_command(server: &ServerInstance, command: &str) -> Result<(), String> {
+    let output = rcon::exec(&server.path, command)
+        .map_err(|e| format!("附加模式需要启用 RCON 才能发送命令: {}", e))?;
+    let manager = global::server_manager();
+    manager.append_log(&server.id, &format!("> {}", command));
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        manager.append_log(&server.id, line);
+    }
+    Ok(())
+}
+
+/// 为所有附加模式的服务器开始跟踪日志，应用启动时调用
+pub fn spawn_log_tails() {
+    for server in global::server_manager().get_server_list() {
+        if server.attach.is_some() {
+            ensure_log_tail(&server.id);
+        }
+    }
+}
+
+/// 跟踪 logs/latest.log 并定期探测状态；服务器被删除或不再是附加模式时线程退出
+pub fn ensure_log_tail(server_id: &str) {
+    if !TAILING.lock().unwrap().insert(server_id.to_string()) {
+        return;
+    }
+    let id = server_id.to_string();
+    std::thread::spawn(move || {
+        tail_log(&id);
+        TAILING.lock().unwrap().remove(&id);
+    });
+}
+
+fn tail_log(server_id: &str) {
+    let manager = global::server_manager();
+    let mut position: Option<u64> = None;
+    let mut head = Vec::new();
+    let mut next_probe = Instant::now();
+    loop {
+        let Some(server) = manager
+            .get_server_list()
+            .into_iter()
+            .find(|s| s.id == server_id && s.attach.is_some())
+        else {
+            STATUS_CACHE.lock().unwrap().remove(server_id);
+            return;
+        };
+        if Instant::now() >= next_probe {
+            if let Some(attach) = &server.attach {
+                probe(&server, attach);
+            }
+            next_probe = Instant::now() + STATUS_INTERVAL;
+        }
+        let max_lines = global::settings_manager().get().max_log_lines as usize;
+        let log_path = Path::new(&server.path).join("logs").join("latest.log");
+        if let Some(lines) = read_new_lines(&log_path, &mut position, &mut head) {
+            for line in lines {
+                manager.push_console_line(server_id, &line, max_lines);
+            }
+        }
+        std::thread::sleep(LOG_POLL_INTERVAL);
+    }
+}
+
+/// 读取上次位置之后的完整行；文件变短或文件头变化说明日志已轮转，从头读取
+fn read_new_lines(
+    path: &Path,
+    position: &mut Option<u64>,
+    head: &mut Vec<u8>,
+) -> Option<Vec<String>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut current_head = vec![0u8; LOG_HEAD_BYTES.min(len as usize)];
+    file.read_exact(&mut current_head).ok()?;
+
+    let start = match *position {
+        Some(pos) if pos <= len && current_head.starts_with(head) => pos,
+        Some(_) => 0,
+        None => len.saturating_sub(LOG_BACKLOG_BYTES),
+    };
+    *head = current_head;
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).ok()?;
+
+    // 只消费完整的行，未写完的行留到下次
+    let mut begin = 0;
+    if position.is_none() && start > 0 {
+        begin = buffer
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(buffer.len(), |i| i + 1);
+    }
+    let end = buffer
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(begin, |i| (i + 1).max(begin));
+    *position = Some(start + end as u64);
+    Some(
+        String::from_utf8_lossy(&buffer[begin..end])
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// 工作目录为 dir 的所有 Java 进程
+pub fn java_processes_in(dir: &Path) -> Vec<u32> {
+    let Ok(dir) = fs::canonicalize(dir) else {
+        return Vec::new();
+    };
+    let mut sys = SYSTEM.lock().unwrap();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind());
+    sys.processes()
+        .iter()
+        .filter(|(_, p)| is_server_process(p, &dir))
+        .map(|(pid, _)| pid.as_u32())
+        .collect()
+}
+
+fn refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::new()
+        .with_cwd(UpdateKind::OnlyIfNotSet)
+        .with_exe(UpdateKind::OnlyIfNotSet)
+}
+
+fn refresh_one(sys: &mut System, pid: u32) -> Option<&Process> {
+    let pid = Pid::from_u32(pid);
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh_kind());
+    sys.process(pid)
+}
+
+fn is_server_process(process: &Process, dir: &Path) -> bool {
+    let is_java = process
+        .name()
+        .to_string_lossy()
+        .to_ascii_lowercase()
+        .starts_with("java");
+    is_java
+        && process
+            .cwd()
+            .is_some_and(|cwd| cwd == dir || fs::canonicalize(cwd).is_ok_and(|c| c == dir))
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 在服务器目录中通过系统 shell 执行命令，输出写入控制台日志
+fn run_shell(server: &ServerInstance, command: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/d").arg("/c").raw_arg(command);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    cmd.current_dir(&server.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let manager = global::server_manager();
+    manager.append_log(&server.id, &format!("[Sea Lantern] 执行: {}", command));
+    let child = cmd.spawn().map_err(|e| format!("执行命令失败: {}", e))?;
+
+    // 命令可能长时间运行（如直接在前台启动服务端），在后台等待并收集输出
+    let id = server.id.clone();
+    std::thread::spawn(move || {
+        let manager = global::server_manager();
+        match child.wait_with_output() {
+            Ok(output) => {
+                for bytes in [&output.stdout, &output.stderr] {
+                    for line in String::from_utf8_lossy(bytes).lines() {
+                        if !line.trim().is_empty() {
+                            manager.append_log(&id, line);
+                        }
+                    }
+                }
+                if !output.status.success() {
+                    manager.append_log(
+                        &id,
+                        &format!("[Sea Lantern] 命令退出，状态: {}", output.status),
+                    );
+                }
+            }
+            Err(e) => manager.append_log(&id, &format!("[Sea Lantern] 命令执行失败: {}", e)),
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tails_new_lines_across_rotation() {
+        let dir = std::env::temp_dir().join(format!("sl-attach-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("latest.log");
+        fs::write(&log, "[10:00:00] first\n[10:00:01] second\n[10:00:02] part").unwrap();
+
+        let (mut position, mut head) = (None, Vec::new());
+        let lines = read_new_lines(&log, &mut position, &mut head).unwrap();
+        assert_eq!(lines, vec!["[10:00:00] first", "[10:00:01] second"]);
+
+        fs::write(&log, "[10:00:00] first\n[10:00:01] second\n[10:00:02] partial\n").unwrap();
+        let lines = read_new_lines(&log, &mut position, &mut head).unwrap();
+        assert_eq!(lines, vec!["[10:00:02] partial"]);
+        assert!(read_new_lines(&log, &mut position, &mut head)
+            .unwrap()
+            .is_empty());
+
+        // 服务器重启后生成新的 latest.log，即使更长也应从头读取
+        fs::write(&log, "[11:00:00] restarted server with a longer log line\n[11:00:01] x\n")
+            .unwrap();
+        let lines = read_new_lines(&log, &mut position, &mut head).unwrap();
+        assert_eq!(lines.len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,415 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::player_manager::{self, IpBanEntry};
+use super::{attached_server, console_response, global, path_guard, rcon};
+use crate::commands::server::blocking;
+use crate::models::server::ServerStatus;
+
+pub const KIND_PLAYER: &str = "player";
+pub const KIND_IP: &str = "ip";
+/// 后台检查临时封禁到期的间隔
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 保护封禁历史的读取-修改-写入
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+/// 后台任务与启动服务器都会检查到期，同一时间只进行一次，避免同一条封禁被重复解除
+static EXPIRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 封禁历史中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanRecord {
+    pub id: String,
+    /// player 或 ip
+    pub kind: String,
+    /// 玩家名或 IP
+    pub target: String,
+    #[serde(default)]
+    pub uuid: Option<String>,
+    /// ban、pardon 或 expire
+    pub action: String,
+    #[serde(default)]
+    pub reason: String,
+    /// 操作者
+    pub issuer: String,
+    /// 操作来源：ui、cli 或 auto（到期自动解除）
+    pub channel: String,
+    /// RFC 3339 格式
+    pub timestamp: String,
+    /// 临时封禁的到期时间，永久封禁为 None
+    #[serde(default)]
+    pub expires: Option<String>,
+}
+
+/// 发起封禁操作的用户与渠道
+#[derive(Debug, Clone)]
+pub struct BanOrigin {
+    pub issuer: String,
+    pub channel: String,
+}
+
+impl BanOrigin {
+    /// 以当前系统用户作为操作者
+    pub fn local(channel: &str) -> Self {
+        let issuer = std::env::var("USERNAME")
+            .or_else(|_| std::env::var("USER"))
+            .unwrap_or_else(|_| "Sea Lantern".to_string());
+        BanOrigin { issuer, channel: channel.to_string() }
+    }
+}
+
+/// 封禁玩家，duration 为 None 时永久封禁。
+/// 运行中通过控制台或 RCON 封禁（原版没有重新加载封禁列表的命令，服务器保存时会覆盖名单文件），
+/// 控制台封禁是永久的，临时封禁由到期任务解除
+pub async fn ban_player(
+    server_id: &str,
+    name: &str,
+    reason: &str,
+    duration: Option<Duration>,
+    origin: &BanOrigin,
+) -> Result<BanRecord, String> {
+    let expires = expiry_time(duration)?;
+    let (target, uuid) = if is_running(server_id)? {
+        let id = server_id.to_string();
+        let command = command_with_reason("ban", name, reason);
+        blocking(move || console_response::run(&id, &command)).await?;
+        (name.to_string(), None)
+    } else {
+        let path = server_path(server_id)?;
+        let player = player_manager::resolve_player(&path, name).await?;
+        let mut ban = player_manager::new_ban(&player, reason, &origin.issuer);
+        if let Some(expires) = expires {
+            ban.expires = player_manager::ban_time(expires);
+        }
+        player_manager::add_ban(&path, &ban)?;
+        (player.name, Some(player.uuid))
+    };
+
+    let mut record = new_record(KIND_PLAYER, &target, "ban", reason, origin);
+    record.uuid = uuid;
+    record.expires = expires.map(|t| t.to_rfc3339());
+    append_record(server_id, record)
+}
+
+/// 封禁 IP，duration 为 None 时永久封禁
+pub fn ban_ip(
+    server_id: &str,
+    ip: &str,
+    reason: &str,
+    duration: Option<Duration>,
+    origin: &BanOrigin,
+) -> Result<BanRecord, String> {
+    let ip = normalize_ip(ip)?;
+    let expires = expiry_time(duration)?;
+
+    if is_running(server_id)? {
+        console_response::run(server_id, &command_with_reason("ban-ip", &ip, reason))?;
+    } else {
+        let ban = IpBanEntry {
+            ip: ip.clone(),
+            reason: if reason.is_empty() {
+                "Banned by an operator.".to_string()
+            } else {
+                reason.to_string()
+            },
+            source: origin.issuer.clone(),
+            created: player_manager::ban_time(Local::now()),
+            expires: expires
+                .map(player_manager::ban_time)
+                .unwrap_or_else(|| "forever".to_string()),
+        };
+        player_manager::add_ip_ban(&server_path(server_id)?, &ban)?;
+    }
+
+    let mut record = new_record(KIND_IP, &ip, "ban", reason, origin);
+    record.expires = expires.map(|t| t.to_rfc3339());
+    append_record(server_id, record)
+}
+
+/// 解除玩家或 IP 封禁
+pub fn pardon(
+    server_id: &str,
+    kind: &str,
+    target: &str,
+    reason: &str,
+    origin: &BanOrigin,
+) -> Result<BanRecord, String> {
+    let target = if kind == KIND_IP {
+        normalize_ip(target)?
+    } else {
+        target.to_string()
+    };
+    if !lift(server_id, kind, &target)? {
+        return Err(format!("{} 不在封禁列表中", target));
+    }
+    append_record(server_id, new_record(kind, &target, "pardon", reason, origin))
+}
+
+/// 封禁历史，最新的在前
+pub fn history(server_id: &str) -> Vec<BanRecord> {
+    let mut records = load_history(server_id);
+    records.reverse();
+    records
+}
+
+/// 解除所有已到期的临时封禁，返回产生的记录；解除失败的留到下次检查
+pub fn expire_due(server_id: &str) -> Vec<BanRecord> {
+    let _expiry = EXPIRY_LOCK.lock().unwrap();
+    let now = Local::now();
+    let active = {
+        let _guard = HISTORY_LOCK.lock().unwrap();
+        active_temp_bans(&load_history(server_id))
+    };
+    let origin = BanOrigin {
+        issuer: "Sea Lantern".to_string(),
+        channel: "auto".to_string(),
+    };
+    let mut records = Vec::new();
+    for ban in active {
+        let due = ban
+            .expires
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t <= now);
+        if !due {
+            continue;
+        }
+        // 通过控制台或 RCON 解除可能需要数秒，期间不持有 HISTORY_LOCK，封禁与解除命令不受影响。
+        // 封禁可能已在名单文件中被移除（返回 false），仍需记录以关闭这次临时封禁
+        if let Err(e) = lift(server_id, &ban.kind, &ban.target) {
+            println!("[封禁] 解除 {} 失败: {}", ban.target, e);
+            continue;
+        }
+        let _guard = HISTORY_LOCK.lock().unwrap();
+        // 解除期间已被手动解除或重新封禁的目标以新的记录为准
+        if !active_temp_bans(&load_history(server_id))
+            .iter()
+            .any(|b| b.id == ban.id)
+        {
+            continue;
+        }
+        let record = new_record(&ban.kind, &ban.target, "expire", "临时封禁到期", &origin);
+        match write_record(server_id, record) {
+            Ok(record) => records.push(record),
+            Err(e) => println!("[封禁] 记录失败: {}", e),
+        }
+    }
+    records
+}
+
+/// 启动后台任务，定期解除所有服务器已到期的临时封禁
+pub fn spawn_expiry_task() {
+    std::thread::spawn(|| loop {
+        for server in global::server_manager().get_server_list() {
+            for record in expire_due(&server.id) {
+                global::server_manager().append_log(
+                    &server.id,
+                    &format!("[Sea Lantern] 临时封禁已到期，已解除: {}", record.target),
+                );
+            }
+        }
+        std::thread::sleep(EXPIRY_CHECK_INTERVAL);
+    });
+}
+
+/// 解析 30s、10m、2h、7d、1w 形式的时长，纯数字按秒计算
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => text.split_at(index),
+        None => (text, "s"),
+    };
+    let value: u64 = number.parse().ok()?;
+    let seconds = match unit.to_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    (value > 0).then(|| Duration::from_secs(value * seconds))
+}
+
+/// 每个目标最近一次操作仍为带到期时间的封禁
+fn active_temp_bans(records: &[BanRecord]) -> Vec<BanRecord> {
+    let mut latest: HashMap<(String, String), &BanRecord> = HashMap::new();
+    for record in records {
+        latest.insert((record.kind.clone(), record.target.to_lowercase()), record);
+    }
+    latest
+        .into_values()
+        .filter(|r| r.action == "ban" && r.expires.is_some())
+        .cloned()
+        .collect()
+}
+
+/// 运行中通过控制台或 RCON 解除，否则从名单文件中移除；返回是否有变化
+fn lift(server_id: &str, kind: &str, target: &str) -> Result<bool, String> {
+    let command = if kind == KIND_IP {
+        "pardon-ip"
+    } else {
+        "pardon"
+    };
+    if is_running(server_id)? {
+        console_response::run(server_id, &format!("{} {}", command, target))?;
+        return Ok(true);
+    }
+    let path = server_path(server_id)?;
+    if kind == KIND_IP {
+        player_manager::remove_ip_ban(&path, target)
+    } else {
+        player_manager::remove_ban(&path, target)
+    }
+}
+
+fn expiry_time(duration: Option<Duration>) -> Result<Option<DateTime<Local>>, String> {
+    duration
+        .map(|d| {
+            chrono::Duration::from_std(d)
+                .map(|d| Local::now() + d)
+                .map_err(|_| "封禁时长过长".to_string())
+        })
+        .transpose()
+}
+
+fn normalize_ip(ip: &str) -> Result<String, String> {
+    ip.trim()
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| format!("无效的 IP 地址: {}", ip))
+}
+
+fn command_with_reason(command: &str, target: &str, reason: &str) -> String {
+    if reason.is_empty() {
+        format!("{} {}", command, target)
+    } else {
+        format!("{} {} {}", command, target, reason)
+    }
+}
+
+fn new_record(
+    kind: &str,
+    target: &str,
+    action: &str,
+    reason: &str,
+    origin: &BanOrigin,
+) -> BanRecord {
+    BanRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        target: target.to_string(),
+        uuid: None,
+        action: action.to_string(),
+        reason: reason.to_string(),
+        issuer: origin.issuer.clone(),
+        channel: origin.channel.clone(),
+        timestamp: Local::now().to_rfc3339(),
+        expires: None,
+    }
+}
+
+fn server_path(server_id: &str) -> Result<String, String> {
+    Ok(path_guard::server_root(server_id)?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// 服务器是否正在运行，运行中只能通过命令修改封禁列表。
+/// 不由本进程管理的服务器（例如在 CLI 中操作图形界面启动的服务器）按目录下的 Java 进程判断，
+/// 此时只能通过 RCON 发送命令，未启用 RCON 时拒绝操作
+fn is_running(server_id: &str) -> Result<bool, String> {
+    match global::server_manager().get_server_status(server_id).status {
+        ServerStatus::Running => return Ok(true),
+        ServerStatus::Starting | ServerStatus::Stopping => {
+            return Err("服务器正在启动或关闭，请稍后再试".to_string())
+        }
+        ServerStatus::Stopped | ServerStatus::Error => {}
+    }
+    let path = server_path(server_id)?;
+    if attached_server::java_processes_in(Path::new(&path)).is_empty() {
+        return Ok(false);
+    }
+    if rcon::read_config(&path).is_some_and(|config| config.usable()) {
+        Ok(true)
+    } else {
+        Err("服务器正在由其他进程运行，请在运行它的 Sea Lantern 中操作，或启用 RCON 后重试"
+            .to_string())
+    }
+}
+
+fn history_path(server_id: &str) -> PathBuf {
+    let data_dir = global::server_manager().data_dir.lock().unwrap().clone();
+    PathBuf::from(data_dir)
+        .join("ban_history")
+        .join(format!("{}.json", server_id))
+}
+
+fn load_history(server_id: &str) -> Vec<BanRecord> {
+    fs::read_to_string(history_path(server_id))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn append_record(server_id: &str, record: BanRecord) -> Result<BanRecord, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    write_record(server_id, record)
+}
+
+/// 追加一条记录，调用方需持有 HISTORY_LOCK
+fn write_record(server_id: &str, record: BanRecord) -> Result<BanRecord, String> {
+    let mut records = load_history(server_id);
+    records.push(record.clone());
+    let path = history_path(server_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("保存封禁历史失败: {}", e))?;
+    Ok(record)
+}
+
+/// 删除服务器时清理其封禁历史
+pub fn remove_history(server_id: &str) {
+    let _ = fs::remove_file(history_path(server_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Some(Duration::from_secs(600)));
+        assert_eq!(parse_duration("7d"), Some(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration("0h"), None);
+        assert_eq!(parse_duration("3x"), None);
+    }
+
+    #[test]
+    fn latest_action_decides_active_temp_bans() {
+        let origin = BanOrigin {
+            issuer: "admin".into(),
+            channel: "ui".into(),
+        };
+        let mut ban = new_record(KIND_PLAYER, "Steve", "ban", "grief", &origin);
+        ban.expires = Some(Local::now().to_rfc3339());
+        let mut ip_ban = new_record(KIND_IP, "10.0.0.1", "ban", "", &origin);
+        ip_ban.expires = Some(Local::now().to_rfc3339());
+        let pardon = new_record(KIND_PLAYER, "steve", "pardon", "", &origin);
+
+        let active = active_temp_bans(&[ban.clone(), ip_ban.clone()]);
+        assert_eq!(active.len(), 2);
+        let active = active_temp_bans(&[ban, ip_ban, pardon]);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].kind, KIND_IP);
+    }
+}
@@ -0,0 +1,350 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use super::json5_document::Json5Document;
+use super::path_guard;
+use super::yaml_document::YamlDocument;
+use crate::models::config::{ConfigFileInfo, ConfigNode};
+
+/// 服务端核心在服务器根目录或 config/ 下生成的 YAML 配置
+const YAML_FILES: &[&str] = &[
+    "bukkit.yml",
+    "spigot.yml",
+    "paper.yml",
+    "purpur.yml",
+    "config/paper-global.yml",
+    "config/paper-world-defaults.yml",
+];
+/// 模组配置目录的最大搜索深度
+const MAX_CONFIG_DEPTH: usize = 4;
+
+enum ConfigDocument {
+    Yaml(YamlDocument),
+    Toml(DocumentMut),
+    Json5(Json5Document),
+}
+
+/// 列出服务器目录中可编辑的 YAML、TOML 与 JSON5 配置文件
+pub fn discover_config_files(server_dir: &Path) -> Vec<ConfigFileInfo> {
+    let mut files: Vec<ConfigFileInfo> = YAML_FILES
+        .iter()
+        .filter(|f| server_dir.join(f).is_file())
+        .map(|f| ConfigFileInfo {
+            path: f.to_string(),
+            format: "yaml".to_string(),
+        })
+        .collect();
+
+    let mut mod_configs = Vec::new();
+    collect_mod_configs(&server_dir.join("config"), "config", 0, &mut mod_configs);
+    mod_configs.sort_by(|a, b| a.path.cmp(&b.path));
+    files.extend(mod_configs);
+    files
+}
+
+pub fn list_values(server_dir: &Path, file: &str) -> Result<Vec<ConfigNode>, String> {
+    let (_, document) = load(server_dir, file)?;
+    Ok(document_nodes(&document))
+}
+
+/// 将配置文件内容展开为 "a.b.c" -> JSON 文本 的映射，不支持的格式返回 None
+pub fn flatten_content(file: &str, content: &str) -> Option<BTreeMap<String, String>> {
+    let document = parse_document(file, format_of(file)?, content).ok()?;
+    Some(
+        document_nodes(&document)
+            .into_iter()
+            .map(|node| (node.path.join("."), node.value.to_string()))
+            .collect(),
+    )
+}
+
+fn document_nodes(document: &ConfigDocument) -> Vec<ConfigNode> {
+    match document {
+        ConfigDocument::Yaml(doc) => doc.list(),
+        ConfigDocument::Toml(doc) => {
+            let mut nodes = Vec::new();
+            collect_toml(doc.as_table(), &mut Vec::new(), &mut nodes);
+            nodes
+        }
+        ConfigDocument::Json5(doc) => doc.list(),
+    }
+}
+
+pub fn get_value(server_dir: &Path, file: &str, path: &[String]) -> Result<Value, String> {
+    let (_, document) = load(server_dir, file)?;
+    let value = match &document {
+        ConfigDocument::Yaml(doc) => doc.get(path),
+        ConfigDocument::Toml(doc) => path
+            .iter()
+            .try_fold(doc.as_item(), |item, key| item.as_table_like()?.get(key))
+            .map(toml_to_json),
+        ConfigDocument::Json5(doc) => doc.get(path),
+    };
+    value.ok_or_else(|| format!("配置项 {} 不存在", path.join(".")))
+}
+
+pub fn set_value(
+    server_dir: &Path,
+    file: &str,
+    path: &[String],
+    value: &Value,
+) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("配置路径不能为空".to_string());
+    }
+    let (file_path, mut document) = load(server_dir, file)?;
+    let text = match &mut document {
+        ConfigDocument::Yaml(doc) => {
+            doc.set(path, value)?;
+            doc.to_text()
+        }
+        ConfigDocument::Toml(doc) => {
+            set_toml(doc.as_table_mut(), path, value)?;
+            doc.to_string()
+        }
+        ConfigDocument::Json5(doc) => {
+            doc.set(path, value)?;
+            doc.to_text()
+        }
+    };
+    fs::write(&file_path, text).map_err(|e| format!("写入 {} 失败: {}", file, e))
+}
+
+fn collect_mod_configs(dir: &Path, prefix: &str, depth: usize, files: &mut Vec<ConfigFileInfo>) {
+    if depth >= MAX_CONFIG_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = format!("{}/{}", prefix, name);
+        if path.is_dir() {
+            collect_mod_configs(&path, &relative, depth + 1, files);
+        } else if let Some(format) = format_of(&name).filter(|f| *f != "yaml") {
+            files.push(ConfigFileInfo {
+                path: relative,
+                format: format.to_string(),
+            });
+        }
+    }
+}
+
+fn format_of(file_name: &str) -> Option<&'static str> {
+    let lower = file_name.to_lowercase();
+    if lower.ends_with(".yml") || lower.ends_with(".yaml") {
+        Some("yaml")
+    } else if lower.ends_with(".toml") {
+        Some("toml")
+    } else if lower.ends_with(".json5") {
+        Some("json5")
+    } else {
+        None
+    }
+}
+
+/// 解析相对路径并读取文件；拒绝跳出服务器目录的路径
+fn load(server_dir: &Path, file: &str) -> Result<(PathBuf, ConfigDocument), String> {
+    let relative =
+        path_guard::safe_relative_path(file).ok_or_else(|| format!("非法路径: {}", file))?;
+    let format = format_of(file).ok_or_else(|| format!("不支持的配置文件类型: {}", file))?;
+    let file_path = server_dir.join(relative);
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("读取 {} 失败: {}", file, e))?;
+    Ok((file_path, parse_document(file, format, &content)?))
+}
+
+fn parse_document(file: &str, format: &str, content: &str) -> Result<ConfigDocument, String> {
+    Ok(match format {
+        "yaml" => ConfigDocument::Yaml(YamlDocument::parse(content)),
+        "toml" => ConfigDocument::Toml(
+            content
+                .parse::<DocumentMut>()
+                .map_err(|e| format!("解析 {} 失败: {}", file, e))?,
+        ),
+        _ => ConfigDocument::Json5(
+            Json5Document::parse(content).map_err(|e| format!("{}: {}", file, e))?,
+        ),
+    })
+}
+
+fn collect_toml(table: &dyn TableLike, path: &mut Vec<String>, nodes: &mut Vec<ConfigNode>) {
+    for (key, item) in table.iter() {
+        path.push(key.to_string());
+        match item.as_table_like() {
+            Some(child) if !child.is_empty() => collect_toml(child, path, nodes),
+            _ => {
+                // Forge/NeoForge 在键上方写说明注释；子表的注释位于表头之前
+                let decor = match item {
+                    Item::Table(t) => Some(t.decor()),
+                    _ => table.key(key).map(|k| k.leaf_decor()),
+                };
+                nodes.push(ConfigNode {
+                    path: path.clone(),
+                    value: toml_to_json(item),
+                    comment: decor
+                        .and_then(|d| d.prefix())
+                        .and_then(|p| p.as_str())
+                        .and_then(comment_text),
+                });
+            }
+        }
+        path.pop();
+    }
+}
+
+fn comment_text(prefix: &str) -> Option<String> {
+    let lines: Vec<&str> = prefix
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix('#'))
+        .map(|l| l.trim())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn set_toml(table: &mut dyn TableLike, path: &[String], value: &Value) -> Result<(), String> {
+    let (key, rest) = path.split_first().ok_or("配置路径不能为空")?;
+    if !rest.is_empty() {
+        if !table.contains_key(key) {
+            table.insert(key, Item::Table(toml_edit::Table::new()));
+        }
+        let child = table
+            .get_mut(key)
+            .and_then(|item| item.as_table_like_mut())
+            .ok_or_else(|| format!("{} 不是表，无法添加子项", key))?;
+        return set_toml(child, rest, value);
+    }
+
+    match table.get_mut(key) {
+        Some(item) if item.is_value() => {
+            let old = item.as_value().cloned();
+            let mut new_value = json_to_toml(value, old.as_ref())?;
+            if let Some(old) = old {
+                *new_value.decor_mut() = old.decor().clone();
+            }
+            *item = Item::Value(new_value);
+        }
+        Some(_) => return Err(format!("{} 是一个表，不能直接赋值", key)),
+        None => {
+            table.insert(key, Item::Value(json_to_toml(value, None)?));
+        }
+    }
+    Ok(())
+}
+
+/// 转换为 TOML 值；原值为浮点数时整数也按浮点写入，避免模组读取时类型不匹配
+fn json_to_toml(value: &Value, old: Option<&toml_edit::Value>) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        Value::Null => return Err("TOML 不支持空值".to_string()),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) if !old.is_some_and(|o| o.is_float()) => i.into(),
+            _ => n.as_f64().ok_or("无效的数字")?.into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => {
+            let old_item = old
+                .and_then(|o| o.as_array())
+                .and_then(|a| a.get(0))
+                .cloned();
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(item, old_item.as_ref())?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (k, v) in map {
+                table.insert(k, json_to_toml(v, None)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+fn toml_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => table_to_json(table),
+        Item::ArrayOfTables(tables) => {
+            Value::Array(tables.iter().map(|t| table_to_json(t)).collect())
+        }
+    }
+}
+
+fn table_to_json(table: &dyn TableLike) -> Value {
+    let map: Map<String, Value> = table
+        .iter()
+        .map(|(k, item)| (k.to_string(), toml_to_json(item)))
+        .collect();
+    Value::Object(map)
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(f.value().to_string())),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(array) => {
+            Value::Array(array.iter().map(toml_value_to_json).collect())
+        }
+        toml_edit::Value::InlineTable(table) => table_to_json(table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn edits_forge_toml_keeping_comments() {
+        let dir = std::env::temp_dir().join(format!("sl-config-editor-{}", std::process::id()));
+        fs::create_dir_all(dir.join("config/create")).unwrap();
+        fs::write(dir.join("bukkit.yml"), "settings:\n  allow-end: true\n").unwrap();
+        fs::write(
+            dir.join("config/create/create-server.toml"),
+            "\n[kinetics]\n\t#Maximum rotation speed\n\t#Range: > 64\n\tmaxRotationSpeed = 256\n\tspeedFactor = 1.0 # inline\n",
+        )
+        .unwrap();
+        fs::write(dir.join("config/readme.txt"), "ignored").unwrap();
+
+        let files: Vec<String> = discover_config_files(&dir)
+            .into_iter()
+            .map(|f| f.path)
+            .collect();
+        assert_eq!(files, ["bukkit.yml", "config/create/create-server.toml"]);
+
+        let file = "config/create/create-server.toml";
+        let nodes = list_values(&dir, file).unwrap();
+        assert_eq!(nodes[0].path, ["kinetics", "maxRotationSpeed"]);
+        assert_eq!(nodes[0].comment.as_deref(), Some("Maximum rotation speed\nRange: > 64"));
+
+        let speed = vec!["kinetics".to_string(), "maxRotationSpeed".to_string()];
+        set_value(&dir, file, &speed, &json!(512)).unwrap();
+        let factor = vec!["kinetics".to_string(), "speedFactor".to_string()];
+        set_value(&dir, file, &factor, &json!(2)).unwrap();
+        let text = fs::read_to_string(dir.join(file)).unwrap();
+        assert!(text.contains("\t#Range: > 64\n\tmaxRotationSpeed = 512\n"));
+        assert!(text.contains("\tspeedFactor = 2.0 # inline\n"));
+        assert_eq!(get_value(&dir, file, &factor).unwrap(), json!(2.0));
+
+        let allow_end = vec!["settings".to_string(), "allow-end".to_string()];
+        set_value(&dir, "bukkit.yml", &allow_end, &json!(false)).unwrap();
+        assert_eq!(get_value(&dir, "bukkit.yml", &allow_end).unwrap(), json!(false));
+        assert!(get_value(&dir, "../bukkit.yml", &allow_end).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,278 @@
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{config_editor, config_parser::PropertiesDocument, global, path_guard};
+use crate::models::config::{ConfigChange, ConfigKeyChange};
+
+/// 每个服务器保留的历史条目上限
+const MAX_ENTRIES: usize = 200;
+/// 同一文件在该时间内的连续写入合并为一条记录（自动保存会频繁写入）
+const COALESCE_SECS: i64 = 60;
+const JOURNAL_FILE: &str = "journal.json";
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// 串行化对日志文件的读写
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// 一次尚未提交的写入：保存写入前的文件内容，写入完成后调用 commit 记录
+pub struct PendingChange {
+    history_dir: PathBuf,
+    server_dir: PathBuf,
+    file: String,
+    before: Option<Vec<u8>>,
+    restored_from: Option<String>,
+}
+
+/// 在写入 server_dir 下的 file 之前调用
+pub fn begin(history_dir: &Path, server_dir: &Path, file: &str) -> PendingChange {
+    PendingChange {
+        history_dir: history_dir.to_path_buf(),
+        server_dir: server_dir.to_path_buf(),
+        file: file.to_string(),
+        before: fs::read(server_dir.join(file)).ok(),
+        restored_from: None,
+    }
+}
+
+/// 服务器配置历史的存放目录
+pub fn history_dir(server_id: &str) -> PathBuf {
+    let data_dir = global::server_manager().data_dir.lock().unwrap().clone();
+    Path::new(&data_dir).join("config_history").join(server_id)
+}
+
+/// 删除服务器时清理其配置历史
+pub fn remove_history(server_id: &str) {
+    let _ = fs::remove_dir_all(history_dir(server_id));
+}
+
+impl PendingChange {
+    /// 比较写入前后的内容并记录；内容未变化时返回 None
+    pub fn commit(self) -> Result<Option<ConfigChange>, String> {
+        let _guard = JOURNAL_LOCK.lock().unwrap();
+        let after = fs::read(self.server_dir.join(&self.file)).ok();
+        let mut journal = load_journal(&self.history_dir);
+        let now = Local::now();
+
+        if self.restored_from.is_none() {
+            if let Some(last) = journal.last_mut().filter(|last| self.can_merge(last, &now)) {
+                let original = read_snapshot(&self.history_dir, last);
+                if original == after {
+                    let last = journal.pop().unwrap();
+                    remove_snapshot(&self.history_dir, &last.id);
+                    save_journal(&self.history_dir, &journal)?;
+                    return Ok(None);
+                }
+                last.changes = diff_contents(&self.file, original.as_deref(), after.as_deref());
+                last.timestamp = now.to_rfc3339();
+                let merged = last.clone();
+                save_journal(&self.history_dir, &journal)?;
+                return Ok(Some(merged));
+            }
+        }
+
+        if self.before == after {
+            return Ok(None);
+        }
+
+        let entry = ConfigChange {
+            id: uuid::Uuid::new_v4().to_string(),
+            file: self.file.clone(),
+            timestamp: now.to_rfc3339(),
+            changes: diff_contents(&self.file, self.before.as_deref(), after.as_deref()),
+            created: self.before.is_none(),
+            restored_from: self.restored_from.clone(),
+        };
+        if let Some(before) = &self.before {
+            let snapshot_dir = self.history_dir.join(SNAPSHOT_DIR);
+            fs::create_dir_all(&snapshot_dir).map_err(|e| format!("创建历史目录失败: {}", e))?;
+            fs::write(snapshot_dir.join(&entry.id), before)
+                .map_err(|e| format!("保存配置快照失败: {}", e))?;
+        }
+        journal.push(entry.clone());
+        while journal.len() > MAX_ENTRIES {
+            let oldest = journal.remove(0);
+            remove_snapshot(&self.history_dir, &oldest.id);
+        }
+        save_journal(&self.history_dir, &journal)?;
+        Ok(Some(entry))
+    }
+
+    fn can_merge(&self, last: &ConfigChange, now: &DateTime<Local>) -> bool {
+        if last.file != self.file || last.restored_from.is_some() {
+            return false;
+        }
+        match DateTime::parse_from_rfc3339(&last.timestamp) {
+            Ok(time) => now.signed_duration_since(time).num_seconds() < COALESCE_SECS,
+            Err(_) => false,
+        }
+    }
+}
+
+/// 列出配置历史，最新的在前
+pub fn list(history_dir: &Path) -> Vec<ConfigChange> {
+    let mut journal = load_journal(history_dir);
+    journal.reverse();
+    journal
+}
+
+/// 当前文件与条目写入前快照的差异，即恢复该条目会产生的变化
+pub fn diff(
+    history_dir: &Path,
+    server_dir: &Path,
+    entry_id: &str,
+) -> Result<Vec<ConfigKeyChange>, String> {
+    let entry = find_entry(history_dir, entry_id)?;
+    let current = fs::read(path_guard::resolve_in(server_dir, &entry.file)?).ok();
+    let snapshot = read_snapshot(history_dir, &entry);
+    Ok(diff_contents(&entry.file, current.as_deref(), snapshot.as_deref()))
+}
+
+/// 将文件恢复到条目写入前的状态；恢复本身也会记录到历史中
+pub fn restore(
+    history_dir: &Path,
+    server_dir: &Path,
+    entry_id: &str,
+) -> Result<Option<ConfigChange>, String> {
+    let entry = find_entry(history_dir, entry_id)?;
+    let file_path = path_guard::resolve_in(server_dir, &entry.file)?;
+    let snapshot = read_snapshot(history_dir, &entry);
+    if snapshot.is_none() && !entry.created {
+        return Err(format!("历史快照 {} 已丢失", entry.id));
+    }
+
+    let mut pending = begin(history_dir, server_dir, &entry.file);
+    pending.restored_from = Some(entry.id.clone());
+    match snapshot {
+        Some(content) => {
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            fs::write(&file_path, content)
+                .map_err(|e| format!("恢复 {} 失败: {}", entry.file, e))?;
+        }
+        None => {
+            if file_path.exists() {
+                fs::remove_file(&file_path)
+                    .map_err(|e| format!("恢复 {} 失败: {}", entry.file, e))?;
+            }
+        }
+    }
+    pending.commit()
+}
+
+/// 按配置项比较两个版本的文件内容；无法解析的格式返回空列表
+pub fn diff_contents(file: &str, old: Option<&[u8]>, new: Option<&[u8]>) -> Vec<ConfigKeyChange> {
+    let (Some(old), Some(new)) = (key_values(file, old), key_values(file, new)) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| ConfigKeyChange {
+            key: key.clone(),
+            old: old.get(key).cloned(),
+            new: new.get(key).cloned(),
+        })
+        .collect()
+}
+
+fn key_values(file: &str, content: Option<&[u8]>) -> Option<BTreeMap<String, String>> {
+    let text = match content {
+        Some(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            // .properties 文件可能是 Latin-1 编码
+            Err(_) => bytes.iter().map(|&b| b as char).collect(),
+        },
+        None => String::new(),
+    };
+    if file.to_lowercase().ends_with(".properties") {
+        return Some(
+            PropertiesDocument::parse(&text)
+                .to_map()
+                .into_iter()
+                .collect(),
+        );
+    }
+    config_editor::flatten_content(file, &text)
+}
+
+fn find_entry(history_dir: &Path, entry_id: &str) -> Result<ConfigChange, String> {
+    load_journal(history_dir)
+        .into_iter()
+        .find(|e| e.id == entry_id)
+        .ok_or_else(|| format!("未找到历史记录: {}", entry_id))
+}
+
+fn read_snapshot(history_dir: &Path, entry: &ConfigChange) -> Option<Vec<u8>> {
+    if entry.created {
+        return None;
+    }
+    fs::read(history_dir.join(SNAPSHOT_DIR).join(&entry.id)).ok()
+}
+
+fn remove_snapshot(history_dir: &Path, entry_id: &str) {
+    let _ = fs::remove_file(history_dir.join(SNAPSHOT_DIR).join(entry_id));
+}
+
+fn load_journal(history_dir: &Path) -> Vec<ConfigChange> {
+    fs::read_to_string(history_dir.join(JOURNAL_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_journal(history_dir: &Path, journal: &[ConfigChange]) -> Result<(), String> {
+    fs::create_dir_all(history_dir).map_err(|e| format!("创建历史目录失败: {}", e))?;
+    let json = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+    fs::write(history_dir.join(JOURNAL_FILE), json).map_err(|e| format!("保存配置历史失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_diffs_and_restores_snapshot() {
+        let root = std::env::temp_dir().join(format!("sl-history-{}", uuid::Uuid::new_v4()));
+        let server_dir = root.join("server");
+        let history = root.join("history");
+        fs::create_dir_all(&server_dir).unwrap();
+        let server_dir = fs::canonicalize(&server_dir).unwrap();
+        let props = server_dir.join("server.properties");
+        fs::write(&props, "# comment\nview-distance=10\nmotd=hi\n").unwrap();
+
+        let pending = begin(&history, &server_dir, "server.properties");
+        fs::write(&props, "# comment\nview-distance=32\nmotd=hi\n").unwrap();
+        let entry = pending.commit().unwrap().unwrap();
+        assert_eq!(
+            entry.changes,
+            vec![ConfigKeyChange {
+                key: "view-distance".into(),
+                old: Some("10".into()),
+                new: Some("32".into()),
+            }]
+        );
+
+        // 一分钟内的再次写入与上一条合并
+        let pending = begin(&history, &server_dir, "server.properties");
+        fs::write(&props, "# comment\nview-distance=32\nmotd=hello\n").unwrap();
+        pending.commit().unwrap();
+        assert_eq!(list(&history).len(), 1);
+        assert_eq!(list(&history)[0].changes.len(), 2);
+
+        let pending_diff = diff(&history, &server_dir, &entry.id).unwrap();
+        assert_eq!(pending_diff[1].new.as_deref(), Some("10"));
+
+        let restored = restore(&history, &server_dir, &entry.id).unwrap().unwrap();
+        assert_eq!(restored.restored_from.as_deref(), Some(entry.id.as_str()));
+        assert_eq!(fs::read_to_string(&props).unwrap(), "# comment\nview-distance=10\nmotd=hi\n");
+        assert_eq!(list(&history).len(), 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
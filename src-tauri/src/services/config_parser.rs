@@ -1,89 +1,360 @@
 use std::collections::HashMap;
 use std::fs;
 
+use super::properties_schema::{self, PropertyKind};
 use crate::models::config::*;
 
-/// Read a .properties file into a HashMap
-pub fn read_properties(file_path: &str) -> Result<HashMap<String, String>, String> {
-    let content =
-        fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+/// A .properties file that keeps every original line, so unchanged entries,
+/// comments and formatting survive a round trip
+#[derive(Debug, Clone)]
+pub struct PropertiesDocument {
+    lines: Vec<PropertiesLine>,
+    line_ending: &'static str,
+    trailing_newline: bool,
+}
 
-    let mut map = HashMap::new();
+#[derive(Debug, Clone)]
+enum PropertiesLine {
+    /// Blank lines and `#` / `!` comments, kept verbatim
+    Other(String),
+    Entry {
+        /// Physical lines of the logical line, including continuations
+        raw: Vec<String>,
+        /// Original text up to the value: indentation, key and separator
+        prefix: String,
+        key: String,
+        value: String,
+    },
+}
 
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+impl PropertiesDocument {
+    /// Parse following java.util.Properties#load
+    pub fn parse(content: &str) -> Self {
+        let line_ending = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let trailing_newline = content.is_empty() || content.ends_with(['\n', '\r']);
+        let mut physical = split_lines(content);
+        if trailing_newline && physical.last().is_some_and(|l| l.is_empty()) {
+            physical.pop();
+        }
+
+        let mut lines = Vec::new();
+        let mut index = 0;
+        while index < physical.len() {
+            let first = physical[index];
+            index += 1;
+            let trimmed = first.trim_start_matches(is_whitespace);
+            if trimmed.is_empty() || trimmed.starts_with(['#', '!']) {
+                lines.push(PropertiesLine::Other(first.to_string()));
+                continue;
+            }
+
+            let mut raw = vec![first.to_string()];
+            let mut logical = trimmed.to_string();
+            let first_len = first.len();
+            let mut first_content_len = first_len;
+            while ends_with_continuation(&logical) && index < physical.len() {
+                logical.pop();
+                if raw.len() == 1 {
+                    first_content_len = first_len - 1;
+                }
+                let next = physical[index];
+                index += 1;
+                raw.push(next.to_string());
+                logical.push_str(next.trim_start_matches(is_whitespace));
+            }
+            if ends_with_continuation(&logical) {
+                // A continuation at end of file is dropped by Java as well
+                logical.pop();
+                if raw.len() == 1 {
+                    first_content_len = first_len - 1;
+                }
+            }
+
+            let (key_end, value_start) = split_key_value(&logical);
+            let key = unescape(&logical[..key_end]);
+            let value = unescape(&logical[value_start..]);
+            let indent = first_len - trimmed.len();
+            let prefix = if indent + value_start <= first_content_len {
+                first[..indent + value_start].to_string()
+            } else {
+                format!("{}=", escape(&key, true))
+            };
+            lines.push(PropertiesLine::Entry { raw, prefix, key, value });
+        }
+
+        Self { lines, line_ending, trailing_newline }
+    }
+
+    /// The value of the last occurrence, as Java does for duplicate keys
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            PropertiesLine::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn to_map(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        for line in &self.lines {
+            if let PropertiesLine::Entry { key, value, .. } = line {
+                map.insert(key.clone(), value.clone());
+            }
         }
-        if let Some(eq_pos) = trimmed.find('=') {
-            let key = trimmed[..eq_pos].trim().to_string();
-            let value = trimmed[eq_pos + 1..].trim().to_string();
-            map.insert(key, value);
+        map
+    }
+
+    /// Update every occurrence of the key, or append it when missing.
+    /// Entries whose value is unchanged keep their original text.
+    pub fn set(&mut self, key: &str, new_value: &str) {
+        let mut found = false;
+        for line in &mut self.lines {
+            if let PropertiesLine::Entry { raw, prefix, key: k, value } = line {
+                if k != key {
+                    continue;
+                }
+                found = true;
+                if value != new_value {
+                    *value = new_value.to_string();
+                    *raw = vec![format!("{}{}", prefix, escape(new_value, false))];
+                }
+            }
+        }
+        if !found {
+            let prefix = format!("{}=", escape(key, true));
+            self.lines.push(PropertiesLine::Entry {
+                raw: vec![format!("{}{}", prefix, escape(new_value, false))],
+                prefix,
+                key: key.to_string(),
+                value: new_value.to_string(),
+            });
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let physical: Vec<&str> = self
+            .lines
+            .iter()
+            .flat_map(|line| match line {
+                PropertiesLine::Other(text) => std::slice::from_ref(text).iter(),
+                PropertiesLine::Entry { raw, .. } => raw.iter(),
+            })
+            .map(|l| l.as_str())
+            .collect();
+        let mut output = physical.join(self.line_ending);
+        if self.trailing_newline && !physical.is_empty() {
+            output.push_str(self.line_ending);
         }
+        output
     }
+}
 
-    Ok(map)
+/// Read a .properties file into a HashMap
+pub fn read_properties(file_path: &str) -> Result<HashMap<String, String>, String> {
+    Ok(load_document(file_path)?.to_map())
 }
 
-/// Write a HashMap to a .properties file, preserving comments
+/// Read server.properties from a server directory
+pub fn read_server_dir_properties(server_path: &str) -> Result<HashMap<String, String>, String> {
+    let path = std::path::Path::new(server_path).join("server.properties");
+    read_properties(&path.to_string_lossy())
+}
+
+/// Write values into a .properties file, preserving comments, order and formatting.
+/// Keys that were not in the file are appended in sorted order.
 pub fn write_properties(file_path: &str, values: &HashMap<String, String>) -> Result<(), String> {
-    let original = fs::read_to_string(file_path).unwrap_or_default();
+    let mut document = load_document(file_path).unwrap_or_else(|_| PropertiesDocument::parse(""));
+
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+    for key in keys {
+        document.set(key, &values[key]);
+    }
 
-    let mut output = String::new();
-    let mut written_keys: Vec<String> = Vec::new();
+    fs::write(file_path, document.to_text()).map_err(|e| format!("Failed to write file: {}", e))
+}
 
-    for line in original.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            output.push_str(line);
-            output.push('\n');
+fn load_document(file_path: &str) -> Result<PropertiesDocument, String> {
+    let bytes = fs::read(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    // Minecraft writes UTF-8 since 1.18 and ISO-8859-1 before that
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    };
+    Ok(PropertiesDocument::parse(&content))
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0c')
+}
+
+/// Split on \n, \r\n and lone \r
+fn split_lines(content: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let bytes = content.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                lines.push(&content[start..i]);
+                start = i + 1;
+            }
+            b'\r' => {
+                lines.push(&content[start..i]);
+                if bytes.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    lines.push(&content[start..]);
+    lines
+}
+
+/// An odd number of trailing backslashes continues the line
+fn ends_with_continuation(line: &str) -> bool {
+    line.bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 1
+}
+
+/// Byte offsets of the key end and value start. The key ends at the first
+/// unescaped `=`, `:` or whitespace; whitespace around one separator is skipped.
+fn split_key_value(line: &str) -> (usize, usize) {
+    let bytes = line.as_bytes();
+    let is_ws = |b: u8| matches!(b, b' ' | b'\t' | b'\x0c');
+    let mut key_end = bytes.len();
+    let mut has_separator = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if b == b'\\' {
+            escaped = true;
+        } else if b == b'=' || b == b':' {
+            key_end = i;
+            has_separator = true;
+            break;
+        } else if is_ws(b) {
+            key_end = i;
+            break;
+        }
+    }
+
+    let mut value_start = (key_end + 1).min(bytes.len());
+    while value_start < bytes.len() && is_ws(bytes[value_start]) {
+        value_start += 1;
+    }
+    if !has_separator && value_start < bytes.len() && matches!(bytes[value_start], b'=' | b':') {
+        value_start += 1;
+        while value_start < bytes.len() && is_ws(bytes[value_start]) {
+            value_start += 1;
+        }
+    }
+    (key_end, value_start)
+}
+
+fn unescape(text: &str) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut buf = [0u16; 2];
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            units.extend_from_slice(c.encode_utf16(&mut buf));
             continue;
         }
-        if let Some(eq_pos) = trimmed.find('=') {
-            let key = trimmed[..eq_pos].trim();
-            if let Some(new_value) = values.get(key) {
-                output.push_str(&format!("{}={}\n", key, new_value));
-                written_keys.push(key.to_string());
-            } else {
-                output.push_str(line);
-                output.push('\n');
+        let Some(next) = chars.next() else {
+            break;
+        };
+        match next {
+            't' => units.push('\t' as u16),
+            'n' => units.push('\n' as u16),
+            'r' => units.push('\r' as u16),
+            'f' => units.push(0x0c),
+            'u' => {
+                let hex: String = chars.clone().take(4).collect();
+                match u16::from_str_radix(&hex, 16) {
+                    Ok(unit) if hex.len() == 4 => {
+                        units.push(unit);
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                    }
+                    // Malformed escapes are kept literally instead of failing the whole file
+                    _ => units.push('u' as u16),
+                }
             }
-        } else {
-            output.push_str(line);
-            output.push('\n');
+            other => units.extend_from_slice(other.encode_utf16(&mut buf)),
         }
     }
+    String::from_utf16_lossy(&units)
+}
 
-    // Add new keys that weren't in the original file
-    for (key, value) in values {
-        if !written_keys.contains(key) {
-            output.push_str(&format!("{}={}\n", key, value));
+/// Escape like java.util.Properties#store: separators, comment markers and
+/// non-ASCII characters (as \uXXXX). Keys escape every space, values only a leading one.
+fn escape(text: &str, is_key: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut buf = [0u16; 2];
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            ' ' if is_key || i == 0 => output.push_str("\\ "),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\x0c' => output.push_str("\\f"),
+            '=' | ':' | '#' | '!' | '\\' => {
+                output.push('\\');
+                output.push(c);
+            }
+            c if !(' '..='~').contains(&c) => {
+                for unit in c.encode_utf16(&mut buf) {
+                    output.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => output.push(c),
         }
     }
-
-    fs::write(file_path, output).map_err(|e| format!("Failed to write file: {}", e))
+    output
 }
 
-/// Parse server.properties with descriptions
-pub fn parse_server_properties(file_path: &str) -> Result<ServerProperties, String> {
+/// Parse server.properties with descriptions in the given locale
+pub fn parse_server_properties(file_path: &str, locale: &str) -> Result<ServerProperties, String> {
     let raw = read_properties(file_path)?;
     let mut entries = Vec::new();
 
-    let descriptions = get_property_descriptions();
-
     for (key, value) in &raw {
-        let desc_info = descriptions.get(key.as_str());
+        let schema = properties_schema::find(key);
+        let (min, max) = match schema.map(|s| s.kind) {
+            Some(PropertyKind::Integer { min, max }) => (Some(min), Some(max)),
+            _ => (None, None),
+        };
+        let options = match schema.map(|s| s.kind) {
+            Some(PropertyKind::Select(options)) => options.iter().map(|o| o.to_string()).collect(),
+            _ => Vec::new(),
+        };
         entries.push(ConfigEntry {
             key: key.clone(),
             value: value.clone(),
-            description: desc_info.map(|d| d.0.to_string()).unwrap_or_default(),
-            value_type: desc_info
-                .map(|d| d.1.to_string())
+            description: schema
+                .map(|s| s.description(locale).to_string())
+                .unwrap_or_default(),
+            value_type: schema
+                .map(|s| s.kind.value_type().to_string())
                 .unwrap_or_else(|| "string".to_string()),
-            default_value: desc_info.map(|d| d.2.to_string()).unwrap_or_default(),
-            category: desc_info
-                .map(|d| d.3.to_string())
+            default_value: schema.map(|s| s.default.to_string()).unwrap_or_default(),
+            category: schema
+                .map(|s| s.category.to_string())
                 .unwrap_or_else(|| "other".to_string()),
+            options,
+            min,
+            max,
+            since: schema.map(|s| s.since.to_string()),
+            removed: schema.and_then(|s| s.removed.map(|v| v.to_string())),
         });
     }
 
@@ -93,46 +364,56 @@ pub fn parse_server_properties(file_path: &str) -> Result<ServerProperties, Stri
     Ok(ServerProperties { entries, raw })
 }
 
-/// Property descriptions: (description, type, default, category)
-fn get_property_descriptions(
-) -> HashMap<&'static str, (&'static str, &'static str, &'static str, &'static str)> {
-    let mut m = HashMap::new();
-    m.insert("server-port", ("服务器端口", "number", "25565", "network"));
-    m.insert("server-ip", ("服务器绑定IP，留空表示所有", "string", "", "network"));
-    m.insert("max-players", ("最大玩家数", "number", "20", "player"));
-    m.insert("online-mode", ("正版验证", "boolean", "true", "player"));
-    m.insert("white-list", ("启用白名单", "boolean", "false", "player"));
-    m.insert("enforce-whitelist", ("强制白名单", "boolean", "false", "player"));
-    m.insert("gamemode", ("默认游戏模式", "select", "survival", "game"));
-    m.insert("difficulty", ("游戏难度", "select", "easy", "game"));
-    m.insert("hardcore", ("极限模式", "boolean", "false", "game"));
-    m.insert("pvp", ("允许PVP", "boolean", "true", "game"));
-    m.insert("allow-flight", ("允许飞行", "boolean", "false", "game"));
-    m.insert("allow-nether", ("允许下界", "boolean", "true", "world"));
-    m.insert("spawn-monsters", ("生成怪物", "boolean", "true", "world"));
-    m.insert("spawn-animals", ("生成动物", "boolean", "true", "world"));
-    m.insert("spawn-npcs", ("生成NPC", "boolean", "true", "world"));
-    m.insert("generate-structures", ("生成结构", "boolean", "true", "world"));
-    m.insert("level-name", ("世界名称", "string", "world", "world"));
-    m.insert("level-seed", ("世界种子", "string", "", "world"));
-    m.insert("level-type", ("世界类型", "string", "minecraft:normal", "world"));
-    m.insert("view-distance", ("视距", "number", "10", "performance"));
-    m.insert("simulation-distance", ("模拟距离", "number", "10", "performance"));
-    m.insert(
-        "max-tick-time",
-        ("最大tick时间(ms)，-1为禁用", "number", "60000", "performance"),
-    );
-    m.insert(
-        "network-compression-threshold",
-        ("网络压缩阈值", "number", "256", "performance"),
-    );
-    m.insert("motd", ("服务器描述(MOTD)", "string", "A Minecraft Server", "display"));
-    m.insert("enable-command-block", ("启用命令方块", "boolean", "false", "game"));
-    m.insert("enable-query", ("启用Query协议", "boolean", "false", "network"));
-    m.insert("enable-rcon", ("启用RCON远程控制", "boolean", "false", "network"));
-    m.insert("enable-status", ("启用服务器列表状态", "boolean", "true", "network"));
-    m.insert("force-gamemode", ("强制游戏模式", "boolean", "false", "game"));
-    m.insert("spawn-protection", ("出生点保护半径", "number", "16", "world"));
-    m.insert("sync-chunk-writes", ("同步区块写入", "boolean", "true", "performance"));
-    m
+/// Validate against the vanilla schema, then write server.properties
+pub fn write_server_properties(
+    file_path: &str,
+    values: &HashMap<String, String>,
+) -> Result<(), String> {
+    if let Err(errors) = properties_schema::validate_properties(values) {
+        let details: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        return Err(format!("配置项校验失败:\n{}", details.join("\n")));
+    }
+    write_properties(file_path, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "#Minecraft server properties\r\n\
+        ! legacy comment\r\n\
+        motd=\\u00A7aHello \\u4E16\\u754C\r\n\
+        server-port = 25565\r\n\
+        level-name:my\\ world\r\n\
+        resource-pack=https\\://example.com/pack.zip?a\\=1\r\n\
+        long-value=first \\\r\n    second\r\n\
+        key\\:with\\=separators value\r\n";
+
+    #[test]
+    fn parses_java_properties_syntax() {
+        let document = PropertiesDocument::parse(SAMPLE);
+        assert_eq!(document.get("motd"), Some("§aHello 世界"));
+        assert_eq!(document.get("server-port"), Some("25565"));
+        assert_eq!(document.get("level-name"), Some("my world"));
+        assert_eq!(document.get("resource-pack"), Some("https://example.com/pack.zip?a=1"));
+        assert_eq!(document.get("long-value"), Some("first second"));
+        assert_eq!(document.get("key:with=separators"), Some("value"));
+        assert_eq!(document.to_map().len(), 6);
+        assert_eq!(document.to_text(), SAMPLE);
+    }
+
+    #[test]
+    fn writes_only_changed_entries() {
+        let mut document = PropertiesDocument::parse(SAMPLE);
+        document.set("server-port", "25566");
+        document.set("long-value", "first second");
+        document.set("motd", "你好 #1");
+        document.set("max-players", "10");
+        let text = document.to_text();
+        assert!(text.contains("server-port = 25566\r\n"));
+        assert!(text.contains("long-value=first \\\r\n    second\r\n"));
+        assert!(text.contains("motd=\\u4F60\\u597D \\#1\r\n"));
+        assert!(text.ends_with("max-players=10\r\n"));
+        assert_eq!(PropertiesDocument::parse(&text).get("motd"), Some("你好 #1"));
+    }
 }
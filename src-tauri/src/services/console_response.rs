@@ -0,0 +1,211 @@
+use regex::Regex;
+use std::time::Duration;
+
+use super::{global, path_guard, rcon};
+use crate::models::server::CommandResponse;
+
+/// 等待命令响应的默认超时
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 原版与 Bukkit 对无效命令的通用失败输出
+const GENERIC_FAILURES: &[&str] = &[
+    r"Unknown or incomplete command",
+    r"Incorrect argument for command",
+    r"Unknown command",
+    r"That player does not exist",
+    r"No player was found",
+];
+
+/// 判断控制台输出属于命令成功还是失败
+pub struct ResponsePattern {
+    success: Vec<Regex>,
+    failure: Vec<Regex>,
+}
+
+impl ResponsePattern {
+    /// 模式为正则表达式，只用于内置常量，无效时 panic
+    pub fn new(success: &[&str], failure: &[&str]) -> Self {
+        let compile = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|p| Regex::new(p).expect("无效的响应模式"))
+                .collect::<Vec<_>>()
+        };
+        ResponsePattern {
+            success: compile(success),
+            failure: compile(failure)
+                .into_iter()
+                .chain(compile(GENERIC_FAILURES))
+                .collect(),
+        }
+    }
+
+    /// 失败模式优先；都不匹配时返回 None
+    pub fn classify(&self, line: &str) -> Option<bool> {
+        let message = message_of(line);
+        if self.failure.iter().any(|r| r.is_match(message)) {
+            Some(false)
+        } else if self.success.iter().any(|r| r.is_match(message)) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// 常用原版命令的响应模式，未知命令只识别通用失败输出
+pub fn for_command(command: &str) -> ResponsePattern {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    match words.as_slice() {
+        ["whitelist", "add", ..] => ResponsePattern::new(
+            &[r"^Added \S+ to the whitelist"],
+            &[r"^Player is already whitelisted"],
+        ),
+        ["whitelist", "remove", ..] => ResponsePattern::new(
+            &[r"^Removed \S+ from the whitelist"],
+            &[r"^Player is not whitelisted"],
+        ),
+        ["whitelist", "reload", ..] => ResponsePattern::new(&[r"^Reloaded the whitelist"], &[]),
+        ["op", ..] => ResponsePattern::new(
+            &[r"^Made \S+ a server operator"],
+            &[r"^Nothing changed\. The player already is an operator"],
+        ),
+        ["deop", ..] => ResponsePattern::new(
+            &[r"^Made \S+ no longer a server operator"],
+            &[r"^Nothing changed\. The player is not an operator"],
+        ),
+        ["ban", ..] => ResponsePattern::new(
+            &[r"^Banned \S+"],
+            &[r"^Nothing changed\. The player is already banned"],
+        ),
+        ["pardon", ..] => ResponsePattern::new(
+            &[r"^Unbanned \S+"],
+            &[r"^Nothing changed\. The player isn't banned"],
+        ),
+        ["ban-ip", ..] => ResponsePattern::new(
+            &[r"^Banned IP \S+"],
+            &[r"^Nothing changed\. That IP is already banned", r"^Invalid IP address"],
+        ),
+        ["pardon-ip", ..] => ResponsePattern::new(
+            &[r"^Unbanned IP \S+"],
+            &[r"^Nothing changed\. That IP isn't banned", r"^Invalid IP address"],
+        ),
+        ["kick", ..] => ResponsePattern::new(&[r"^Kicked \S+"], &[]),
+        _ => ResponsePattern::new(&[], &[]),
+    }
+}
+
+/// 去掉 "[12:00:00] [Server thread/INFO]: " 形式的日志前缀
+pub fn message_of(line: &str) -> &str {
+    match line.find("]: ") {
+        Some(index) if line.starts_with('[') => &line[index + 3..],
+        _ => line,
+    }
+}
+
+/// 发送命令并等待响应：成功返回服务器的输出，失败返回其错误信息；
+/// 超时未识别到响应时视为已发送
+pub fn run(server_id: &str, command: &str) -> Result<String, String> {
+    let pattern = for_command(command);
+    if let Some(output) = via_rcon(server_id, command) {
+        let lines: Vec<String> = output
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        let accepted = lines.iter().find_map(|l| pattern.classify(l));
+        // RCON 的响应是同步且完整的，无法识别时直接返回原文
+        if accepted.is_none() && !lines.is_empty() {
+            return Ok(lines.join("\n"));
+        }
+        return into_result(command, &pattern, &CommandResponse { accepted, lines });
+    }
+    let response = global::server_manager().send_command_and_wait(
+        server_id,
+        command,
+        &pattern,
+        DEFAULT_TIMEOUT,
+    )?;
+    into_result(command, &pattern, &response)
+}
+
+/// 服务器启用了 RCON 时优先使用；连接或认证失败时返回 None，改用控制台发送
+fn via_rcon(server_id: &str, command: &str) -> Option<String> {
+    let root = path_guard::server_root(server_id).ok()?;
+    let root = root.to_string_lossy();
+    if !rcon::read_config(&root)?.usable() {
+        return None;
+    }
+    match rcon::exec(&root, command) {
+        Ok(output) => Some(output),
+        Err(e) => {
+            println!("[RCON] {}，改用控制台发送", e);
+            None
+        }
+    }
+}
+
+/// 返回与判定结果一致的那一行，多行响应中其他行可能是无关输出
+fn into_result(
+    command: &str,
+    pattern: &ResponsePattern,
+    response: &CommandResponse,
+) -> Result<String, String> {
+    let matched = response
+        .lines
+        .iter()
+        .find(|l| pattern.classify(l) == response.accepted)
+        .or(response.lines.last())
+        .map(|l| message_of(l).to_string())
+        .unwrap_or_default();
+    match response.accepted {
+        Some(true) => Ok(matched),
+        Some(false) => Err(matched),
+        None => Ok(format!("Sent: {}", command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_vanilla_output() {
+        let pattern = for_command("whitelist add Steve");
+        assert_eq!(
+            pattern.classify("[12:00:00] [Server thread/INFO]: Added Steve to the whitelist"),
+            Some(true)
+        );
+        assert_eq!(
+            pattern.classify("[12:00:00] [Server thread/INFO]: Player is already whitelisted"),
+            Some(false)
+        );
+        assert_eq!(pattern.classify("That player does not exist"), Some(false));
+        assert_eq!(
+            pattern.classify("[12:00:01] [Server thread/INFO]: Steve joined the game"),
+            None
+        );
+
+        let response = CommandResponse {
+            accepted: Some(false),
+            lines: vec!["[12:00:00] [Server thread/INFO]: That player does not exist".into()],
+        };
+        assert_eq!(
+            into_result("op Nobody", &for_command("op Nobody"), &response),
+            Err("That player does not exist".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_the_matching_line_of_multi_line_replies() {
+        let command = "ban Steve griefing";
+        let response = CommandResponse {
+            accepted: Some(true),
+            lines: vec!["Banned Steve: griefing".into(), "Steve left the game".into()],
+        };
+        assert_eq!(
+            into_result(command, &for_command(command), &response),
+            Ok("Banned Steve: griefing".to_string())
+        );
+    }
+}
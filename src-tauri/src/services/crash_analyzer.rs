@@ -0,0 +1,385 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::server::{CrashDiagnosis, CrashFinding};
+
+/// 从控制台日志末尾读取的字节数
+const LOG_TAIL_BYTES: u64 = 128 * 1024;
+/// 诊断结果中保留的控制台行数
+const LOG_TAIL_LINES: usize = 200;
+
+/// 已知崩溃原因：任一模式匹配即命中，describe 根据捕获组生成说明与建议
+struct Rule {
+    id: &'static str,
+    title: &'static str,
+    patterns: Vec<Regex>,
+    describe: fn(&Captures) -> (String, String),
+}
+
+impl Rule {
+    fn new(
+        id: &'static str,
+        title: &'static str,
+        patterns: &[&str],
+        describe: fn(&Captures) -> (String, String),
+    ) -> Self {
+        Rule {
+            id,
+            title,
+            patterns: patterns
+                .iter()
+                .map(|p| Regex::new(p).expect("无效的崩溃规则"))
+                .collect(),
+            describe,
+        }
+    }
+
+    fn find(&self, text: &str) -> Option<(String, String)> {
+        self.patterns
+            .iter()
+            .find_map(|p| p.captures(text))
+            .map(|caps| (self.describe)(&caps))
+    }
+}
+
+fn group<'t>(caps: &Captures<'t>, name: &str) -> Option<&'t str> {
+    caps.name(name).map(|m| m.as_str().trim())
+}
+
+static RULES: Lazy<Vec<Rule>> = Lazy::new(|| {
+    vec![
+        Rule::new(
+            "java_version",
+            "Java 版本不匹配",
+            &[
+                r"UnsupportedClassVersionError[^\n]*?class file version (?P<class>\d+)(?:\.\d+)?[^\n]*?up to (?P<runtime>\d+)",
+                r"UnsupportedClassVersionError[^\n]*?class file version (?P<class>\d+)",
+                r"UnsupportedClassVersionError",
+            ],
+            |caps| {
+                let required = group(caps, "class")
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .map(|v| v.saturating_sub(44));
+                let current = group(caps, "runtime")
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .map(|v| v.saturating_sub(44));
+                match (required, current) {
+                    (Some(required), Some(current)) => (
+                        format!("服务端需要 Java {}，当前使用的是 Java {}", required, current),
+                        format!("在服务器设置中选择 Java {} 或更高版本", required),
+                    ),
+                    (Some(required), None) => (
+                        format!("服务端需要 Java {} 或更高版本", required),
+                        format!("在服务器设置中选择 Java {} 或更高版本", required),
+                    ),
+                    _ => (
+                        "服务端或模组使用了更高版本的 Java 编译".to_string(),
+                        "在服务器设置中选择更高版本的 Java".to_string(),
+                    ),
+                }
+            },
+        ),
+        Rule::new(
+            "out_of_memory",
+            "内存不足",
+            &[
+                r"java\.lang\.OutOfMemoryError:?\s*(?P<kind>[^\n]*)",
+                r"(?P<native>There is insufficient memory for the Java Runtime Environment|Native memory allocation \(\w+\) failed)",
+            ],
+            |caps| {
+                if group(caps, "native").is_some() {
+                    (
+                        "系统可用内存不足，Java 无法分配内存".to_string(),
+                        "关闭其他占用内存的程序，或调低服务器的最大内存".to_string(),
+                    )
+                } else {
+                    let kind = group(caps, "kind").filter(|k| !k.is_empty());
+                    (
+                        format!(
+                            "Java 堆内存耗尽{}",
+                            kind.map(|k| format!("（{}）", k)).unwrap_or_default()
+                        ),
+                        "在服务器设置中调高最大内存，或减少模组、降低视距".to_string(),
+                    )
+                }
+            },
+        ),
+        Rule::new(
+            "missing_dependency",
+            "缺少前置模组",
+            &[
+                r"Mod ID: '(?P<dep>[^']+)', Requested by: '(?P<mod>[^']+)'",
+                r"Mod '(?P<mod>[^']+)' \([^)]*\) \S+ requires (?P<dep>[^\n]+?), which is missing",
+                r"MissingModsException: Mod (?P<mod>\S+) \([^)]*\) requires \[(?P<dep>[^\]@]+)",
+            ],
+            |caps| {
+                let dep = group(caps, "dep").unwrap_or("未知模组");
+                let requested = group(caps, "mod").unwrap_or("未知模组");
+                (
+                    format!("模组 {} 需要前置 {}", requested, dep),
+                    format!("安装 {} 的服务端版本，或移除 {}", dep, requested),
+                )
+            },
+        ),
+        Rule::new(
+            "duplicate_mods",
+            "模组重复",
+            &[
+                r"Mod ID: '(?P<id>[^']+)' from mod files: (?P<files>[^\n]+)",
+                r"(?i)duplicate mods?(?: found)?[^\n]*?'?(?P<id>[\w-]+)'?",
+                r"DuplicateModsFoundException",
+            ],
+            |caps| {
+                let id = group(caps, "id").unwrap_or("未知模组");
+                let detail = match group(caps, "files") {
+                    Some(files) => format!("模组 {} 同时存在于: {}", id, files),
+                    None => format!("模组 {} 被安装了多次", id),
+                };
+                (detail, "在 mods 文件夹中只保留一个版本".to_string())
+            },
+        ),
+        Rule::new(
+            "client_only_mod",
+            "服务端安装了仅客户端模组",
+            &[
+                r"Attempted to load class (?P<class>net/minecraft/client/\S+) for invalid dist DEDICATED_SERVER",
+                r"NoClassDefFoundError: (?P<class>net/minecraft/client/\S+)",
+                r"ClassNotFoundException: (?P<class>net\.minecraft\.client\.\S+)",
+                r"Cannot load (?P<class>net/minecraft/client/\S+)",
+            ],
+            |caps| {
+                let class = group(caps, "class").unwrap_or("net/minecraft/client");
+                (
+                    format!("有模组在服务端加载了客户端类 {}", class),
+                    "移除仅客户端模组（如光影、小地图、OptiFine 等），可查看崩溃报告中的 Suspected Mods".to_string(),
+                )
+            },
+        ),
+        Rule::new(
+            "port_in_use",
+            "端口已被占用",
+            &[
+                r"(?i)FAILED TO BIND TO PORT",
+                r"(?i)Perhaps a server is already running on that port",
+                r"(?i)Address already in use",
+            ],
+            |_| {
+                (
+                    "服务器端口已被其他程序或服务器占用".to_string(),
+                    "关闭占用端口的程序，或在服务器设置中更换端口".to_string(),
+                )
+            },
+        ),
+        Rule::new(
+            "corrupt_level_dat",
+            "世界存档损坏",
+            &[
+                r"(?i)(?:exception reading|failed to (?:load|read)|error reading)[^\n]*level\.dat",
+                r"(?i)level\.dat[^\n]*(?:corrupt|EOFException|ZipException|Not in GZIP format)",
+            ],
+            |_| {
+                (
+                    "level.dat 无法读取，可能已损坏".to_string(),
+                    "用世界目录中的 level.dat_old 替换 level.dat，或从备份恢复".to_string(),
+                )
+            },
+        ),
+        Rule::new(
+            "mixin_failure",
+            "Mixin 注入失败",
+            &[
+                r"Mixin apply for mod (?P<mod>[\w-]+) failed (?P<mixin>\S+)",
+                r"Mixin \[(?P<mixin>[^\]]+?) from mod (?P<mod>[\w-]+)\][^\n]*FAILED",
+                r"Mixin apply failed (?P<mixin>\S+)",
+                r"Critical injection failure: [^\n]*?in (?P<mixin>[\w.-]+\.json:\S+)(?: from mod (?P<mod>[\w-]+))?",
+            ],
+            |caps| {
+                let mixin = group(caps, "mixin").unwrap_or("未知");
+                match group(caps, "mod") {
+                    Some(name) => (
+                        format!("模组 {} 的 Mixin {} 注入失败", name, mixin),
+                        format!("更新或移除模组 {}，并确认它与当前游戏版本和加载器兼容", name),
+                    ),
+                    None => (
+                        format!("Mixin {} 注入失败", mixin),
+                        "根据 Mixin 配置名找到对应模组，更新或移除它".to_string(),
+                    ),
+                }
+            },
+        ),
+    ]
+});
+
+/// 分析服务器退出原因：依次检查本次启动后的崩溃报告、JVM 错误日志与控制台输出
+pub fn analyze(
+    server_id: &str,
+    server_path: &str,
+    started_at: Option<u64>,
+    exit_code: Option<i32>,
+) -> CrashDiagnosis {
+    let dir = Path::new(server_path);
+    let crash_report =
+        newest_since(&dir.join("crash-reports"), started_at, |name| name.ends_with(".txt"));
+    let jvm_error_log = newest_since(dir, started_at, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    });
+    let log_tail = read_tail(&dir.join("latest.log"));
+
+    let mut sources: Vec<(String, String)> = Vec::new();
+    for path in [&crash_report, &jvm_error_log].into_iter().flatten() {
+        if let Ok(content) = read_lossy(path) {
+            sources.push((file_name(path), content));
+        }
+    }
+    sources.push(("控制台".to_string(), log_tail.join("\n")));
+
+    let findings = RULES
+        .iter()
+        .filter_map(|rule| {
+            sources.iter().find_map(|(source, text)| {
+                rule.find(text).map(|(detail, suggestion)| CrashFinding {
+                    rule: rule.id.to_string(),
+                    title: rule.title.to_string(),
+                    detail,
+                    suggestion,
+                    source: source.clone(),
+                })
+            })
+        })
+        .collect();
+
+    CrashDiagnosis {
+        server_id: server_id.to_string(),
+        detected_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        exit_code,
+        findings,
+        crash_report: crash_report.map(|p| p.to_string_lossy().to_string()),
+        jvm_error_log: jvm_error_log.map(|p| p.to_string_lossy().to_string()),
+        log_tail,
+    }
+}
+
+/// 目录中修改时间不早于 since 的最新文件
+fn newest_since(dir: &Path, since: Option<u64>, matches: fn(&str) -> bool) -> Option<PathBuf> {
+    let since = since.map(|s| UNIX_EPOCH + std::time::Duration::from_secs(s));
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| matches(&e.file_name().to_string_lossy()))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .filter(|(modified, _)| !matches!(since, Some(s) if *modified < s))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn read_lossy(path: &Path) -> std::io::Result<String> {
+    Ok(String::from_utf8_lossy(&fs::read(path)?).to_string())
+}
+
+/// 控制台日志末尾的若干行
+fn read_tail(path: &Path) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(LOG_TAIL_BYTES);
+    let mut buffer = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut buffer).is_err() {
+        return Vec::new();
+    }
+    let content = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = content.lines().skip(usize::from(start > 0)).collect();
+    lines[lines.len().saturating_sub(LOG_TAIL_LINES)..]
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_ids(log: &str) -> Vec<&'static str> {
+        RULES
+            .iter()
+            .filter(|r| r.find(log).is_some())
+            .map(|r| r.id)
+            .collect()
+    }
+
+    #[test]
+    fn matches_known_crash_causes() {
+        let java = "Error: LinkageError occurred while loading main class net.minecraft.bundler.Main\n\
+            java.lang.UnsupportedClassVersionError: net/minecraft/bundler/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 52.0";
+        assert_eq!(rule_ids(java), ["java_version"]);
+        let (detail, _) = RULES[0].find(java).unwrap();
+        assert_eq!(detail, "服务端需要 Java 21，当前使用的是 Java 8");
+
+        let forge = "Missing or unsupported mandatory dependencies:\n\
+            \tMod ID: 'geckolib', Requested by: 'alexsmobs', Expected range: '[4.2,)', Actual version: '[MISSING]'";
+        let (detail, _) = RULES[2].find(forge).unwrap();
+        assert_eq!(detail, "模组 alexsmobs 需要前置 geckolib");
+
+        let fabric = " - Mod 'Sodium Extra' (sodium-extra) 0.5.1 requires any version of sodium, which is missing!";
+        assert_eq!(rule_ids(fabric), ["missing_dependency"]);
+
+        let mixin = "org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered\n\
+            Caused by: org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [carpet.mixins.json:ServerWorld_tickMixin from mod carpet] from phase [DEFAULT] in config [carpet.mixins.json] FAILED during APPLY";
+        let (detail, _) = RULES[7].find(mixin).unwrap();
+        assert!(detail.contains("模组 carpet"));
+
+        assert_eq!(rule_ids("[Server thread/WARN]: **** FAILED TO BIND TO PORT!"), ["port_in_use"]);
+        assert_eq!(
+            rule_ids("java.lang.RuntimeException: Attempted to load class net/minecraft/client/gui/screens/Screen for invalid dist DEDICATED_SERVER"),
+            ["client_only_mod"]
+        );
+        assert_eq!(
+            rule_ids("Exception reading ./world/level.dat\njava.util.zip.ZipException: Not in GZIP format"),
+            ["corrupt_level_dat"]
+        );
+        assert_eq!(rule_ids("java.lang.OutOfMemoryError: Java heap space"), ["out_of_memory"]);
+        assert!(rule_ids("[Server thread/INFO]: Done (3.2s)! For help, type \"help\"").is_empty());
+    }
+
+    #[test]
+    fn analyzes_reports_written_after_start() {
+        let dir = std::env::temp_dir().join(format!("sl-crash-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("crash-reports")).unwrap();
+        fs::write(
+            dir.join("crash-reports").join("crash-2026-01-01_00.00.00-server.txt"),
+            "---- Minecraft Crash Report ----\nDescription: Exception in server tick loop\n\njava.lang.OutOfMemoryError: Java heap space\n",
+        )
+        .unwrap();
+        fs::write(dir.join("latest.log"), "[INFO]: Starting minecraft server\n").unwrap();
+
+        let diagnosis = analyze("s1", &dir.to_string_lossy(), None, Some(1));
+        assert_eq!(diagnosis.findings.len(), 1);
+        assert_eq!(diagnosis.findings[0].rule, "out_of_memory");
+        assert_eq!(diagnosis.findings[0].source, "crash-2026-01-01_00.00.00-server.txt");
+        assert_eq!(diagnosis.log_tail, ["[INFO]: Starting minecraft server"]);
+
+        let future = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600;
+        let later = analyze("s1", &dir.to_string_lossy(), Some(future), Some(1));
+        assert!(later.crash_report.is_none());
+        assert!(later.findings.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::global;
+use super::loader_installer::{self, InstalledCore, LoaderSpec};
+use super::{mrpack, path_guard};
+use crate::utils::http;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const OFFICIAL_API: &str = "https://api.curseforge.com";
+
+/// API 不允许第三方下载、需要用户手动下载的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockedFile {
+    pub project_id: u64,
+    pub file_id: u64,
+    pub file_name: String,
+    /// 浏览器中打开即可下载
+    pub url: String,
+}
+
+/// CurseForge 整合包导入结果
+#[derive(Debug, Clone)]
+pub struct CurseForgeImport {
+    pub name: String,
+    pub mc_version: String,
+    pub core: InstalledCore,
+    pub blocked: Vec<BlockedFile>,
+    /// 可选文件以及资源包等服务端不需要的文件
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifest {
+    #[serde(default)]
+    pub manifest_type: String,
+    pub minecraft: ManifestMinecraft,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    pub version: String,
+    #[serde(default)]
+    pub mod_loaders: Vec<ManifestModLoader>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestModLoader {
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: u64,
+    #[serde(rename = "fileID")]
+    pub file_id: u64,
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 压缩包根目录含有 manifestType 为 minecraftModpack 的 manifest.json
+pub fn is_curseforge_pack(pack_path: &Path) -> bool {
+    let Ok(file) = fs::File::open(pack_path) else {
+        return false;
+    };
+    let Ok(mut archive) = zip::ZipArchive::new(file) else {
+        return false;
+    };
+    read_manifest(&mut archive).is_ok_and(|m| m.manifest_type == "minecraftModpack")
+}
+
+/// 下载 manifest.json 中的必需文件，应用 overrides 并安装 minecraft.modLoaders 中的加载器。
+/// 作者禁止第三方下载的文件不会导致失败，而是记录在 blocked 中
+pub async fn install_curseforge_pack(
+    pack_path: &Path,
+    server_dir: &Path,
+    java_path: &str,
+) -> Result<CurseForgeImport, String> {
+    let file = fs::File::open(pack_path).map_err(|e| format!("无法打开整合包: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("无效的整合包: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    let loader = loader_from_mod_loaders(&manifest.minecraft.mod_loaders)?;
+
+    let settings = global::settings_manager().get();
+    let api_url = settings
+        .curseforge_api_url
+        .trim_end_matches('/')
+        .to_string();
+    let api_key = settings.curseforge_api_key.trim().to_string();
+    if api_key.is_empty() && api_url.starts_with(OFFICIAL_API) {
+        return Err("未配置 CurseForge API Key，请在设置中填写，或改用兼容的镜像地址".to_string());
+    }
+
+    let client = http::client();
+    let mut skipped = Vec::new();
+    let required: Vec<&ManifestFile> = manifest
+        .files
+        .iter()
+        .filter(|f| {
+            if !f.required {
+                skipped.push(format!("{}/{}", f.project_id, f.file_id));
+            }
+            f.required
+        })
+        .collect();
+    let file_ids: Vec<u64> = required.iter().map(|f| f.file_id).collect();
+    let resolved = get_files(&client, &api_url, &api_key, &file_ids).await?;
+
+    fs::create_dir_all(server_dir.join("mods"))
+        .map_err(|e| format!("无法创建 mods 目录: {}", e))?;
+    let mut blocked = Vec::new();
+    for entry in required {
+        let blocked_file = |file_name: String| BlockedFile {
+            project_id: entry.project_id,
+            file_id: entry.file_id,
+            file_name,
+            url: format!(
+                "https://www.curseforge.com/api/v1/mods/{}/files/{}/download",
+                entry.project_id, entry.file_id
+            ),
+        };
+        let Some(cf_file) = resolved.get(&entry.file_id) else {
+            blocked.push(blocked_file(format!("{}/{}", entry.project_id, entry.file_id)));
+            continue;
+        };
+        if !cf_file.file_name.to_lowercase().ends_with(".jar") {
+            skipped.push(cf_file.file_name.clone());
+            continue;
+        }
+        let Some(url) = cf_file.download_url.as_deref() else {
+            blocked.push(blocked_file(cf_file.file_name.clone()));
+            continue;
+        };
+        let target = path_guard::safe_relative_path(&cf_file.file_name)
+            .filter(|p| p.components().count() == 1)
+            .map(|p| server_dir.join("mods").join(p))
+            .ok_or_else(|| format!("非法文件名: {}", cf_file.file_name))?;
+        loader_installer::download_file(&client, url, &target).await?;
+        verify_sha1(&target, cf_file)?;
+    }
+
+    let prefix = format!("{}/", manifest.overrides.trim_end_matches('/'));
+    mrpack::extract_zip_dir(&mut archive, &prefix, server_dir)?;
+
+    let loader_ref = loader
+        .as_ref()
+        .map(|(name, version)| (*name, version.as_str()));
+    let core = loader_installer::install_server_core(
+        server_dir,
+        &manifest.minecraft.version,
+        loader_ref,
+        java_path,
+    )
+    .await?;
+
+    Ok(CurseForgeImport {
+        name: manifest.name,
+        mc_version: manifest.minecraft.version,
+        core,
+        blocked,
+        skipped,
+    })
+}
+
+pub fn read_manifest<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<CurseForgeManifest, String> {
+    let mut entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| format!("整合包中缺少 {}", MANIFEST_FILE))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", MANIFEST_FILE, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", MANIFEST_FILE, e))
+}
+
+/// "forge-47.2.0" -> ("forge", "47.2.0")，优先使用 primary 加载器
+pub fn loader_from_mod_loaders(
+    loaders: &[ManifestModLoader],
+) -> Result<Option<LoaderSpec>, String> {
+    let Some(loader) = loaders.iter().find(|l| l.primary).or(loaders.first()) else {
+        return Ok(None);
+    };
+    let (name, version) = loader
+        .id
+        .split_once('-')
+        .ok_or_else(|| format!("无法识别的加载器: {}", loader.id))?;
+    let name = match name {
+        "forge" => "forge",
+        "neoforge" => "neoforge",
+        "fabric" => "fabric",
+        "quilt" => "quilt",
+        other => return Err(format!("不支持的模组加载器: {}", other)),
+    };
+    Ok(Some((name, version.to_string())))
+}
+
+async fn get_files(
+    client: &reqwest::Client,
+    api_url: &str,
+    api_key: &str,
+    file_ids: &[u64],
+) -> Result<HashMap<u64, CurseForgeFile>, String> {
+    if file_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut request = client
+        .post(format!("{}/mods/files", api_url))
+        .json(&serde_json::json!({ "fileIds": file_ids }));
+    if !api_key.is_empty() {
+        request = request.header("x-api-key", api_key);
+    }
+    let resp: CurseForgeFilesResponse = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("请求 CurseForge API 失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析 CurseForge API 响应失败: {}", e))?;
+    Ok(resp.data.into_iter().map(|f| (f.id, f)).collect())
+}
+
+fn verify_sha1(path: &Path, file: &CurseForgeFile) -> Result<(), String> {
+    // algo 1 为 SHA-1，2 为 MD5
+    let Some(expected) = file.hashes.iter().find(|h| h.algo == 1) else {
+        return Ok(());
+    };
+    let bytes = fs::read(path).map_err(|e| format!("无法读取 {}: {}", path.display(), e))?;
+    let actual = format!("{:x}", Sha1::digest(&bytes));
+    if actual.eq_ignore_ascii_case(&expected.value) {
+        return Ok(());
+    }
+    let _ = fs::remove_file(path);
+    Err(format!("{} 校验失败，文件可能已损坏或被篡改", file.file_name))
+}
+
+#[derive(Deserialize)]
+struct CurseForgeFilesResponse {
+    data: Vec<CurseForgeFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeFile {
+    id: u64,
+    file_name: String,
+    download_url: Option<String>,
+    #[serde(default)]
+    hashes: Vec<CurseForgeHash>,
+}
+
+#[derive(Deserialize)]
+struct CurseForgeHash {
+    value: String,
+    algo: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest_and_loader() {
+        let manifest: CurseForgeManifest = serde_json::from_str(
+            r#"{
+                "minecraft": {
+                    "version": "1.20.1",
+                    "modLoaders": [{ "id": "forge-47.2.0", "primary": true }]
+                },
+                "manifestType": "minecraftModpack",
+                "name": "Example Pack",
+                "files": [
+                    { "projectID": 238222, "fileID": 4712861, "required": true },
+                    { "projectID": 306612, "fileID": 4690231, "required": false }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.overrides, "overrides");
+        assert_eq!(manifest.files.len(), 2);
+        assert!(!manifest.files[1].required);
+
+        let loader = loader_from_mod_loaders(&manifest.minecraft.mod_loaders).unwrap();
+        assert_eq!(loader, Some(("forge", "47.2.0".to_string())));
+        assert_eq!(loader_from_mod_loaders(&[]).unwrap(), None);
+    }
+}
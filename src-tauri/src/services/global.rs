@@ -29,3 +29,17 @@ pub fn server_id_manager() -> &'static ServerIdManager {
     static INSTANCE: OnceLock<ServerIdManager> = OnceLock::new();
     INSTANCE.get_or_init(ServerIdManager::new)
 }
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+pub fn set_app_handle(handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(handle);
+}
+
+/// 向前端发送事件；应用尚未初始化时忽略
+pub fn emit<S: serde::Serialize + Clone>(event: &str, payload: S) {
+    use tauri::Emitter;
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit(event, payload);
+    }
+}
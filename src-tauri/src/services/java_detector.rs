@@ -43,7 +43,7 @@ pub fn detect_java_installations() -> Vec<JavaInfo> {
         }
     }
 
-    results.sort_by(|a, b| b.major_version.cmp(&a.major_version));
+    results.sort_by_key(|java| std::cmp::Reverse(java.major_version));
     results
 }
 
@@ -165,7 +165,9 @@ fn check_java(path: &str) -> Option<JavaInfo> {
     let major_version = parse_major_version(&version);
     let is_64bit = combined.contains("64-Bit") || combined.contains("64-bit");
 
-    let vendor = if combined.to_lowercase().contains("zulu") {
+    let vendor = if combined.to_lowercase().contains("graalvm") {
+        "GraalVM".to_string()
+    } else if combined.to_lowercase().contains("zulu") {
         "Zulu".to_string()
     } else if combined.to_lowercase().contains("openjdk") {
         "OpenJDK".to_string()
@@ -0,0 +1,541 @@
+use serde_json::{Map, Value};
+
+use crate::models::config::ConfigNode;
+
+/// JSON5 配置编辑器：修改时只替换对应值的文本，注释、尾逗号与缩进保持不变
+#[derive(Debug, Clone)]
+pub struct Json5Document {
+    text: String,
+}
+
+#[derive(Debug)]
+struct Spanned {
+    start: usize,
+    end: usize,
+    node: Node,
+}
+
+#[derive(Debug)]
+enum Node {
+    Object(Vec<Member>),
+    Array(Vec<Spanned>),
+    Scalar(Value),
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    key_quoted: bool,
+    value: Spanned,
+}
+
+impl Json5Document {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        parse_root(content)?;
+        Ok(Self { text: content.to_string() })
+    }
+
+    pub fn to_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get(&self, path: &[String]) -> Option<Value> {
+        let root = parse_root(&self.text).ok()?;
+        find(&root, path).map(to_value)
+    }
+
+    /// 所有叶子节点，非空对象会继续展开
+    pub fn list(&self) -> Vec<ConfigNode> {
+        let mut nodes = Vec::new();
+        if let Ok(root) = parse_root(&self.text) {
+            self.collect(&root, &mut Vec::new(), None, &mut nodes);
+        }
+        nodes
+    }
+
+    pub fn set(&mut self, path: &[String], value: &Value) -> Result<(), String> {
+        let root = parse_root(&self.text)?;
+        let replacement = serde_json::to_string(value).map_err(|e| e.to_string())?;
+        let mut current = &root;
+        for (depth, key) in path.iter().enumerate() {
+            let next = match &current.node {
+                Node::Object(members) => match members.iter().find(|m| &m.key == key) {
+                    Some(member) => &member.value,
+                    None => {
+                        let nested = nest(&path[depth + 1..], value.clone());
+                        let (at, text) = self.member_insertion(current, members, key, &nested)?;
+                        self.text.insert_str(at, &text);
+                        return Ok(());
+                    }
+                },
+                Node::Array(items) => key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| items.get(i))
+                    .ok_or_else(|| format!("{} 不存在", path[..=depth].join(".")))?,
+                Node::Scalar(_) => {
+                    return Err(format!("{} 不是对象，无法添加子项", path[..depth].join(".")));
+                }
+            };
+            current = next;
+        }
+        self.text
+            .replace_range(current.start..current.end, &replacement);
+        Ok(())
+    }
+
+    fn collect(
+        &self,
+        spanned: &Spanned,
+        path: &mut Vec<String>,
+        comment: Option<String>,
+        nodes: &mut Vec<ConfigNode>,
+    ) {
+        match &spanned.node {
+            Node::Object(members) if !members.is_empty() => {
+                for member in members {
+                    path.push(member.key.clone());
+                    let comment = leading_comment(&self.text, member.key_start);
+                    self.collect(&member.value, path, comment, nodes);
+                    path.pop();
+                }
+            }
+            _ => nodes.push(ConfigNode {
+                path: path.clone(),
+                value: to_value(spanned),
+                comment,
+            }),
+        }
+    }
+
+    /// 新成员的插入位置与文本，沿用最后一个成员的缩进与引号风格
+    fn member_insertion(
+        &self,
+        object: &Spanned,
+        members: &[Member],
+        key: &str,
+        value: &Value,
+    ) -> Result<(usize, String), String> {
+        let line_ending = if self.text.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let value = serde_json::to_string(value).map_err(|e| e.to_string())?;
+        let last_quoted = match members.last() {
+            Some(m) => m.key_quoted,
+            None => true,
+        };
+        let quoted = last_quoted || !is_identifier(key);
+        let key = if quoted {
+            serde_json::to_string(key).map_err(|e| e.to_string())?
+        } else {
+            key.to_string()
+        };
+
+        let Some(last) = members.last() else {
+            let indent = line_indent(&self.text, object.start);
+            let inner = &self.text[object.start + 1..object.end - 1];
+            let text = format!("{le}{indent}  {key}: {value}{le}{indent}", le = line_ending);
+            if inner.trim().is_empty() {
+                return Ok((object.start + 1, text));
+            }
+            return Ok((object.start + 1, format!("{},", text.trim_end_matches(' '))));
+        };
+
+        let indent = line_indent(&self.text, last.key_start);
+        let bytes = self.text.as_bytes();
+        let mut pos = last.value.end;
+        while pos < bytes.len() && (bytes[pos] == b' ' || bytes[pos] == b'\t') {
+            pos += 1;
+        }
+        if bytes.get(pos) != Some(&b',') {
+            let text = format!(",{}{}{}: {}", line_ending, indent, key, value);
+            return Ok((last.value.end, text));
+        }
+        // 已有尾逗号：插入到该行末尾（跳过行尾注释），并保留尾逗号风格
+        let line_end = self.text[pos..]
+            .find(['\r', '\n'])
+            .map_or(self.text.len(), |i| pos + i);
+        let rest = self.text[pos + 1..line_end].trim();
+        let at = if rest.is_empty() || rest.starts_with("//") {
+            line_end
+        } else {
+            pos + 1
+        };
+        Ok((at, format!("{}{}{}: {},", line_ending, indent, key, value)))
+    }
+}
+
+fn find<'a>(root: &'a Spanned, path: &[String]) -> Option<&'a Spanned> {
+    let mut current = root;
+    for key in path {
+        current = match &current.node {
+            Node::Object(members) => &members.iter().find(|m| &m.key == key)?.value,
+            Node::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            Node::Scalar(_) => return None,
+        };
+    }
+    Some(current)
+}
+
+fn to_value(spanned: &Spanned) -> Value {
+    match &spanned.node {
+        Node::Object(members) => {
+            let map: Map<String, Value> = members
+                .iter()
+                .map(|m| (m.key.clone(), to_value(&m.value)))
+                .collect();
+            Value::Object(map)
+        }
+        Node::Array(items) => Value::Array(items.iter().map(to_value).collect()),
+        Node::Scalar(value) => value.clone(),
+    }
+}
+
+/// ["a", "b"] + v -> {"a": {"b": v}}，用于一次性插入缺失的父节点
+fn nest(path: &[String], value: Value) -> Value {
+    path.iter().rev().fold(value, |inner, key| {
+        let mut map = Map::new();
+        map.insert(key.clone(), inner);
+        Value::Object(map)
+    })
+}
+
+fn is_identifier(key: &str) -> bool {
+    key.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn line_indent(text: &str, pos: usize) -> String {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    text[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// 键上方紧邻的 // 或 /* */ 注释
+fn leading_comment(text: &str, key_start: usize) -> Option<String> {
+    let line_start = text[..key_start].rfind('\n').map_or(0, |i| i + 1);
+    if !text[line_start..key_start].trim().is_empty() {
+        return None;
+    }
+    let mut comments = Vec::new();
+    let mut in_block = false;
+    for line in text[..line_start].lines().rev() {
+        let t = line.trim();
+        if in_block || t.ends_with("*/") {
+            let body = t.trim_end_matches("*/");
+            in_block = !body.contains("/*");
+            let body = body.rsplit("/*").next().unwrap_or(body);
+            let body = body.trim().trim_start_matches('*').trim();
+            if !body.is_empty() {
+                comments.push(body.to_string());
+            }
+        } else if let Some(body) = t.strip_prefix("//") {
+            comments.push(body.trim().to_string());
+        } else {
+            break;
+        }
+    }
+    if comments.is_empty() {
+        return None;
+    }
+    comments.reverse();
+    Some(comments.join("\n"))
+}
+
+fn parse_root(text: &str) -> Result<Spanned, String> {
+    let mut parser = Parser { text, bytes: text.as_bytes(), pos: 0 };
+    if text.starts_with('\u{feff}') {
+        parser.pos = '\u{feff}'.len_utf8();
+    }
+    parser.skip_trivia()?;
+    let root = parser.value()?;
+    parser.skip_trivia()?;
+    if parser.pos < parser.bytes.len() {
+        return Err(parser.error("多余的内容"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos.min(self.text.len())]
+            .matches('\n')
+            .count()
+            + 1;
+        format!("JSON5 解析失败（第 {} 行）: {}", line, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// 跳过空白与注释
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c) => self.pos += 1,
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'/') => {
+                    while self.peek().is_some_and(|b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+                    let end = self.text[self.pos + 2..]
+                        .find("*/")
+                        .ok_or_else(|| self.error("注释未闭合"))?;
+                    self.pos += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Spanned, String> {
+        let start = self.pos;
+        let node = match self.peek() {
+            Some(b'{') => self.object()?,
+            Some(b'[') => self.array()?,
+            Some(q @ (b'"' | b'\'')) => Node::Scalar(Value::String(self.string(q)?)),
+            Some(_) => Node::Scalar(self.literal()?),
+            None => return Err(self.error("缺少值")),
+        };
+        Ok(Spanned { start, end: self.pos, node })
+    }
+
+    fn object(&mut self) -> Result<Node, String> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Node::Object(members));
+            }
+            let key_start = self.pos;
+            let (key, key_quoted) = match self.peek() {
+                Some(q @ (b'"' | b'\'')) => (self.string(q)?, true),
+                Some(_) => (self.identifier()?, false),
+                None => return Err(self.error("对象未闭合")),
+            };
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(self.error("缺少冒号"));
+            }
+            self.pos += 1;
+            self.skip_trivia()?;
+            let value = self.value()?;
+            members.push(Member { key, key_start, key_quoted, value });
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("缺少逗号或右花括号")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Node, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Node::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("缺少逗号或右方括号")),
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while let Some(c) = self.text[self.pos..].chars().next() {
+            if c.is_whitespace() || c == ':' || c == '/' {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        if start == self.pos {
+            return Err(self.error("缺少键名"));
+        }
+        Ok(self.text[start..self.pos].to_string())
+    }
+
+    fn string(&mut self, quote: u8) -> Result<String, String> {
+        self.pos += 1;
+        let mut result = String::new();
+        loop {
+            let c = self.text[self.pos..]
+                .chars()
+                .next()
+                .ok_or_else(|| self.error("字符串未闭合"))?;
+            self.pos += c.len_utf8();
+            if c as u32 == quote as u32 {
+                return Ok(result);
+            }
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            let escaped = self.text[self.pos..]
+                .chars()
+                .next()
+                .ok_or_else(|| self.error("字符串未闭合"))?;
+            self.pos += escaped.len_utf8();
+            match escaped {
+                'b' => result.push('\u{8}'),
+                'f' => result.push('\u{c}'),
+                'n' => result.push('\n'),
+                'r' => result.push('\r'),
+                't' => result.push('\t'),
+                'v' => result.push('\u{b}'),
+                '0' => result.push('\0'),
+                'x' | 'u' => {
+                    let len = if escaped == 'x' { 2 } else { 4 };
+                    let hex = self.text.get(self.pos..self.pos + len).unwrap_or("");
+                    let mut code =
+                        u32::from_str_radix(hex, 16).map_err(|_| self.error("无效的转义"))?;
+                    self.pos += len;
+                    // 代理对
+                    if (0xD800..0xDC00).contains(&code) && self.text[self.pos..].starts_with("\\u")
+                    {
+                        let low = self.text.get(self.pos + 2..self.pos + 6).unwrap_or("");
+                        if let Ok(low) = u32::from_str_radix(low, 16) {
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            self.pos += 6;
+                        }
+                    }
+                    result.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                }
+                // 行尾的反斜杠表示续行
+                '\r' => {
+                    if self.peek() == Some(b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                '\n' | '\u{2028}' | '\u{2029}' => {}
+                other => result.push(other),
+            }
+        }
+    }
+
+    fn literal(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'+' | b'-' | b'_'))
+        {
+            self.pos += 1;
+        }
+        let token = &self.text[start..self.pos];
+        match token {
+            "true" => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            "null" => return Ok(Value::Null),
+            // JSON 无法表示，按原文保留
+            "Infinity" | "+Infinity" | "-Infinity" | "NaN" | "+NaN" | "-NaN" => {
+                return Ok(Value::String(token.to_string()))
+            }
+            _ => {}
+        }
+        let (negative, digits) = match token.as_bytes().first() {
+            Some(b'-') => (true, &token[1..]),
+            Some(b'+') => (false, &token[1..]),
+            _ => (false, token),
+        };
+        if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            let n = i64::from_str_radix(hex, 16).map_err(|_| self.error("无效的数字"))?;
+            return Ok(Value::from(if negative { -n } else { n }));
+        }
+        let normalized = format!(
+            "{}{}{}",
+            if negative { "-" } else { "" },
+            if digits.starts_with('.') { "0" } else { "" },
+            digits.trim_end_matches('.')
+        );
+        if let Ok(n) = normalized.parse::<i64>() {
+            if !digits.ends_with('.') {
+                return Ok(Value::from(n));
+            }
+        }
+        normalized
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| self.error(&format!("无法识别的值 {}", token)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SAMPLE: &str = "{\n\
+        \x20 // Enable the feature\n\
+        \x20 enabled: true,\n\
+        \x20 /* Spawn weight */\n\
+        \x20 weight: .5,\n\
+        \x20 'name': 'it\\'s',\n\
+        \x20 limits: { max: 0x10, list: [1, 2,], },\n\
+        }\n";
+
+    fn path(p: &str) -> Vec<String> {
+        p.split('.').map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_json5_syntax() {
+        let doc = Json5Document::parse(SAMPLE).unwrap();
+        assert_eq!(doc.get(&path("enabled")), Some(json!(true)));
+        assert_eq!(doc.get(&path("weight")), Some(json!(0.5)));
+        assert_eq!(doc.get(&path("name")), Some(json!("it's")));
+        assert_eq!(doc.get(&path("limits.max")), Some(json!(16)));
+        assert_eq!(doc.get(&path("limits.list.1")), Some(json!(2)));
+
+        let nodes = doc.list();
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0].comment.as_deref(), Some("Enable the feature"));
+        assert_eq!(nodes[1].comment.as_deref(), Some("Spawn weight"));
+        assert!(Json5Document::parse("{ a: 1 b: 2 }").is_err());
+    }
+
+    #[test]
+    fn writes_preserving_comments() {
+        let mut doc = Json5Document::parse(SAMPLE).unwrap();
+        doc.set(&path("enabled"), &json!(false)).unwrap();
+        doc.set(&path("limits.max"), &json!(32)).unwrap();
+        doc.set(&path("extra.level"), &json!("high")).unwrap();
+        let text = doc.to_text();
+        assert!(text.contains("  // Enable the feature\n  enabled: false,\n"));
+        assert!(text
+            .contains("limits: { max: 32, list: [1, 2,], },\n  extra: {\"level\":\"high\"},\n}"));
+        let reparsed = Json5Document::parse(&text).unwrap();
+        assert_eq!(reparsed.get(&path("extra.level")), Some(json!("high")));
+    }
+}
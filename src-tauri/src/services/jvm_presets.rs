@@ -0,0 +1,254 @@
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::models::server::JvmPresetInfo;
+
+/// 超过该堆大小时 Aikar 参数使用大内存变体
+const LARGE_HEAP_MB: u32 = 12 * 1024;
+/// 低于该堆大小时低内存预设改用 SerialGC
+const TINY_HEAP_MB: u32 = 1024;
+
+/// 每个 Java 可识别的 -XX 参数名，按 Java 路径缓存
+static SUPPORTED_FLAGS: Lazy<Mutex<HashMap<String, HashSet<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub const PRESETS: &[(&str, &str, &str)] = &[
+    (
+        "aikar",
+        "Aikar's Flags",
+        "适合大多数服务器的 G1 参数，12 GB 以上堆内存自动使用大内存变体",
+    ),
+    ("zgc", "ZGC", "低停顿的 ZGC，Java 21 起使用分代模式，适合大内存服务器"),
+    ("low_memory", "低内存", "减少 JVM 自身开销，适合 2 GB 以下的小型服务器"),
+    (
+        "graalvm",
+        "GraalVM",
+        "为 GraalVM 优化的 JIT 与 G1 参数，其他 JVM 会忽略不支持的部分",
+    ),
+];
+
+pub fn list() -> Vec<JvmPresetInfo> {
+    PRESETS
+        .iter()
+        .map(|(id, name, description)| JvmPresetInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+pub fn exists(preset: &str) -> bool {
+    PRESETS.iter().any(|(id, _, _)| *id == preset)
+}
+
+/// 根据堆大小与 Java 主版本生成预设参数；JVMCI 参数只在 GraalVM 上启用
+pub fn flags(
+    preset: &str,
+    max_memory: u32,
+    java_major: u32,
+    graalvm: bool,
+) -> Result<Vec<String>, String> {
+    let flags: Vec<&str> = match preset {
+        "aikar" => aikar(max_memory),
+        "zgc" => {
+            let mut flags = vec!["-XX:+UseZGC", "-XX:+AlwaysPreTouch", "-XX:+DisableExplicitGC"];
+            if java_major < 15 {
+                flags.insert(0, "-XX:+UnlockExperimentalVMOptions");
+            }
+            // Java 23 起分代模式为默认，24 起参数被移除
+            if (21..23).contains(&java_major) {
+                flags.push("-XX:+ZGenerational");
+            }
+            flags.push("-XX:+PerfDisableSharedMem");
+            flags
+        }
+        "low_memory" => {
+            let gc: &[&str] = if max_memory <= TINY_HEAP_MB {
+                &["-XX:+UseSerialGC"]
+            } else {
+                &["-XX:+UseG1GC", "-XX:MaxGCPauseMillis=100"]
+            };
+            let mut flags = gc.to_vec();
+            flags.extend([
+                "-XX:+UseStringDeduplication",
+                "-XX:+DisableExplicitGC",
+                "-XX:ReservedCodeCacheSize=64M",
+                "-XX:MaxMetaspaceExpansion=16M",
+                "-Xss512K",
+            ]);
+            flags
+        }
+        "graalvm" => {
+            let mut flags = vec![
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+UnlockDiagnosticVMOptions",
+                "-XX:+AlwaysActAsServerClassMachine",
+                "-XX:+UseNUMA",
+                "-XX:AllocatePrefetchStyle=3",
+                "-XX:ReservedCodeCacheSize=400M",
+                "-XX:NonNMethodCodeHeapSize=12M",
+                "-XX:ProfiledCodeHeapSize=194M",
+                "-XX:NonProfiledCodeHeapSize=194M",
+                "-XX:-DontCompileHugeMethods",
+                "-XX:+UseFastUnorderedTimeStamps",
+                "-XX:+UseCriticalJavaThreadPriority",
+            ];
+            if graalvm {
+                flags.extend(["-XX:+EagerJVMCI", "-Dgraal.TuneInlinerExploration=1"]);
+            }
+            let g1: Vec<&str> = aikar(max_memory)
+                .into_iter()
+                .filter(|f| !flags.contains(f))
+                .collect();
+            flags.extend(g1);
+            flags
+        }
+        _ => return Err(format!("未知的 JVM 预设: {}", preset)),
+    };
+    Ok(flags.into_iter().map(String::from).collect())
+}
+
+fn aikar(max_memory: u32) -> Vec<&'static str> {
+    let sized: [&str; 5] = if max_memory > LARGE_HEAP_MB {
+        [
+            "-XX:G1NewSizePercent=40",
+            "-XX:G1MaxNewSizePercent=50",
+            "-XX:G1HeapRegionSize=16M",
+            "-XX:G1ReservePercent=15",
+            "-XX:InitiatingHeapOccupancyPercent=20",
+        ]
+    } else {
+        [
+            "-XX:G1NewSizePercent=30",
+            "-XX:G1MaxNewSizePercent=40",
+            "-XX:G1HeapRegionSize=8M",
+            "-XX:G1ReservePercent=20",
+            "-XX:InitiatingHeapOccupancyPercent=15",
+        ]
+    };
+    let mut flags = vec![
+        "-XX:+UseG1GC",
+        "-XX:+ParallelRefProcEnabled",
+        "-XX:MaxGCPauseMillis=200",
+        "-XX:+UnlockExperimentalVMOptions",
+        "-XX:+DisableExplicitGC",
+        "-XX:+AlwaysPreTouch",
+    ];
+    flags.extend(sized);
+    flags.extend([
+        "-XX:G1HeapWastePercent=5",
+        "-XX:G1MixedGCCountTarget=4",
+        "-XX:G1MixedGCLiveThresholdPercent=90",
+        "-XX:G1RSetUpdatingPauseTimePercent=5",
+        "-XX:SurvivorRatio=32",
+        "-XX:+PerfDisableSharedMem",
+        "-XX:MaxTenuringThreshold=1",
+        "-Dusing.aikars.flags=https://mcflags.emc.gs",
+        "-Daikars.new.flags=true",
+    ]);
+    flags
+}
+
+/// 去掉 Java 不支持的 -XX 参数，返回保留与去掉的参数；无法查询时全部保留
+pub fn retain_supported(java_path: &str, flags: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let Some(supported) = supported_flags(java_path) else {
+        return (flags, Vec::new());
+    };
+    flags
+        .into_iter()
+        .partition(|flag| !matches!(flag_name(flag), Some(name) if !supported.contains(name)))
+}
+
+/// -XX:+Name、-XX:-Name、-XX:Name=value 中的 Name；其他参数返回 None
+fn flag_name(flag: &str) -> Option<&str> {
+    let option = flag.strip_prefix("-XX:")?;
+    let option = option.trim_start_matches(['+', '-']);
+    Some(option.split('=').next().unwrap_or(option))
+}
+
+fn supported_flags(java_path: &str) -> Option<HashSet<String>> {
+    if let Some(flags) = SUPPORTED_FLAGS.lock().unwrap().get(java_path) {
+        return Some(flags.clone());
+    }
+
+    let mut cmd = Command::new(java_path);
+    cmd.args([
+        "-XX:+UnlockExperimentalVMOptions",
+        "-XX:+UnlockDiagnosticVMOptions",
+        "-XX:+PrintFlagsFinal",
+        "-version",
+    ]);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok()?;
+    let flags = parse_flags_final(&String::from_utf8_lossy(&output.stdout));
+    if flags.is_empty() {
+        return None;
+    }
+    SUPPORTED_FLAGS
+        .lock()
+        .unwrap()
+        .insert(java_path.to_string(), flags.clone());
+    Some(flags)
+}
+
+/// -XX:+PrintFlagsFinal 每行为 "类型 名称 = 值 {种类}"
+fn parse_flags_final(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter(|line| line.contains('='))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapts_presets_to_heap_and_java_version() {
+        let small = flags("aikar", 8192, 21, false).unwrap();
+        assert!(small.contains(&"-XX:G1HeapRegionSize=8M".to_string()));
+        let large = flags("aikar", 16384, 21, false).unwrap();
+        assert!(large.contains(&"-XX:G1HeapRegionSize=16M".to_string()));
+
+        assert!(flags("zgc", 16384, 21, false)
+            .unwrap()
+            .contains(&"-XX:+ZGenerational".to_string()));
+        assert!(!flags("zgc", 16384, 24, false)
+            .unwrap()
+            .contains(&"-XX:+ZGenerational".to_string()));
+        assert!(flags("low_memory", 1024, 17, false)
+            .unwrap()
+            .contains(&"-XX:+UseSerialGC".to_string()));
+        assert!(flags("unknown", 1024, 17, false).is_err());
+
+        let graal = flags("graalvm", 8192, 21, true).unwrap();
+        assert!(graal.contains(&"-XX:+EagerJVMCI".to_string()));
+        let unique: HashSet<&String> = graal.iter().collect();
+        assert_eq!(unique.len(), graal.len());
+    }
+
+    #[test]
+    fn parses_supported_flags() {
+        let output = "[Global flags]\n\
+             bool UseZGC                                   = false                                     {product} {default}\n\
+            uintx G1HeapRegionSize                         = 0                                         {product} {default}\n\
+        openjdk version \"17.0.9\"";
+        let supported = parse_flags_final(output);
+        assert_eq!(supported.len(), 2);
+        assert!(supported.contains("UseZGC"));
+
+        assert_eq!(flag_name("-XX:+UseZGC"), Some("UseZGC"));
+        assert_eq!(flag_name("-XX:G1HeapRegionSize=16M"), Some("G1HeapRegionSize"));
+        assert_eq!(flag_name("-Dusing.aikars.flags=true"), None);
+    }
+}
@@ -0,0 +1,408 @@
+use reqwest::Client;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::http;
+
+const MOJANG_VERSION_MANIFEST: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+const FABRIC_META: &str = "https://meta.fabricmc.net/v2";
+const QUILT_META: &str = "https://meta.quiltmc.org/v3";
+const FORGE_MAVEN: &str = "https://maven.minecraftforge.net/net/minecraftforge/forge";
+const NEOFORGE_MAVEN: &str = "https://maven.neoforged.net/releases/net/neoforged/neoforge";
+
+/// 安装好的服务端核心，字段可直接填入 ServerInstance
+#[derive(Debug, Clone)]
+pub struct InstalledCore {
+    pub core_type: String,
+    pub core_version: String,
+    pub jar_path: PathBuf,
+    /// "jar" 或 Forge/NeoForge 生成的启动脚本对应的 "bat" / "sh"
+    pub startup_mode: String,
+}
+
+/// (加载器名称, 加载器版本)，名称为 fabric / quilt / forge / neoforge
+pub type LoaderSpec = (&'static str, String);
+
+/// 在 server_dir 中安装指定 Minecraft 版本的服务端，
+/// loader 为 fabric / quilt / forge / neoforge，None 时安装原版服务端
+pub async fn install_server_core(
+    server_dir: &Path,
+    mc_version: &str,
+    loader: Option<(&str, &str)>,
+    java_path: &str,
+) -> Result<InstalledCore, String> {
+    let client = http::client();
+    fs::create_dir_all(server_dir).map_err(|e| format!("无法创建服务器目录: {}", e))?;
+
+    match loader {
+        None => install_vanilla(&client, server_dir, mc_version).await,
+        Some(("fabric", version)) => install_fabric(&client, server_dir, mc_version, version).await,
+        Some(("quilt", version)) => {
+            install_quilt(&client, server_dir, mc_version, version, java_path).await
+        }
+        Some(("forge", version)) => {
+            let coordinate = format!("{}-{}", mc_version, version);
+            let url = format!("{}/{}/forge-{}-installer.jar", FORGE_MAVEN, coordinate, coordinate);
+            run_forge_installer(&client, server_dir, "forge", version, &url, java_path).await
+        }
+        Some(("neoforge", version)) => {
+            let url = format!("{}/{}/neoforge-{}-installer.jar", NEOFORGE_MAVEN, version, version);
+            run_forge_installer(&client, server_dir, "neoforge", version, &url, java_path).await
+        }
+        Some((other, _)) => Err(format!("不支持的模组加载器: {}", other)),
+    }
+}
+
+/// 下载文件到 target，先写入 .part 再重命名，避免留下半截文件
+pub async fn download_file(client: &Client, url: &str, target: &Path) -> Result<(), String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("无法创建目录: {}", e))?;
+    }
+    let part = PathBuf::from(format!("{}.part", target.display()));
+    fs::write(&part, &bytes).map_err(|e| format!("写入 {} 失败: {}", part.display(), e))?;
+    fs::rename(&part, target).map_err(|e| {
+        let _ = fs::remove_file(&part);
+        format!("写入 {} 失败: {}", target.display(), e)
+    })
+}
+
+async fn install_vanilla(
+    client: &Client,
+    server_dir: &Path,
+    mc_version: &str,
+) -> Result<InstalledCore, String> {
+    let manifest: VersionManifest = get_json(client, MOJANG_VERSION_MANIFEST).await?;
+    let entry = manifest
+        .versions
+        .into_iter()
+        .find(|v| v.id == mc_version)
+        .ok_or_else(|| format!("未知的 Minecraft 版本: {}", mc_version))?;
+    let version: VersionDetail = get_json(client, &entry.url).await?;
+    let server = version
+        .downloads
+        .server
+        .ok_or_else(|| format!("Minecraft {} 没有提供服务端", mc_version))?;
+
+    let jar_path = server_dir.join("server.jar");
+    download_file(client, &server.url, &jar_path).await?;
+    Ok(InstalledCore {
+        core_type: "vanilla".to_string(),
+        core_version: mc_version.to_string(),
+        jar_path,
+        startup_mode: "jar".to_string(),
+    })
+}
+
+/// Fabric 提供可直接运行的服务端启动 jar，无需执行安装器
+async fn install_fabric(
+    client: &Client,
+    server_dir: &Path,
+    mc_version: &str,
+    loader_version: &str,
+) -> Result<InstalledCore, String> {
+    let installers: Vec<FabricInstaller> =
+        get_json(client, &format!("{}/versions/installer", FABRIC_META)).await?;
+    let installer = installers
+        .iter()
+        .find(|i| i.stable)
+        .or(installers.first())
+        .ok_or_else(|| "无法获取 Fabric 安装器版本".to_string())?;
+
+    let url = format!(
+        "{}/versions/loader/{}/{}/{}/server/jar",
+        FABRIC_META, mc_version, loader_version, installer.version
+    );
+    let jar_path = server_dir.join("fabric-server-launch.jar");
+    download_file(client, &url, &jar_path).await?;
+    Ok(InstalledCore {
+        core_type: "fabric".to_string(),
+        core_version: loader_version.to_string(),
+        jar_path,
+        startup_mode: "jar".to_string(),
+    })
+}
+
+async fn install_quilt(
+    client: &Client,
+    server_dir: &Path,
+    mc_version: &str,
+    loader_version: &str,
+    java_path: &str,
+) -> Result<InstalledCore, String> {
+    let installers: Vec<QuiltInstaller> =
+        get_json(client, &format!("{}/versions/installer", QUILT_META)).await?;
+    let installer = installers
+        .first()
+        .ok_or_else(|| "无法获取 Quilt 安装器版本".to_string())?;
+
+    let installer_path = server_dir.join("quilt-installer.jar");
+    download_file(client, &installer.url, &installer_path).await?;
+    let args = vec![
+        "-jar".to_string(),
+        "quilt-installer.jar".to_string(),
+        "install".to_string(),
+        "server".to_string(),
+        mc_version.to_string(),
+        loader_version.to_string(),
+        "--download-server".to_string(),
+        "--install-dir=.".to_string(),
+    ];
+    let (java, dir) = (java_path.to_string(), server_dir.to_path_buf());
+    let result = tokio::task::spawn_blocking(move || run_java(&java, &dir, &args))
+        .await
+        .map_err(|e| format!("安装任务失败: {}", e))?;
+    let _ = fs::remove_file(&installer_path);
+    result?;
+
+    let jar_path = server_dir.join("quilt-server-launch.jar");
+    if !jar_path.exists() {
+        return Err("Quilt 安装器未生成 quilt-server-launch.jar".to_string());
+    }
+    Ok(InstalledCore {
+        core_type: "quilt".to_string(),
+        core_version: loader_version.to_string(),
+        jar_path,
+        startup_mode: "jar".to_string(),
+    })
+}
+
+async fn run_forge_installer(
+    client: &Client,
+    server_dir: &Path,
+    core_type: &str,
+    loader_version: &str,
+    installer_url: &str,
+    java_path: &str,
+) -> Result<InstalledCore, String> {
+    let installer_path = server_dir.join(format!("{}-installer.jar", core_type));
+    download_file(client, installer_url, &installer_path).await?;
+    let (dir, core_type, loader_version, java_path) = (
+        server_dir.to_path_buf(),
+        core_type.to_string(),
+        loader_version.to_string(),
+        java_path.to_string(),
+    );
+    tokio::task::spawn_blocking(move || {
+        run_installer_jar(&dir, &installer_path, &core_type, &loader_version, &java_path)
+    })
+    .await
+    .map_err(|e| format!("安装任务失败: {}", e))?
+}
+
+/// 以 --installServer 运行 Forge / NeoForge 安装器并删除安装器；
+/// 新版本生成 run.bat / run.sh，旧版本生成 forge-*.jar
+pub fn run_installer_jar(
+    server_dir: &Path,
+    installer_path: &Path,
+    core_type: &str,
+    loader_version: &str,
+    java_path: &str,
+) -> Result<InstalledCore, String> {
+    let installer_name = installer_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let result = run_java(
+        java_path,
+        server_dir,
+        &["-jar".to_string(), installer_name.clone(), "--installServer".to_string()],
+    );
+    let _ = fs::remove_file(installer_path);
+    let _ = fs::remove_file(server_dir.join(format!("{}.log", installer_name)));
+    result?;
+
+    let (jar_path, startup_mode) = match find_run_script(server_dir) {
+        Some((script, mode)) => (script, mode),
+        None => (find_legacy_forge_jar(server_dir, core_type)?, "jar"),
+    };
+    Ok(InstalledCore {
+        core_type: core_type.to_string(),
+        core_version: loader_version.to_string(),
+        jar_path,
+        startup_mode: startup_mode.to_string(),
+    })
+}
+
+/// 识别自带安装器或启动脚本的服务端包（如 startserver.sh + forge-installer.jar）：
+/// 有安装器且尚未安装时先运行安装器，然后使用生成的 run 脚本；
+/// 只有启动脚本时直接使用该脚本。不是服务端包时返回 None
+pub fn prepare_server_pack(
+    server_dir: &Path,
+    java_path: &str,
+) -> Result<Option<InstalledCore>, String> {
+    if let Some((installer, core_type)) = find_bundled_installer(server_dir) {
+        if find_run_script(server_dir).is_none() {
+            println!("检测到服务端包安装器，正在安装: {}", installer.display());
+            let version = installer_version(&installer, core_type);
+            return run_installer_jar(server_dir, &installer, core_type, &version, java_path)
+                .map(Some);
+        }
+        let _ = fs::remove_file(&installer);
+    }
+
+    let script = find_run_script(server_dir).or_else(|| {
+        let (names, mode): (&[&str], &str) = if cfg!(target_os = "windows") {
+            (&["startserver.bat", "ServerStart.bat", "start.bat"], "bat")
+        } else {
+            (&["startserver.sh", "ServerStart.sh", "start.sh"], "sh")
+        };
+        names
+            .iter()
+            .map(|name| server_dir.join(name))
+            .find(|p| p.is_file())
+            .map(|p| (p, mode))
+    });
+    Ok(script.map(|(jar_path, mode)| InstalledCore {
+        core_type: "modpack".to_string(),
+        core_version: String::new(),
+        jar_path,
+        startup_mode: mode.to_string(),
+    }))
+}
+
+fn find_run_script(server_dir: &Path) -> Option<(PathBuf, &'static str)> {
+    let (script, mode) = if cfg!(target_os = "windows") {
+        ("run.bat", "bat")
+    } else {
+        ("run.sh", "sh")
+    };
+    let path = server_dir.join(script);
+    path.is_file().then_some((path, mode))
+}
+
+fn find_bundled_installer(server_dir: &Path) -> Option<(PathBuf, &'static str)> {
+    let entries = fs::read_dir(server_dir).ok()?;
+    entries.flatten().map(|e| e.path()).find_map(|p| {
+        let name = p.file_name()?.to_string_lossy().to_lowercase();
+        if !name.ends_with(".jar") || !name.contains("installer") {
+            return None;
+        }
+        if name.starts_with("neoforge") {
+            Some((p, "neoforge"))
+        } else if name.starts_with("forge") {
+            Some((p, "forge"))
+        } else {
+            None
+        }
+    })
+}
+
+/// forge-1.20.1-47.2.0-installer.jar -> 47.2.0，neoforge-20.4.237-installer.jar -> 20.4.237
+fn installer_version(installer: &Path, core_type: &str) -> String {
+    installer
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .and_then(|name| {
+            let middle = name
+                .strip_prefix(&format!("{}-", core_type))?
+                .strip_suffix("-installer.jar")?
+                .to_string();
+            middle.rsplit('-').next().map(|v| v.to_string())
+        })
+        .unwrap_or_default()
+}
+
+fn find_legacy_forge_jar(server_dir: &Path, core_type: &str) -> Result<PathBuf, String> {
+    let entries = fs::read_dir(server_dir).map_err(|e| format!("无法读取服务器目录: {}", e))?;
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| {
+            let name = p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            name.starts_with(&format!("{}-", core_type))
+                && name.ends_with(".jar")
+                && !name.contains("installer")
+        })
+        .ok_or_else(|| format!("{} 安装器未生成启动脚本或服务端 jar", core_type))
+}
+
+fn run_java(java_path: &str, work_dir: &Path, args: &[String]) -> Result<(), String> {
+    let mut cmd = Command::new(java_path);
+    cmd.args(args).current_dir(work_dir);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| format!("无法运行 Java ({}): {}", java_path, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let tail: Vec<&str> = stderr.lines().chain(stdout.lines()).rev().take(5).collect();
+    Err(format!(
+        "安装器执行失败 ({}): {}",
+        output.status,
+        tail.into_iter().rev().collect::<Vec<_>>().join(" | ")
+    ))
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(client: &Client, url: &str) -> Result<T, String> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("请求 {} 失败: {}", url, e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析 {} 失败: {}", url, e))
+}
+
+#[derive(Deserialize)]
+struct VersionManifest {
+    versions: Vec<VersionManifestEntry>,
+}
+
+#[derive(Deserialize)]
+struct VersionManifestEntry {
+    id: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct VersionDetail {
+    downloads: VersionDownloads,
+}
+
+#[derive(Deserialize)]
+struct VersionDownloads {
+    server: Option<VersionDownload>,
+}
+
+#[derive(Deserialize)]
+struct VersionDownload {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct FabricInstaller {
+    version: String,
+    stable: bool,
+}
+
+#[derive(Deserialize)]
+struct QuiltInstaller {
+    url: String,
+}
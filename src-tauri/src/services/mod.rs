@@ -1,10 +1,32 @@
+pub mod attached_server;
+pub mod ban_manager;
+pub mod config_editor;
+pub mod config_history;
 pub mod config_parser;
+pub mod console_response;
+pub mod crash_analyzer;
+pub mod curseforge;
 pub mod global;
 pub mod java_detector;
 pub mod java_installer;
 pub mod join_manager;
+pub mod json5_document;
+pub mod jvm_presets;
+pub mod loader_installer;
+pub mod mod_inventory;
 pub mod mod_manager;
+pub mod mod_metadata;
+pub mod modpack_export;
+pub mod mrpack;
+pub mod path_guard;
 pub mod player_manager;
+pub mod plugin_market;
+pub mod port_manager;
+pub mod preflight;
+pub mod properties_schema;
+pub mod rcon;
 pub mod server_id_manager;
 pub mod server_manager;
+pub mod server_ping;
 pub mod settings_manager;
+pub mod yaml_document;
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::mod_metadata::{self, ModMetadata};
+
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// mods/ 或 plugins/ 目录中的一个 jar，禁用的 jar 以 .jar.disabled 结尾
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledModEntry {
+    /// 不含 .disabled 后缀的文件名
+    pub file_name: String,
+    pub enabled: bool,
+    pub size: u64,
+    pub metadata: Option<ModMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModToggleResult {
+    pub file_name: String,
+    pub enabled: bool,
+    pub warnings: Vec<String>,
+}
+
+/// 根据类型返回服务器下的 mods 或 plugins 目录
+pub fn content_dir(server_path: &str, kind: &str) -> Result<PathBuf, String> {
+    match kind {
+        "mods" | "plugins" => Ok(Path::new(server_path).join(kind)),
+        _ => Err(format!("未知的类型: {}", kind)),
+    }
+}
+
+/// 列出目录中所有启用和禁用的 jar
+pub fn list_installed(dir: &Path) -> Result<Vec<InstalledModEntry>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir).map_err(|e| format!("无法读取目录: {}", e))?;
+
+    let mut result = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let (file_name, enabled) = match name.strip_suffix(DISABLED_SUFFIX) {
+            Some(base) if base.ends_with(".jar") => (base.to_string(), false),
+            None if name.ends_with(".jar") => (name.clone(), true),
+            _ => continue,
+        };
+        result.push(InstalledModEntry {
+            file_name,
+            enabled,
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            metadata: mod_metadata::read_jar_metadata(&path),
+        });
+    }
+    result.sort_by_key(|e| e.file_name.to_lowercase());
+    Ok(result)
+}
+
+/// 启用或禁用一个 jar；禁用时提示仍依赖它的模组，启用时提示缺失的前置
+pub fn set_enabled(dir: &Path, file_name: &str, enabled: bool) -> Result<ModToggleResult, String> {
+    let inventory = list_installed(dir)?;
+    let entry = inventory
+        .iter()
+        .find(|e| e.file_name == file_name)
+        .ok_or_else(|| format!("未找到文件: {}", file_name))?;
+
+    let mut warnings = Vec::new();
+    if entry.enabled != enabled {
+        rename_jar(dir, file_name, enabled)?;
+    }
+
+    if enabled {
+        warnings.extend(missing_dependencies(&inventory, entry));
+    } else {
+        let dependents = find_dependents(&inventory, entry);
+        if !dependents.is_empty() {
+            warnings.push(format!("以下模组依赖 {}: {}", file_name, dependents.join(", ")));
+        }
+    }
+
+    Ok(ModToggleResult {
+        file_name: file_name.to_string(),
+        enabled,
+        warnings,
+    })
+}
+
+/// 只启用 enabled_jars 中的 jar，其余全部禁用，返回提示信息
+pub fn apply_enabled_set(dir: &Path, enabled_jars: &[String]) -> Result<Vec<String>, String> {
+    let inventory = list_installed(dir)?;
+    let wanted: HashSet<&str> = enabled_jars.iter().map(|s| s.as_str()).collect();
+
+    for entry in &inventory {
+        let should_enable = wanted.contains(entry.file_name.as_str());
+        if entry.enabled != should_enable {
+            rename_jar(dir, &entry.file_name, should_enable)?;
+        }
+    }
+
+    let mut warnings: Vec<String> = enabled_jars
+        .iter()
+        .filter(|jar| !inventory.iter().any(|e| &e.file_name == *jar))
+        .map(|jar| format!("文件不存在，已跳过: {}", jar))
+        .collect();
+    let after = list_installed(dir)?;
+    for entry in after.iter().filter(|e| e.enabled) {
+        warnings.extend(missing_dependencies(&after, entry));
+    }
+    Ok(warnings)
+}
+
+/// 当前启用的 jar 文件名
+pub fn enabled_jars(dir: &Path) -> Result<Vec<String>, String> {
+    Ok(list_installed(dir)?
+        .into_iter()
+        .filter(|e| e.enabled)
+        .map(|e| e.file_name)
+        .collect())
+}
+
+fn rename_jar(dir: &Path, file_name: &str, enable: bool) -> Result<(), String> {
+    if Path::new(file_name).components().count() != 1 {
+        return Err(format!("非法文件名: {}", file_name));
+    }
+    let active = dir.join(file_name);
+    let disabled = dir.join(format!("{}{}", file_name, DISABLED_SUFFIX));
+    let (from, to) = if enable {
+        (disabled, active)
+    } else {
+        (active, disabled)
+    };
+    if to.exists() {
+        return Err(format!("目标文件已存在: {}", to.display()));
+    }
+    fs::rename(&from, &to).map_err(|e| format!("重命名 {} 失败: {}", file_name, e))
+}
+
+/// 已启用且声明依赖 target 的模组
+fn find_dependents(inventory: &[InstalledModEntry], target: &InstalledModEntry) -> Vec<String> {
+    let Some(target_ids) = target.metadata.as_ref().map(|m| &m.ids) else {
+        return Vec::new();
+    };
+    inventory
+        .iter()
+        .filter(|e| e.enabled && e.file_name != target.file_name)
+        .filter(|e| {
+            e.metadata
+                .as_ref()
+                .is_some_and(|m| m.depends.iter().any(|d| target_ids.contains(d)))
+        })
+        .map(|e| e.file_name.clone())
+        .collect()
+}
+
+/// entry 声明的前置中，当前没有启用的那些
+fn missing_dependencies(inventory: &[InstalledModEntry], entry: &InstalledModEntry) -> Vec<String> {
+    let Some(meta) = entry.metadata.as_ref() else {
+        return Vec::new();
+    };
+    let available: HashSet<&str> = inventory
+        .iter()
+        .filter(|e| e.enabled)
+        .filter_map(|e| e.metadata.as_ref())
+        .flat_map(|m| m.ids.iter().map(|id| id.as_str()))
+        .collect();
+    meta.depends
+        .iter()
+        .filter(|d| !available.contains(d.as_str()))
+        .map(|d| format!("{} 缺少前置: {}", entry.file_name, d))
+        .collect()
+}
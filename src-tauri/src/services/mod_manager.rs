@@ -1,7 +1,18 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::plugin_market::{self, PluginFile};
+use super::{mod_inventory, mod_metadata};
+use crate::models::server::ServerInstance;
+use crate::utils::http;
+
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModInfo {
@@ -13,18 +24,91 @@ pub struct ModInfo {
     pub source: String, // "modrinth" or "curseforge"
 }
 
+/// 一个可更新的模组：当前 jar 与 Modrinth 上的最新兼容版本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModUpdateInfo {
+    pub file_name: String,
+    pub project_id: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub latest_version_id: String,
+    pub download_url: String,
+    pub new_file_name: String,
+    /// 新文件的 SHA-512，下载后校验通过才会替换旧 jar
+    pub sha512: String,
+}
+
+/// 模组更新检查结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModUpdateReport {
+    pub updates: Vec<ModUpdateInfo>,
+    pub up_to_date: Vec<String>,
+    /// Modrinth 无法识别的 jar，只做标记，不做任何改动
+    pub unknown: Vec<String>,
+}
+
+/// 安装计划中的一个待下载文件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedModFile {
+    pub project_id: String,
+    pub title: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub file_name: String,
+    pub download_url: String,
+    pub sha512: String,
+    /// 为空表示用户直接选择安装的模组，否则为依赖它的模组 project_id
+    pub required_by: Option<String>,
+}
+
+/// 可选依赖或不兼容关系
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModRelation {
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+    pub title: String,
+    pub declared_by: String,
+    /// 对应模组是否已在 mods 目录中
+    pub installed: bool,
+}
+
+/// 安装前展示给用户确认的计划，errors 非空时不允许安装
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModInstallPlan {
+    pub to_install: Vec<PlannedModFile>,
+    pub already_installed: Vec<String>,
+    pub optional: Vec<ModRelation>,
+    pub incompatible: Vec<ModRelation>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// 批量更新结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModUpdateResult {
+    pub updated: Vec<String>,
+    pub failed: Vec<String>,
+    pub backup_dir: String,
+}
+
+/// 服务端不支持（仅客户端）的模组
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientOnlyMod {
+    pub file_name: String,
+    pub name: Option<String>,
+    /// 判定依据，例如 "fabric.mod.json" 或 "Modrinth"
+    pub source: String,
+    /// 是否已被自动禁用
+    pub disabled: bool,
+}
+
 pub struct ModManager {
     client: Client,
 }
 
 impl ModManager {
     pub fn new() -> Self {
-        ModManager {
-            client: Client::builder()
-                .user_agent("SeaLantern/0.5.0 (contact@manus.im)")
-                .build()
-                .unwrap(),
-        }
+        ModManager { client: http::client() }
     }
 
     pub async fn search_modrinth(
@@ -34,8 +118,8 @@ impl ModManager {
         loader: &str,
     ) -> Result<Vec<ModInfo>, String> {
         let url = format!(
-            "https://api.modrinth.com/v2/search?query={}&facets=[[\"versions:{}\"],[\"categories:{}\"],[\"project_type:mod\"]]",
-            query, game_version, loader.to_lowercase()
+            "{}/search?query={}&facets=[[\"versions:{}\"],[\"categories:{}\"],[\"project_type:mod\"]]",
+            MODRINTH_API, query, game_version, loader.to_lowercase()
         );
 
         let resp = self
@@ -50,7 +134,7 @@ impl ModManager {
         for hit in data.hits {
             // 获取最新版本以获取下载链接
             if let Ok(version) = self
-                .get_latest_modrinth_version(&hit.project_id, game_version, loader)
+                .get_latest_modrinth_version(&hit.project_id, game_version, &[loader])
                 .await
             {
                 results.push(ModInfo {
@@ -66,35 +150,180 @@ impl ModManager {
         Ok(results)
     }
 
-    async fn get_latest_modrinth_version(
+    /// 同时搜索 Modrinth 插件、Hangar 与 SpigotMC，loaders 由服务端核心决定
+    pub async fn search_plugins(
         &self,
-        project_id: &str,
+        query: &str,
         game_version: &str,
-        loader: &str,
-    ) -> Result<ModrinthVersionFile, String> {
-        let url = format!(
-            "https://api.modrinth.com/v2/project/{}/version?loaders=[\"{}\"]&game_versions=[\"{}\"]",
-            project_id, loader.to_lowercase(), game_version
+        loaders: &[&str],
+    ) -> Result<Vec<ModInfo>, String> {
+        let use_hangar = loaders.contains(&"paper");
+        let use_spiget = loaders.contains(&"spigot");
+        let (modrinth, hangar, spiget) = futures::join!(
+            self.search_modrinth_plugins(query, game_version, loaders),
+            async {
+                if use_hangar {
+                    plugin_market::search_hangar(&self.client, query, game_version).await
+                } else {
+                    Ok(Vec::new())
+                }
+            },
+            async {
+                if use_spiget {
+                    plugin_market::search_spiget(&self.client, query, game_version).await
+                } else {
+                    Ok(Vec::new())
+                }
+            }
         );
 
+        // 任一来源可用即返回结果，全部失败时返回第一个错误
+        let mut results = Vec::new();
+        let mut first_error = None;
+        for source in [modrinth, hangar, spiget] {
+            match source {
+                Ok(found) => results.extend(found),
+                Err(e) => {
+                    println!("[ModManager] 插件搜索来源失败: {}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if results.is_empty() => Err(e),
+            _ => Ok(results),
+        }
+    }
+
+    async fn search_modrinth_plugins(
+        &self,
+        query: &str,
+        game_version: &str,
+        loaders: &[&str],
+    ) -> Result<Vec<ModInfo>, String> {
+        let categories: Vec<String> = loaders
+            .iter()
+            .map(|l| format!("categories:{}", l))
+            .collect();
+        let facets = serde_json::json!([
+            ["project_type:plugin"],
+            [format!("versions:{}", game_version)],
+            categories,
+        ]);
         let resp = self
             .client
-            .get(&url)
+            .get(format!("{}/search", MODRINTH_API))
+            .query(&[("query", query.to_string()), ("facets", facets.to_string())])
             .send()
             .await
             .map_err(|e| e.to_string())?;
-        let versions: Vec<ModrinthVersion> = resp.json().await.map_err(|e| e.to_string())?;
+        let data: ModrinthSearchResponse = resp.json().await.map_err(|e| e.to_string())?;
+
+        // 搜索结果已按版本与加载器过滤，具体文件在安装时再解析，避免逐个查询版本列表
+        Ok(data
+            .hits
+            .into_iter()
+            .map(|hit| ModInfo {
+                id: hit.project_id,
+                name: hit.title,
+                summary: hit.description,
+                download_url: String::new(),
+                file_name: String::new(),
+                source: "modrinth".to_string(),
+            })
+            .collect())
+    }
+
+    /// 下载插件到 plugins 目录，返回文件名；已有同名文件时拒绝覆盖。
+    /// 下载地址与文件名在此时按来源与 plugin.id 重新解析，不使用前端传来的值
+    pub async fn install_plugin(
+        &self,
+        plugins_dir: &Path,
+        plugin: &ModInfo,
+        game_version: &str,
+        loaders: &[&str],
+    ) -> Result<String, String> {
+        let file = match plugin.source.as_str() {
+            "modrinth" => {
+                let version = self
+                    .get_latest_modrinth_version(&plugin.id, game_version, loaders)
+                    .await
+                    .map_err(|e| format!("{} 没有兼容的版本: {}", plugin.name, e))?;
+                PluginFile {
+                    download_url: version.url,
+                    file_name: version.filename,
+                    sha256: None,
+                    sha512: version.sha512,
+                }
+            }
+            "hangar" => plugin_market::get_hangar_download(&self.client, &plugin.id, game_version)
+                .await
+                .map_err(|e| format!("查询 {} 的版本失败: {}", plugin.name, e))?
+                .ok_or_else(|| format!("{} 没有可直接下载的兼容版本", plugin.name))?,
+            "spigot" => plugin_market::spiget_download(&plugin.id, &plugin.name)?,
+            other => return Err(format!("未知的插件来源: {}", other)),
+        };
+        let target = safe_join(plugins_dir, &file.file_name)?;
+        if !file.file_name.to_lowercase().ends_with(".jar") {
+            return Err(format!("不是 jar 文件: {}", file.file_name));
+        }
+        if target.exists()
+            || safe_join(plugins_dir, &format!("{}.disabled", file.file_name))?.exists()
+        {
+            return Err(format!("插件文件已存在: {}", file.file_name));
+        }
+
+        let bytes = self
+            .client
+            .get(&file.download_url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("下载 {} 失败: {}", plugin.name, e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("下载 {} 失败: {}", plugin.name, e))?;
+        // 部分来源在资源失效时会返回网页而不是 jar
+        if !bytes.starts_with(b"PK") {
+            return Err(format!("{} 的下载内容不是有效的 jar 文件", plugin.name));
+        }
+        let mismatched = |expected: &Option<String>, actual: String| {
+            expected
+                .as_ref()
+                .is_some_and(|e| !e.eq_ignore_ascii_case(&actual))
+        };
+        if mismatched(&file.sha256, to_hex(&Sha256::digest(&bytes)))
+            || mismatched(&file.sha512, to_hex(&Sha512::digest(&bytes)))
+        {
+            return Err(format!("{} 的文件校验失败", plugin.name));
+        }
+
+        fs::create_dir_all(plugins_dir).map_err(|e| e.to_string())?;
+        let temp_path = plugins_dir.join(format!("{}.part", file.file_name));
+        fs::write(&temp_path, &bytes).map_err(|e| e.to_string())?;
+        fs::rename(&temp_path, &target).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            e.to_string()
+        })?;
+        Ok(file.file_name.clone())
+    }
+
+    async fn get_latest_modrinth_version(
+        &self,
+        project_id: &str,
+        game_version: &str,
+        loaders: &[&str],
+    ) -> Result<ModrinthVersionFile, String> {
+        let versions = self
+            .get_project_versions(project_id, game_version, loaders)
+            .await?;
 
         if let Some(version) = versions.first() {
-            if let Some(file) = version
-                .files
-                .iter()
-                .find(|f| f.primary)
-                .or(version.files.first())
-            {
+            if let Some(file) = primary_file(version) {
                 return Ok(ModrinthVersionFile {
                     url: file.url.clone(),
                     filename: file.filename.clone(),
+                    sha512: file.hashes.get("sha512").cloned(),
                 });
             }
         }
@@ -107,6 +336,7 @@ impl ModManager {
             .get(download_url)
             .send()
             .await
+            .and_then(|r| r.error_for_status())
             .map_err(|e| e.to_string())?;
         let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
 
@@ -117,6 +347,768 @@ impl ModManager {
         fs::write(target_path, bytes).map_err(|e| e.to_string())?;
         Ok(())
     }
+
+    /// 通过 SHA-512 识别 mods 目录下的所有 jar（未命中的再用 SHA-1），并查询兼容当前版本与加载器的最新版本
+    pub async fn check_updates(
+        &self,
+        mods_dir: &Path,
+        game_version: &str,
+        loader: &str,
+    ) -> Result<ModUpdateReport, String> {
+        let hashes = hash_mod_jars(mods_dir)?;
+        let mut report = ModUpdateReport {
+            updates: Vec::new(),
+            up_to_date: Vec::new(),
+            unknown: Vec::new(),
+        };
+        if hashes.is_empty() {
+            return Ok(report);
+        }
+
+        let all_hashes: Vec<String> = hashes.iter().map(|(_, h)| h.clone()).collect();
+        let mut current = self.get_versions_by_hashes(&all_hashes, "sha512").await?;
+        let mut latest = self
+            .get_latest_versions_by_hashes(&all_hashes, "sha512", game_version, loader)
+            .await?;
+
+        // 部分旧文件只登记了 SHA-1，结果按 SHA-512 归并
+        let unmatched = hashes
+            .iter()
+            .filter(|(_, hash)| !current.contains_key(hash))
+            .map(|(file_name, hash)| Ok((hash_file_sha1(&mods_dir.join(file_name))?, hash.clone())))
+            .collect::<Result<Vec<(String, String)>, String>>()?;
+        if !unmatched.is_empty() {
+            let sha1s: Vec<String> = unmatched.iter().map(|(sha1, _)| sha1.clone()).collect();
+            let mut current_sha1 = self.get_versions_by_hashes(&sha1s, "sha1").await?;
+            let mut latest_sha1 = self
+                .get_latest_versions_by_hashes(&sha1s, "sha1", game_version, loader)
+                .await?;
+            for (sha1, sha512) in unmatched {
+                if let Some(version) = current_sha1.remove(&sha1) {
+                    current.insert(sha512.clone(), version);
+                }
+                if let Some(version) = latest_sha1.remove(&sha1) {
+                    latest.insert(sha512, version);
+                }
+            }
+        }
+
+        for (file_name, hash) in hashes {
+            let Some(installed) = current.get(&hash) else {
+                report.unknown.push(file_name);
+                continue;
+            };
+            match latest.get(&hash) {
+                Some(newest) if newest.id != installed.id => {
+                    if let Some(file) = primary_file(newest) {
+                        report.updates.push(ModUpdateInfo {
+                            file_name,
+                            project_id: installed.project_id.clone(),
+                            current_version: installed.version_number.clone(),
+                            latest_version: newest.version_number.clone(),
+                            latest_version_id: newest.id.clone(),
+                            download_url: file.url.clone(),
+                            new_file_name: file.filename.clone(),
+                            sha512: file.hashes.get("sha512").cloned().unwrap_or_default(),
+                        });
+                    } else {
+                        report.up_to_date.push(file_name);
+                    }
+                }
+                _ => report.up_to_date.push(file_name),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 批量更新模组，替换前先把旧 jar 备份到 mods_backup/<时间戳>/
+    pub async fn apply_updates(
+        &self,
+        mods_dir: &Path,
+        updates: &[ModUpdateInfo],
+    ) -> Result<ModUpdateResult, String> {
+        let backup_root = mods_dir
+            .parent()
+            .unwrap_or(mods_dir)
+            .join("mods_backup")
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+        fs::create_dir_all(&backup_root).map_err(|e| format!("无法创建备份目录: {}", e))?;
+
+        let mut result = ModUpdateResult {
+            updated: Vec::new(),
+            failed: Vec::new(),
+            backup_dir: backup_root.to_string_lossy().to_string(),
+        };
+
+        for update in updates {
+            match self
+                .apply_single_update(mods_dir, &backup_root, update)
+                .await
+            {
+                Ok(()) => result.updated.push(update.file_name.clone()),
+                Err(e) => result.failed.push(format!("{}: {}", update.file_name, e)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn apply_single_update(
+        &self,
+        mods_dir: &Path,
+        backup_root: &Path,
+        update: &ModUpdateInfo,
+    ) -> Result<(), String> {
+        let old_path = safe_join(mods_dir, &update.file_name)?;
+        safe_join(mods_dir, &update.new_file_name)?;
+        if !old_path.is_file() {
+            return Err("原文件不存在".to_string());
+        }
+        if update.sha512.is_empty() {
+            return Err("缺少新文件的 SHA-512，无法校验".to_string());
+        }
+
+        // 先下载到临时文件，校验通过后再替换，避免留下半截 jar
+        let temp_path = mods_dir.join(format!("{}.part", update.new_file_name));
+        if let Err(e) = self.download_mod(&update.download_url, &temp_path).await {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        replace_mod_file(mods_dir, backup_root, update, &temp_path)
+    }
+
+    /// 解析模组及其必需依赖，生成安装计划，不写入任何文件
+    pub async fn plan_install(
+        &self,
+        mods_dir: &Path,
+        project_id: &str,
+        version_id: Option<&str>,
+        game_version: &str,
+        loader: &str,
+    ) -> Result<ModInstallPlan, String> {
+        let installed = self.identify_installed(mods_dir).await?;
+        let mut plan = ModInstallPlan {
+            to_install: Vec::new(),
+            already_installed: Vec::new(),
+            optional: Vec::new(),
+            incompatible: Vec::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let mut queue: VecDeque<(Option<String>, Option<String>, Option<String>)> = VecDeque::new();
+        queue.push_back((Some(project_id.to_string()), version_id.map(|v| v.to_string()), None));
+        let mut visited = HashSet::new();
+
+        while let Some((dep_project, dep_version, required_by)) = queue.pop_front() {
+            let version = match self
+                .resolve_version(
+                    dep_project.as_deref(),
+                    dep_version.as_deref(),
+                    game_version,
+                    loader,
+                )
+                .await
+            {
+                Ok(version) => version,
+                Err(e) => {
+                    plan.errors.push(e);
+                    continue;
+                }
+            };
+            if !visited.insert(version.project_id.clone()) {
+                continue;
+            }
+
+            if !version.loaders.is_empty()
+                && !version
+                    .loaders
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(loader))
+            {
+                plan.errors.push(format!(
+                    "{} {} 不支持加载器 {}（支持: {}）",
+                    version.project_id,
+                    version.version_number,
+                    loader,
+                    version.loaders.join(", ")
+                ));
+                continue;
+            }
+            if !version.game_versions.is_empty()
+                && !version.game_versions.iter().any(|v| v == game_version)
+            {
+                plan.warnings.push(format!(
+                    "{} {} 未声明支持 Minecraft {}",
+                    version.project_id, version.version_number, game_version
+                ));
+            }
+
+            if let Some(file_name) = installed.get(&version.project_id) {
+                if required_by.is_none() {
+                    plan.errors.push(format!("该模组已安装: {}", file_name));
+                } else {
+                    plan.already_installed.push(file_name.clone());
+                }
+                continue;
+            }
+
+            let Some(file) = primary_file(&version) else {
+                plan.errors
+                    .push(format!("{} 没有可下载的文件", version.project_id));
+                continue;
+            };
+            plan.to_install.push(PlannedModFile {
+                project_id: version.project_id.clone(),
+                title: String::new(),
+                version_id: version.id.clone(),
+                version_number: version.version_number.clone(),
+                file_name: file.filename.clone(),
+                download_url: file.url.clone(),
+                sha512: file.hashes.get("sha512").cloned().unwrap_or_default(),
+                required_by: required_by.clone(),
+            });
+
+            for dep in &version.dependencies {
+                let relation = || ModRelation {
+                    project_id: dep.project_id.clone(),
+                    version_id: dep.version_id.clone(),
+                    title: String::new(),
+                    declared_by: version.project_id.clone(),
+                    installed: dep
+                        .project_id
+                        .as_ref()
+                        .is_some_and(|p| installed.contains_key(p)),
+                };
+                match dep.dependency_type.as_str() {
+                    "required" => queue.push_back((
+                        dep.project_id.clone(),
+                        dep.version_id.clone(),
+                        Some(version.project_id.clone()),
+                    )),
+                    "optional" => plan.optional.push(relation()),
+                    "incompatible" => plan.incompatible.push(relation()),
+                    _ => {}
+                }
+            }
+        }
+
+        // 不兼容的模组已安装或同在本次计划中时拒绝安装
+        let planned: HashSet<&str> = plan
+            .to_install
+            .iter()
+            .map(|f| f.project_id.as_str())
+            .collect();
+        for relation in &plan.incompatible {
+            let Some(pid) = relation.project_id.as_deref() else {
+                continue;
+            };
+            if relation.installed || planned.contains(pid) {
+                plan.errors
+                    .push(format!("{} 与 {} 不兼容", relation.declared_by, pid));
+            }
+        }
+
+        self.fill_titles(&mut plan).await;
+        Ok(plan)
+    }
+
+    /// 按计划下载模组；任一文件失败时回滚本次已写入的文件
+    pub async fn install_plan(
+        &self,
+        mods_dir: &Path,
+        plan: &ModInstallPlan,
+    ) -> Result<Vec<String>, String> {
+        if !plan.errors.is_empty() {
+            return Err(format!("安装计划存在错误: {}", plan.errors.join("; ")));
+        }
+        fs::create_dir_all(mods_dir).map_err(|e| format!("无法创建 mods 目录: {}", e))?;
+
+        let mut existing_ids = mod_metadata::collect_mod_ids(mods_dir);
+        let mut written: Vec<PathBuf> = Vec::new();
+        for file in &plan.to_install {
+            match self
+                .install_planned_file(mods_dir, file, &existing_ids)
+                .await
+            {
+                Ok((path, ids)) => {
+                    for id in ids {
+                        existing_ids.insert(id, file.file_name.clone());
+                    }
+                    written.push(path);
+                }
+                Err(e) => {
+                    for path in &written {
+                        let _ = fs::remove_file(path);
+                    }
+                    return Err(format!("{}: {}", file.file_name, e));
+                }
+            }
+        }
+
+        Ok(plan
+            .to_install
+            .iter()
+            .map(|f| f.file_name.clone())
+            .collect())
+    }
+
+    async fn install_planned_file(
+        &self,
+        mods_dir: &Path,
+        file: &PlannedModFile,
+        existing_ids: &HashMap<String, String>,
+    ) -> Result<(PathBuf, Vec<String>), String> {
+        let target = safe_join(mods_dir, &file.file_name)?;
+        if target.exists() {
+            return Err("同名文件已存在".to_string());
+        }
+        if file.sha512.is_empty() {
+            return Err("缺少文件的 SHA-512，无法校验".to_string());
+        }
+
+        let temp_path = mods_dir.join(format!("{}.part", file.file_name));
+        self.download_mod(&file.download_url, &temp_path).await?;
+
+        let check = || -> Result<Vec<String>, String> {
+            if hash_file_sha512(&temp_path)? != file.sha512 {
+                return Err("文件校验失败".to_string());
+            }
+            let ids = mod_metadata::read_jar_metadata(&temp_path)
+                .map(|m| m.ids)
+                .unwrap_or_default();
+            if let Some((id, owner)) = ids
+                .iter()
+                .find_map(|id| existing_ids.get(id).map(|owner| (id, owner)))
+            {
+                return Err(format!("模组 ID {} 已由 {} 提供", id, owner));
+            }
+            Ok(ids)
+        };
+
+        match check() {
+            Ok(ids) => {
+                fs::rename(&temp_path, &target).map_err(|e| format!("写入文件失败: {}", e))?;
+                Ok((target, ids))
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// 找出 mods 目录中已启用的仅客户端模组，先看 jar 元数据，
+    /// 元数据未声明的再通过 Modrinth 项目的 server_side 判断；auto_disable 时将其改为 .jar.disabled
+    pub async fn scan_client_only(
+        &self,
+        mods_dir: &Path,
+        auto_disable: bool,
+    ) -> Result<Vec<ClientOnlyMod>, String> {
+        let mut found = Vec::new();
+        let mut undeclared = Vec::new();
+        for entry in mod_inventory::list_installed(mods_dir)? {
+            if !entry.enabled {
+                continue;
+            }
+            let name = entry.metadata.as_ref().and_then(|m| m.name.clone());
+            match entry
+                .metadata
+                .as_ref()
+                .and_then(|m| m.environment.as_deref())
+            {
+                Some("client") => found.push(ClientOnlyMod {
+                    file_name: entry.file_name,
+                    name,
+                    source: metadata_source(entry.metadata.as_ref().map(|m| m.loader.as_str())),
+                    disabled: false,
+                }),
+                Some(_) => {}
+                None => undeclared.push((entry.file_name, name)),
+            }
+        }
+
+        if !undeclared.is_empty() {
+            match self.find_server_unsupported(mods_dir, &undeclared).await {
+                Ok(files) => {
+                    for (file_name, name) in undeclared {
+                        if files.contains(&file_name) {
+                            found.push(ClientOnlyMod {
+                                file_name,
+                                name,
+                                source: "Modrinth".to_string(),
+                                disabled: false,
+                            });
+                        }
+                    }
+                }
+                Err(e) => println!("[ModManager] 查询 Modrinth 失败，仅使用本地元数据: {}", e),
+            }
+        }
+
+        if auto_disable {
+            for item in &mut found {
+                mod_inventory::set_enabled(mods_dir, &item.file_name, false)?;
+                item.disabled = true;
+            }
+        }
+        found.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        Ok(found)
+    }
+
+    /// 返回 Modrinth 上 server_side 为 unsupported 的文件名
+    async fn find_server_unsupported(
+        &self,
+        mods_dir: &Path,
+        files: &[(String, Option<String>)],
+    ) -> Result<HashSet<String>, String> {
+        let mut by_hash = HashMap::new();
+        for (file_name, _) in files {
+            by_hash.insert(hash_file_sha512(&mods_dir.join(file_name))?, file_name.clone());
+        }
+        let hashes: Vec<String> = by_hash.keys().cloned().collect();
+        let versions = self.get_versions_by_hashes(&hashes, "sha512").await?;
+
+        let mut file_by_project = HashMap::new();
+        for (hash, version) in versions {
+            if let Some(file_name) = by_hash.remove(&hash) {
+                file_by_project.insert(version.project_id, file_name);
+            }
+        }
+        let ids: Vec<String> = file_by_project.keys().cloned().collect();
+        Ok(self
+            .get_projects(&ids)
+            .await?
+            .into_iter()
+            .filter(|p| p.server_side == "unsupported")
+            .filter_map(|p| file_by_project.remove(&p.id))
+            .collect())
+    }
+
+    /// 通过 SHA-512 查找 Modrinth 上对应文件的下载地址，返回 哈希 -> 下载地址；未收录的哈希不在结果中
+    pub async fn lookup_downloads(
+        &self,
+        hashes: &[String],
+    ) -> Result<HashMap<String, String>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let versions = self.get_versions_by_hashes(hashes, "sha512").await?;
+        Ok(versions
+            .into_iter()
+            .filter_map(|(hash, version)| {
+                let file = version
+                    .files
+                    .iter()
+                    .find(|f| f.hashes.get("sha512") == Some(&hash))?;
+                Some((hash, file.url.clone()))
+            })
+            .collect())
+    }
+
+    /// 通过哈希识别已安装的模组，返回 project_id -> 文件名
+    async fn identify_installed(&self, mods_dir: &Path) -> Result<HashMap<String, String>, String> {
+        let mut by_hash: HashMap<String, String> = hash_mod_jars(mods_dir)?
+            .into_iter()
+            .map(|(file_name, hash)| (hash, file_name))
+            .collect();
+        if by_hash.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let hashes: Vec<String> = by_hash.keys().cloned().collect();
+        let versions = self.get_versions_by_hashes(&hashes, "sha512").await?;
+        Ok(versions
+            .into_iter()
+            .filter_map(|(hash, v)| by_hash.remove(&hash).map(|file| (v.project_id, file)))
+            .collect())
+    }
+
+    async fn resolve_version(
+        &self,
+        project_id: Option<&str>,
+        version_id: Option<&str>,
+        game_version: &str,
+        loader: &str,
+    ) -> Result<ModrinthVersion, String> {
+        if let Some(version_id) = version_id {
+            return self.get_version(version_id).await;
+        }
+        let project_id = project_id.ok_or("依赖未声明 project_id 或 version_id")?;
+        self.get_project_versions(project_id, game_version, &[loader])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("{} 没有适配 {} {} 的版本", project_id, loader, game_version))
+    }
+
+    async fn fill_titles(&self, plan: &mut ModInstallPlan) {
+        let ids: HashSet<String> = plan
+            .to_install
+            .iter()
+            .map(|f| f.project_id.clone())
+            .chain(
+                plan.optional
+                    .iter()
+                    .chain(plan.incompatible.iter())
+                    .filter_map(|r| r.project_id.clone()),
+            )
+            .collect();
+        let Ok(projects) = self
+            .get_projects(&ids.into_iter().collect::<Vec<_>>())
+            .await
+        else {
+            return;
+        };
+        let titles: HashMap<String, String> =
+            projects.into_iter().map(|p| (p.id, p.title)).collect();
+
+        let title_of = |id: &str| titles.get(id).cloned().unwrap_or_else(|| id.to_string());
+        for file in &mut plan.to_install {
+            file.title = title_of(&file.project_id);
+        }
+        for relation in plan.optional.iter_mut().chain(plan.incompatible.iter_mut()) {
+            relation.title = relation
+                .project_id
+                .as_deref()
+                .map(title_of)
+                .unwrap_or_default();
+        }
+    }
+
+    async fn get_project_versions(
+        &self,
+        project_id: &str,
+        game_version: &str,
+        loaders: &[&str],
+    ) -> Result<Vec<ModrinthVersion>, String> {
+        let loaders: Vec<String> = loaders.iter().map(|l| l.to_lowercase()).collect();
+        let loaders = serde_json::to_string(&loaders).map_err(|e| e.to_string())?;
+        let game_versions = serde_json::to_string(&[game_version]).map_err(|e| e.to_string())?;
+
+        let resp = self
+            .client
+            .get(format!("{}/project/{}/version", MODRINTH_API, project_id))
+            .query(&[("loaders", loaders), ("game_versions", game_versions)])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn get_version(&self, version_id: &str) -> Result<ModrinthVersion, String> {
+        let resp = self
+            .client
+            .get(format!("{}/version/{}", MODRINTH_API, version_id))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn get_projects(&self, ids: &[String]) -> Result<Vec<ModrinthProject>, String> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids_json = serde_json::to_string(ids).map_err(|e| e.to_string())?;
+        let resp = self
+            .client
+            .get(format!("{}/projects", MODRINTH_API))
+            .query(&[("ids", ids_json)])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn get_versions_by_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        let resp = self
+            .client
+            .post(format!("{}/version_files", MODRINTH_API))
+            .json(&serde_json::json!({ "hashes": hashes, "algorithm": algorithm }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn get_latest_versions_by_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        game_version: &str,
+        loader: &str,
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        let resp = self
+            .client
+            .post(format!("{}/version_files/update", MODRINTH_API))
+            .json(&serde_json::json!({
+                "hashes": hashes,
+                "algorithm": algorithm,
+                "loaders": [loader.to_lowercase()],
+                "game_versions": [game_version],
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        resp.json().await.map_err(|e| e.to_string())
+    }
+}
+
+/// 根据服务端核心类型推断 Modrinth 加载器名称
+pub fn loader_for_core_type(core_type: &str) -> Option<&'static str> {
+    let core = core_type.to_lowercase();
+    if core.contains("neoforge") {
+        Some("neoforge")
+    } else if core.contains("forge") {
+        Some("forge")
+    } else if core.contains("quilt") {
+        Some("quilt")
+    } else if core.contains("fabric") {
+        Some("fabric")
+    } else {
+        None
+    }
+}
+
+/// 导入的服务器核心类型可能为 unknown 或 modpack，此时根据启动文件名判断
+pub fn core_hint(server: &ServerInstance) -> String {
+    if server.core_type.is_empty() || server.core_type == "unknown" || server.core_type == "modpack"
+    {
+        Path::new(&server.jar_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        server.core_type.clone()
+    }
+}
+
+fn metadata_source(loader: Option<&str>) -> String {
+    match loader {
+        Some("fabric") => "fabric.mod.json",
+        Some("quilt") => "quilt.mod.json",
+        Some("neoforge") => "neoforge.mods.toml",
+        _ => "mods.toml",
+    }
+    .to_string()
+}
+
+/// 计算文件的 SHA-512（小写十六进制）
+pub fn hash_file_sha512(path: &Path) -> Result<String, String> {
+    hash_file::<Sha512>(path)
+}
+
+/// 计算文件的 SHA-1（小写十六进制）
+pub fn hash_file_sha1(path: &Path) -> Result<String, String> {
+    hash_file::<Sha1>(path)
+}
+
+fn hash_file<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("无法读取 {}: {}", path.display(), e))?;
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 列出 mods 目录下所有启用的 jar
+pub fn list_mod_jars(mods_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !mods_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(mods_dir).map_err(|e| format!("无法读取 mods 目录: {}", e))?;
+    let mut jars: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "jar"))
+        .collect();
+    jars.sort();
+    Ok(jars)
+}
+
+/// 计算 mods 目录下每个 jar 的 (文件名, SHA-512)
+fn hash_mod_jars(mods_dir: &Path) -> Result<Vec<(String, String)>, String> {
+    list_mod_jars(mods_dir)?
+        .into_iter()
+        .map(|jar| {
+            let file_name = jar
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Ok((file_name, hash_file_sha512(&jar)?))
+        })
+        .collect()
+}
+
+/// 校验下载好的临时文件并替换旧 jar，旧 jar 先备份到 backup_root；
+/// 校验失败时删除临时文件，替换失败时从备份恢复旧 jar
+fn replace_mod_file(
+    mods_dir: &Path,
+    backup_root: &Path,
+    update: &ModUpdateInfo,
+    temp_path: &Path,
+) -> Result<(), String> {
+    let old_path = safe_join(mods_dir, &update.file_name)?;
+    let new_path = safe_join(mods_dir, &update.new_file_name)?;
+    let discard = |e: String| {
+        let _ = fs::remove_file(temp_path);
+        e
+    };
+
+    if hash_file_sha512(temp_path).map_err(discard)? != update.sha512 {
+        return Err(discard("文件校验失败，已保留旧版本".to_string()));
+    }
+
+    let backup_path = backup_root.join(&update.file_name);
+    fs::copy(&old_path, &backup_path).map_err(|e| discard(format!("备份失败: {}", e)))?;
+    fs::remove_file(&old_path).map_err(|e| discard(format!("删除旧文件失败: {}", e)))?;
+    if let Err(e) = fs::rename(temp_path, &new_path) {
+        let _ = fs::remove_file(temp_path);
+        return Err(match fs::copy(&backup_path, &old_path) {
+            Ok(_) => format!("替换文件失败，已恢复旧版本: {}", e),
+            Err(restore) => format!(
+                "替换文件失败: {}；恢复旧版本也失败: {}，请从 {} 手动恢复",
+                e,
+                restore,
+                backup_path.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// 拼接文件名到目录下，拒绝带路径分隔符的文件名
+fn safe_join(dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    let name = Path::new(file_name);
+    if file_name.is_empty() || name.components().count() != 1 || name.file_name().is_none() {
+        return Err(format!("非法文件名: {}", file_name));
+    }
+    Ok(dir.join(name))
+}
+
+fn primary_file(version: &ModrinthVersion) -> Option<&ModrinthFile> {
+    version
+        .files
+        .iter()
+        .find(|f| f.primary)
+        .or(version.files.first())
 }
 
 #[derive(Deserialize)]
@@ -133,17 +1125,105 @@ struct ModrinthHit {
 
 #[derive(Deserialize)]
 struct ModrinthVersion {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    project_id: String,
+    #[serde(default)]
+    version_number: String,
+    #[serde(default)]
+    loaders: Vec<String>,
+    #[serde(default)]
+    game_versions: Vec<String>,
+    #[serde(default)]
+    dependencies: Vec<ModrinthDependency>,
     files: Vec<ModrinthFile>,
 }
 
+#[derive(Deserialize)]
+struct ModrinthDependency {
+    version_id: Option<String>,
+    project_id: Option<String>,
+    dependency_type: String,
+}
+
 #[derive(Deserialize)]
 struct ModrinthFile {
     url: String,
     filename: String,
     primary: bool,
+    #[serde(default)]
+    hashes: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthProject {
+    id: String,
+    title: String,
+    /// required / optional / unsupported / unknown
+    #[serde(default)]
+    server_side: String,
 }
 
 struct ModrinthVersionFile {
     url: String,
     filename: String,
+    sha512: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(file_name: &str, new_file_name: &str, sha512: String) -> ModUpdateInfo {
+        ModUpdateInfo {
+            file_name: file_name.to_string(),
+            project_id: "P7dR8mSH".to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            latest_version_id: "v2".to_string(),
+            download_url: "https://cdn.modrinth.com/data/P7dR8mSH/versions/v2/mod.jar".to_string(),
+            new_file_name: new_file_name.to_string(),
+            sha512,
+        }
+    }
+
+    #[test]
+    fn replaces_verified_jar_and_rolls_back_otherwise() {
+        let root = std::env::temp_dir().join(format!("sl-mod-update-{}", uuid::Uuid::new_v4()));
+        let mods_dir = root.join("mods");
+        let backup_root = root.join("mods_backup");
+        fs::create_dir_all(&mods_dir).unwrap();
+        fs::create_dir_all(&backup_root).unwrap();
+        let old_jar = mods_dir.join("mod-1.0.0.jar");
+        let temp = mods_dir.join("mod-1.1.0.jar.part");
+        fs::write(&old_jar, b"PK old").unwrap();
+
+        // 校验失败：保留旧 jar，删除下载内容
+        fs::write(&temp, b"<html>rate limited</html>").unwrap();
+        let mismatch = update("mod-1.0.0.jar", "mod-1.1.0.jar", "0".repeat(128));
+        assert!(replace_mod_file(&mods_dir, &backup_root, &mismatch, &temp).is_err());
+        assert_eq!(fs::read(&old_jar).unwrap(), b"PK old");
+        assert!(!temp.exists());
+
+        // 替换失败：从备份恢复旧 jar
+        fs::write(&temp, b"PK new").unwrap();
+        let blocked = mods_dir.join("blocked.jar");
+        fs::create_dir_all(blocked.join("inner")).unwrap();
+        let failing = update("mod-1.0.0.jar", "blocked.jar", hash_file_sha512(&temp).unwrap());
+        assert!(replace_mod_file(&mods_dir, &backup_root, &failing, &temp).is_err());
+        assert_eq!(fs::read(&old_jar).unwrap(), b"PK old");
+        assert!(!temp.exists());
+
+        // 校验通过：旧 jar 进入备份，新 jar 就位
+        fs::write(&temp, b"PK new").unwrap();
+        let verified = update("mod-1.0.0.jar", "mod-1.1.0.jar", hash_file_sha512(&temp).unwrap());
+        replace_mod_file(&mods_dir, &backup_root, &verified, &temp).unwrap();
+        assert!(!old_jar.exists());
+        assert!(!temp.exists());
+        assert_eq!(fs::read(mods_dir.join("mod-1.1.0.jar")).unwrap(), b"PK new");
+        assert_eq!(fs::read(backup_root.join("mod-1.0.0.jar")).unwrap(), b"PK old");
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
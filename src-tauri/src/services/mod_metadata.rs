@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+/// 从模组 jar 内读取的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModMetadata {
+    /// "fabric"、"quilt"、"forge"、"neoforge" 或 "bukkit"
+    pub loader: String,
+    pub ids: Vec<String>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// 必需的前置模组/插件 ID（不含 minecraft、java 和加载器本身）
+    pub depends: Vec<String>,
+    /// "client"、"server" 或 "*"，未声明时为 None
+    pub environment: Option<String>,
+}
+
+/// 这些依赖由游戏或加载器本身提供，不算作前置模组
+const BUILTIN_DEPENDENCIES: &[&str] =
+    &["minecraft", "java", "fabricloader", "quilt_loader", "forge", "neoforge"];
+
+/// 读取 jar 中的 fabric.mod.json / quilt.mod.json / mods.toml / plugin.yml，无法识别时返回 None
+pub fn read_jar_metadata(jar_path: &Path) -> Option<ModMetadata> {
+    let file = fs::File::open(jar_path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+
+    if let Some(content) = read_entry(&mut archive, "fabric.mod.json") {
+        return parse_fabric_mod_json(&content);
+    }
+    if let Some(content) = read_entry(&mut archive, "quilt.mod.json") {
+        return parse_quilt_mod_json(&content);
+    }
+    if let Some(content) = read_entry(&mut archive, "META-INF/neoforge.mods.toml") {
+        return parse_mods_toml(&content, "neoforge");
+    }
+    if let Some(content) = read_entry(&mut archive, "META-INF/mods.toml") {
+        return parse_mods_toml(&content, "forge");
+    }
+    if let Some(content) = read_entry(&mut archive, "paper-plugin.yml")
+        .or_else(|| read_entry(&mut archive, "plugin.yml"))
+    {
+        return parse_plugin_yml(&content);
+    }
+    None
+}
+
+/// 收集目录下所有 jar 声明的模组 ID，返回 模组ID -> 文件名
+pub fn collect_mod_ids(mods_dir: &Path) -> HashMap<String, String> {
+    let mut ids = HashMap::new();
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return ids;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_jar = path.is_file() && path.extension().is_some_and(|ext| ext == "jar");
+        if !is_jar {
+            continue;
+        }
+        if let Some(meta) = read_jar_metadata(&path) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            for id in meta.ids {
+                ids.insert(id, file_name.clone());
+            }
+        }
+    }
+    ids
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+fn parse_fabric_mod_json(content: &str) -> Option<ModMetadata> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let id = json["id"].as_str()?.to_string();
+    let depends = json["depends"]
+        .as_object()
+        .map(|deps| deps.keys().cloned().collect())
+        .unwrap_or_default();
+    Some(ModMetadata {
+        loader: "fabric".to_string(),
+        ids: vec![id],
+        name: json["name"].as_str().map(|s| s.to_string()),
+        version: json["version"].as_str().map(|s| s.to_string()),
+        depends: filter_builtin(depends),
+        environment: json["environment"].as_str().map(|s| s.to_string()),
+    })
+}
+
+fn parse_quilt_mod_json(content: &str) -> Option<ModMetadata> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let loader = &json["quilt_loader"];
+    let id = loader["id"].as_str()?.to_string();
+    // depends 中的元素既可以是字符串，也可以是 {"id": ...} 对象
+    let depends = loader["depends"]
+        .as_array()
+        .map(|deps| {
+            deps.iter()
+                .filter_map(|d| d.as_str().or_else(|| d["id"].as_str()))
+                .map(|d| d.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(ModMetadata {
+        loader: "quilt".to_string(),
+        ids: vec![id],
+        name: loader["metadata"]["name"].as_str().map(|s| s.to_string()),
+        version: loader["version"].as_str().map(|s| s.to_string()),
+        depends: filter_builtin(depends),
+        environment: json["minecraft"]["environment"]
+            .as_str()
+            .map(|env| match env {
+                "dedicated_server" => "server".to_string(),
+                other => other.to_string(),
+            }),
+    })
+}
+
+/// mods.toml 读取 [[mods]] 表中的 modId / displayName / version，
+/// 以及 [[dependencies.*]] 表中 mandatory=true 或 type="required" 的依赖。
+/// 顶层 clientSideOnly=true 或对 minecraft/加载器的依赖声明 side="CLIENT" 视为仅客户端
+fn parse_mods_toml(content: &str, loader: &str) -> Option<ModMetadata> {
+    let doc: DocumentMut = content.parse().ok()?;
+    let text = |item: Option<&Item>| item.and_then(Item::as_str).map(|s| s.to_string());
+
+    let mut ids = Vec::new();
+    let mut name = None;
+    let mut version = None;
+    for table in toml_tables(doc.get("mods")?) {
+        ids.extend(text(table.get("modId")));
+        name = name.or_else(|| text(table.get("displayName")));
+        version = version.or_else(|| text(table.get("version")));
+    }
+    if ids.is_empty() {
+        return None;
+    }
+
+    let mut depends = Vec::new();
+    let mut client_only = doc
+        .get("clientSideOnly")
+        .and_then(Item::as_bool)
+        .unwrap_or(false);
+    let declared = doc
+        .get("dependencies")
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|deps| deps.iter().flat_map(|(_, list)| toml_tables(list)));
+    for dependency in declared {
+        let Some(mod_id) = text(dependency.get("modId")) else {
+            continue;
+        };
+        let required = dependency
+            .get("mandatory")
+            .and_then(Item::as_bool)
+            .unwrap_or(false)
+            || dependency
+                .get("type")
+                .and_then(Item::as_str)
+                .is_some_and(|t| t.eq_ignore_ascii_case("required"));
+        client_only |= BUILTIN_DEPENDENCIES.contains(&mod_id.as_str())
+            && dependency
+                .get("side")
+                .and_then(Item::as_str)
+                .is_some_and(|s| s.eq_ignore_ascii_case("CLIENT"));
+        if required && !depends.contains(&mod_id) {
+            depends.push(mod_id);
+        }
+    }
+
+    Some(ModMetadata {
+        loader: loader.to_string(),
+        ids,
+        name,
+        version,
+        depends: filter_builtin(depends),
+        environment: client_only.then(|| "client".to_string()),
+    })
+}
+
+/// [[x]] 表数组与 x = [{ ... }] 内联表数组
+fn toml_tables(item: &Item) -> Vec<&dyn TableLike> {
+    match item {
+        Item::ArrayOfTables(tables) => tables.iter().map(|t| t as &dyn TableLike).collect(),
+        Item::Value(Value::Array(array)) => array
+            .iter()
+            .filter_map(Value::as_inline_table)
+            .map(|t| t as &dyn TableLike)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// plugin.yml 只解析顶层的 name / version / depend
+fn parse_plugin_yml(content: &str) -> Option<ModMetadata> {
+    let mut name = None;
+    let mut version = None;
+    let mut depends = Vec::new();
+    let mut in_depend_list = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if indented {
+            if in_depend_list {
+                if let Some(item) = line.trim().strip_prefix('-') {
+                    depends.push(yaml_scalar(item));
+                }
+            }
+            continue;
+        }
+        in_depend_list = false;
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "name" => name = Some(yaml_scalar(value)),
+            "version" => version = Some(yaml_scalar(value)),
+            "depend" if value.is_empty() => in_depend_list = true,
+            "depend" => {
+                depends.extend(
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(yaml_scalar)
+                        .filter(|d| !d.is_empty()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let name = name?;
+    Some(ModMetadata {
+        loader: "bukkit".to_string(),
+        ids: vec![name.clone()],
+        name: Some(name),
+        version,
+        depends,
+        environment: Some("server".to_string()),
+    })
+}
+
+fn yaml_scalar(raw: &str) -> String {
+    raw.trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+fn filter_builtin(depends: Vec<String>) -> Vec<String> {
+    depends
+        .into_iter()
+        .filter(|d| !BUILTIN_DEPENDENCIES.contains(&d.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mods_toml_reads_mods_table_only() {
+        let content = r#"
+modLoader="javafml"
+loaderVersion="[47,)"
+
+[[mods]]
+modId="examplemod" # 行尾注释
+version="1.2.0"
+displayName='Example Mod'
+
+[[dependencies.examplemod]]
+modId="minecraft"
+mandatory=true
+side="BOTH"
+
+[[dependencies.examplemod]]
+modId="curios"
+type="required"
+
+[[dependencies.examplemod]]
+modId="jei"
+mandatory=false
+"#;
+        let meta = parse_mods_toml(content, "forge").unwrap();
+        assert_eq!(meta.ids, vec!["examplemod".to_string()]);
+        assert_eq!(meta.depends, vec!["curios".to_string()]);
+        assert_eq!(meta.name.as_deref(), Some("Example Mod"));
+        assert_eq!(meta.version.as_deref(), Some("1.2.0"));
+        assert_eq!(meta.environment, None);
+
+        let client = r#"
+[[mods]]
+modId="minimap"
+
+[[dependencies.minimap]]
+modId="minecraft"
+side="CLIENT"
+"#;
+        let meta = parse_mods_toml(client, "neoforge").unwrap();
+        assert_eq!(meta.environment.as_deref(), Some("client"));
+
+        // 多行字符串、内联表与写在其他表数组之后的 [[mods]]
+        let mixed = r#"
+modLoader = "javafml"
+dependencies.core = [{ modId = "minecraft", type = "required" }, { modId = "geckolib", type = "required" }]
+
+[[accessTransformers]]
+file = "META-INF/accesstransformer.cfg"
+
+[[mods]]
+modId = "mobs"
+description = """
+[[mods]]
+modId = "fake"
+"""
+"#;
+        let meta = parse_mods_toml(mixed, "neoforge").unwrap();
+        assert_eq!(meta.ids, vec!["mobs".to_string()]);
+        assert_eq!(meta.depends, vec!["geckolib".to_string()]);
+        assert!(parse_mods_toml("modId = \"broken", "forge").is_none());
+    }
+
+    #[test]
+    fn parse_fabric_and_quilt_ids() {
+        let fabric = parse_fabric_mod_json(r#"{"id":"sodium","version":"0.5.8"}"#).unwrap();
+        assert_eq!(fabric.ids, vec!["sodium".to_string()]);
+
+        let quilt =
+            parse_quilt_mod_json(r#"{"quilt_loader":{"id":"qsl","version":"7.0.0"}}"#).unwrap();
+        assert_eq!(quilt.ids, vec!["qsl".to_string()]);
+        assert_eq!(quilt.loader, "quilt");
+    }
+
+    #[test]
+    fn parse_plugin_yml_handles_both_list_styles() {
+        let inline = parse_plugin_yml("name: Shop\nversion: '2.1'\ndepend: [Vault, 'LuckPerms']\n");
+        let inline = inline.unwrap();
+        assert_eq!(inline.ids, vec!["Shop".to_string()]);
+        assert_eq!(inline.version.as_deref(), Some("2.1"));
+        assert_eq!(inline.depends, vec!["Vault".to_string(), "LuckPerms".to_string()]);
+
+        let block = parse_plugin_yml("name: Shop\ndepend:\n  - Vault\nsoftdepend: [Other]\n");
+        assert_eq!(block.unwrap().depends, vec!["Vault".to_string()]);
+    }
+}
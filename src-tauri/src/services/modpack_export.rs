@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha512};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use super::global;
+use super::mod_manager;
+use crate::models::server::ServerInstance;
+
+/// 随 .mrpack 一并打包的配置目录
+const OVERRIDE_DIRS: &[&str] = &["config", "defaultconfigs", "kubejs", "scripts"];
+/// 完整归档中描述 ServerInstance 的清单文件
+pub const ARCHIVE_MANIFEST: &str = "sealantern-server.json";
+
+/// .mrpack 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackExportResult {
+    pub path: String,
+    /// 以 Modrinth 下载地址引用的模组
+    pub downloads: Vec<String>,
+    /// 无法识别、直接打包进 overrides/ 的模组
+    pub bundled: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerArchiveManifest {
+    pub format_version: u32,
+    pub exported_at: String,
+    /// path 为 "."，jar_path 为相对服务器目录的路径
+    pub server: ServerInstance,
+}
+
+/// mods 目录中的一个 jar 及其 .mrpack 索引所需的信息
+struct HashedJar {
+    path: PathBuf,
+    sha1: String,
+    sha512: String,
+    size: u64,
+}
+
+/// 从服务器生成 .mrpack：Modrinth 收录的模组作为下载引用，其余 jar 与配置目录放入 overrides/
+pub async fn export_mrpack(
+    server: &ServerInstance,
+    target: &Path,
+    version_id: &str,
+) -> Result<ModpackExportResult, String> {
+    if server.mc_version.is_empty() || server.mc_version == "unknown" {
+        return Err("服务器未设置 Minecraft 版本，无法导出 .mrpack".to_string());
+    }
+    let mut jars = Vec::new();
+    for path in mod_manager::list_mod_jars(&Path::new(&server.path).join("mods"))? {
+        let (sha1, sha512) = hash_file(&path)?;
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        jars.push(HashedJar { path, sha1, sha512, size });
+    }
+    let dependencies = mrpack_dependencies(server, !jars.is_empty())?;
+    let hashes: Vec<String> = jars.iter().map(|jar| jar.sha512.clone()).collect();
+    let download_urls = global::mod_manager().lookup_downloads(&hashes).await?;
+    write_mrpack(server, target, version_id, &dependencies, &jars, &download_urls)
+}
+
+/// .mrpack 的 dependencies；核心类型未记录加载器时与模组页面一样根据启动文件名推断
+fn mrpack_dependencies(
+    server: &ServerInstance,
+    has_mods: bool,
+) -> Result<HashMap<String, String>, String> {
+    let mut dependencies = HashMap::new();
+    dependencies.insert("minecraft".to_string(), server.mc_version.clone());
+    match mod_manager::loader_for_core_type(&mod_manager::core_hint(server)) {
+        Some(loader) => {
+            if server.core_version.is_empty() {
+                return Err(format!("服务器未记录 {} 加载器版本，无法导出 .mrpack", loader));
+            }
+            let key = match loader {
+                "fabric" => "fabric-loader",
+                "quilt" => "quilt-loader",
+                other => other,
+            };
+            dependencies.insert(key.to_string(), server.core_version.clone());
+        }
+        None if has_mods => {
+            return Err(format!(
+                "无法从核心类型 {} 确定模组加载器，无法导出 .mrpack",
+                server.core_type
+            ));
+        }
+        None => {}
+    }
+    Ok(dependencies)
+}
+
+fn write_mrpack(
+    server: &ServerInstance,
+    target: &Path,
+    version_id: &str,
+    dependencies: &HashMap<String, String>,
+    jars: &[HashedJar],
+    download_urls: &HashMap<String, String>,
+) -> Result<ModpackExportResult, String> {
+    let server_dir = Path::new(&server.path);
+    let file = fs::File::create(target).map_err(|e| format!("无法创建导出文件: {}", e))?;
+    let mut writer = ZipWriter::new(file);
+    let mut files = Vec::new();
+    let mut result = ModpackExportResult {
+        path: target.to_string_lossy().to_string(),
+        downloads: Vec::new(),
+        bundled: Vec::new(),
+    };
+    for jar in jars {
+        let file_name = jar
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match download_urls.get(&jar.sha512) {
+            Some(url) => {
+                files.push(serde_json::json!({
+                    "path": format!("mods/{}", file_name),
+                    "hashes": { "sha1": jar.sha1, "sha512": jar.sha512 },
+                    "downloads": [url],
+                    "fileSize": jar.size,
+                }));
+                result.downloads.push(file_name);
+            }
+            None => {
+                add_path(&mut writer, &jar.path, &format!("overrides/mods/{}", file_name), target)?;
+                result.bundled.push(file_name);
+            }
+        }
+    }
+    for dir in OVERRIDE_DIRS {
+        let source = server_dir.join(dir);
+        if source.is_dir() {
+            add_path(&mut writer, &source, &format!("overrides/{}", dir), target)?;
+        }
+    }
+
+    let index = serde_json::json!({
+        "formatVersion": 1,
+        "game": "minecraft",
+        "versionId": version_id,
+        "name": server.name,
+        "files": files,
+        "dependencies": dependencies,
+    });
+    let index = serde_json::to_vec_pretty(&index).map_err(|e| e.to_string())?;
+    write_entry(&mut writer, "modrinth.index.json", &index)?;
+    writer
+        .finish()
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+    Ok(result)
+}
+
+/// 将整个服务器目录打包为 zip，并附带描述 ServerInstance 的清单
+pub fn export_archive(server: &ServerInstance, target: &Path) -> Result<String, String> {
+    let server_dir = Path::new(&server.path);
+    let mut portable = server.clone();
+    portable.path = ".".to_string();
+    portable.jar_path = Path::new(&server.jar_path)
+        .strip_prefix(server_dir)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| server.jar_path.clone());
+    let manifest = ServerArchiveManifest {
+        format_version: 1,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        server: portable,
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+
+    let file = fs::File::create(target).map_err(|e| format!("无法创建导出文件: {}", e))?;
+    let mut writer = ZipWriter::new(file);
+    let entries = fs::read_dir(server_dir).map_err(|e| format!("无法读取服务器目录: {}", e))?;
+    let mut paths: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if name == ARCHIVE_MANIFEST {
+            continue;
+        }
+        add_path(&mut writer, &path, &name, target)?;
+    }
+    write_entry(&mut writer, ARCHIVE_MANIFEST, &manifest)?;
+    writer
+        .finish()
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// 递归写入文件或目录；跳过导出文件本身、session.lock 和未完成的下载
+fn add_path(
+    writer: &mut ZipWriter<fs::File>,
+    source: &Path,
+    name: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if source == target || file_name == "session.lock" || file_name.ends_with(".part") {
+        return Ok(());
+    }
+    if source.is_dir() {
+        let entries = fs::read_dir(source)
+            .map_err(|e| format!("无法读取目录 {}: {}", source.display(), e))?;
+        let mut children: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        children.sort();
+        for child in children {
+            let child_name = child
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            add_path(writer, &child, &format!("{}/{}", name, child_name), target)?;
+        }
+        return Ok(());
+    }
+
+    let mut file =
+        fs::File::open(source).map_err(|e| format!("无法读取 {}: {}", source.display(), e))?;
+    writer
+        .start_file(name, file_options())
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    std::io::copy(&mut file, writer).map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    Ok(())
+}
+
+fn write_entry(writer: &mut ZipWriter<fs::File>, name: &str, bytes: &[u8]) -> Result<(), String> {
+    writer
+        .start_file(name, file_options())
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+fn file_options() -> FileOptions {
+    FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true)
+}
+
+/// 同时计算 SHA-1 与 SHA-512，.mrpack 索引要求两者都提供
+fn hash_file(path: &Path) -> Result<(String, String), String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("无法读取 {}: {}", path.display(), e))?;
+    let mut sha1 = Sha1::new();
+    let mut sha512 = Sha512::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if bytes_read == 0 {
+            break;
+        }
+        sha1.update(&buffer[..bytes_read]);
+        sha512.update(&buffer[..bytes_read]);
+    }
+    Ok((format!("{:x}", sha1.finalize()), format!("{:x}", sha512.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(path: &Path, core_type: &str, core_version: &str) -> ServerInstance {
+        ServerInstance {
+            name: "Pack".into(),
+            core_type: core_type.into(),
+            core_version: core_version.into(),
+            mc_version: "1.20.1".into(),
+            jar_path: path
+                .join("fabric-server-launch.jar")
+                .to_string_lossy()
+                .to_string(),
+            max_memory: 4096,
+            min_memory: 1024,
+            ..ServerInstance::for_test("pack", path)
+        }
+    }
+
+    fn entry_names(path: &Path) -> Vec<String> {
+        let archive = zip::ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+        archive.file_names().map(String::from).collect()
+    }
+
+    #[test]
+    fn derives_loader_like_the_mods_page() {
+        let dir = Path::new("/srv/pack");
+        let deps = mrpack_dependencies(&server(dir, "modpack", "0.15.11"), true).unwrap();
+        assert_eq!(deps.get("fabric-loader").map(String::as_str), Some("0.15.11"));
+        assert_eq!(deps.get("minecraft").map(String::as_str), Some("1.20.1"));
+
+        assert!(mrpack_dependencies(&server(dir, "modpack", ""), true).is_err());
+        let mut unknown = server(dir, "unknown", "");
+        unknown.jar_path = "server.jar".into();
+        assert!(mrpack_dependencies(&unknown, true).is_err());
+        assert_eq!(mrpack_dependencies(&unknown, false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn writes_index_and_skips_locks_and_partial_downloads() {
+        let root = std::env::temp_dir().join(format!("sl-export-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("server");
+        fs::create_dir_all(dir.join("mods")).unwrap();
+        fs::create_dir_all(dir.join("config")).unwrap();
+        fs::create_dir_all(dir.join("world")).unwrap();
+        fs::write(dir.join("mods/known.jar"), b"PK known").unwrap();
+        fs::write(dir.join("mods/local.jar"), b"PK local").unwrap();
+        fs::write(dir.join("config/a.toml"), "a = 1").unwrap();
+        fs::write(dir.join("config/session.lock"), "").unwrap();
+        fs::write(dir.join("config/b.toml.part"), "b =").unwrap();
+        fs::write(dir.join("world/level.dat"), b"level").unwrap();
+        fs::write(dir.join("world/session.lock"), "").unwrap();
+
+        let server = server(&dir, "fabric", "0.15.11");
+        let jars: Vec<HashedJar> = mod_manager::list_mod_jars(&dir.join("mods"))
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                let (sha1, sha512) = hash_file(&path).unwrap();
+                HashedJar {
+                    size: fs::metadata(&path).unwrap().len(),
+                    path,
+                    sha1,
+                    sha512,
+                }
+            })
+            .collect();
+        let url = "https://cdn.modrinth.com/data/AAAA/versions/BBBB/known.jar".to_string();
+        let downloads: HashMap<String, String> = [(jars[0].sha512.clone(), url.clone())]
+            .into_iter()
+            .collect();
+        let deps = mrpack_dependencies(&server, true).unwrap();
+
+        let target = root.join("pack.mrpack");
+        let result = write_mrpack(&server, &target, "1.0.0", &deps, &jars, &downloads).unwrap();
+        assert_eq!(result.downloads, vec!["known.jar"]);
+        assert_eq!(result.bundled, vec!["local.jar"]);
+
+        let names = entry_names(&target);
+        assert!(names.contains(&"overrides/mods/local.jar".to_string()));
+        assert!(names.contains(&"overrides/config/a.toml".to_string()));
+        assert!(!names
+            .iter()
+            .any(|n| n.ends_with("session.lock") || n.ends_with(".part")));
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&target).unwrap()).unwrap();
+        let index: serde_json::Value =
+            serde_json::from_reader(archive.by_name("modrinth.index.json").unwrap()).unwrap();
+        assert_eq!(index["formatVersion"], 1);
+        assert_eq!(index["dependencies"]["fabric-loader"], "0.15.11");
+        assert_eq!(index["files"][0]["path"], "mods/known.jar");
+        assert_eq!(index["files"][0]["downloads"][0], url.as_str());
+        assert_eq!(index["files"][0]["hashes"]["sha512"], jars[0].sha512.as_str());
+        assert_eq!(index["files"].as_array().unwrap().len(), 1);
+
+        let archive_path = root.join("server.zip");
+        export_archive(&server, &archive_path).unwrap();
+        let names = entry_names(&archive_path);
+        assert!(names.contains(&"world/level.dat".to_string()));
+        assert!(names.contains(&ARCHIVE_MANIFEST.to_string()));
+        assert!(!names
+            .iter()
+            .any(|n| n.ends_with("session.lock") || n.ends_with(".part")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
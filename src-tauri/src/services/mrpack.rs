@@ -0,0 +1,274 @@
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::loader_installer::{self, InstalledCore, LoaderSpec};
+use super::mod_manager;
+use super::path_guard;
+use crate::utils::http;
+
+const INDEX_FILE: &str = "modrinth.index.json";
+const CONCURRENT_DOWNLOADS: usize = 6;
+/// mrpack 规范允许的下载域名
+const ALLOWED_HOSTS: &[&str] =
+    &["cdn.modrinth.com", "github.com", "raw.githubusercontent.com", "gitlab.com"];
+
+/// .mrpack 导入结果
+#[derive(Debug, Clone)]
+pub struct MrpackImport {
+    pub name: String,
+    pub mc_version: String,
+    pub core: InstalledCore,
+    /// 因 env.server = unsupported 跳过的文件
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    #[serde(default)]
+    pub game: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub files: Vec<MrpackFile>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MrpackFile {
+    pub path: String,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+    pub env: Option<MrpackEnv>,
+    #[serde(default)]
+    pub downloads: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MrpackEnv {
+    #[serde(default)]
+    pub server: String,
+}
+
+/// 将 .mrpack 安装到 server_dir：下载服务端需要的文件并校验哈希，
+/// 依次应用 overrides/ 与 server-overrides/，最后安装 dependencies 中声明的加载器
+pub async fn install_mrpack(
+    pack_path: &Path,
+    server_dir: &Path,
+    java_path: &str,
+) -> Result<MrpackImport, String> {
+    let file = fs::File::open(pack_path).map_err(|e| format!("无法打开整合包: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("无效的 .mrpack: {}", e))?;
+    let index = read_index(&mut archive)?;
+    if index.format_version != 1 || (!index.game.is_empty() && index.game != "minecraft") {
+        return Err(format!(
+            "不支持的 .mrpack 格式: formatVersion={}, game={}",
+            index.format_version, index.game
+        ));
+    }
+    let (mc_version, loader) = loader_from_dependencies(&index.dependencies)?;
+
+    fs::create_dir_all(server_dir).map_err(|e| format!("无法创建服务器目录: {}", e))?;
+    let mut skipped = Vec::new();
+    let mut wanted = Vec::new();
+    for file in &index.files {
+        if file
+            .env
+            .as_ref()
+            .is_some_and(|env| env.server == "unsupported")
+        {
+            skipped.push(file.path.clone());
+            continue;
+        }
+        let target = path_guard::safe_relative_path(&file.path)
+            .map(|p| server_dir.join(p))
+            .ok_or_else(|| format!("整合包包含非法路径: {}", file.path))?;
+        wanted.push((file, target));
+    }
+
+    let client = http::client();
+    let results: Vec<Result<(), String>> = stream::iter(wanted)
+        .map(|(file, target)| {
+            let client = &client;
+            async move { download_verified(client, file, &target).await }
+        })
+        .buffer_unordered(CONCURRENT_DOWNLOADS)
+        .collect()
+        .await;
+    if let Some(err) = results.into_iter().find_map(|r| r.err()) {
+        return Err(err);
+    }
+
+    extract_zip_dir(&mut archive, "overrides/", server_dir)?;
+    extract_zip_dir(&mut archive, "server-overrides/", server_dir)?;
+
+    let loader_ref = loader
+        .as_ref()
+        .map(|(name, version)| (*name, version.as_str()));
+    let core =
+        loader_installer::install_server_core(server_dir, &mc_version, loader_ref, java_path)
+            .await?;
+
+    Ok(MrpackImport {
+        name: index.name,
+        mc_version,
+        core,
+        skipped,
+    })
+}
+
+pub fn read_index<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<MrpackIndex, String> {
+    let mut entry = archive
+        .by_name(INDEX_FILE)
+        .map_err(|_| format!("整合包中缺少 {}", INDEX_FILE))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", INDEX_FILE, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", INDEX_FILE, e))
+}
+
+/// 从 dependencies 中取出 Minecraft 版本与加载器（加载器名称与 loader_installer 一致）
+pub fn loader_from_dependencies(
+    dependencies: &HashMap<String, String>,
+) -> Result<(String, Option<LoaderSpec>), String> {
+    let mc_version = dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| "整合包未声明 Minecraft 版本".to_string())?;
+    let loader = [
+        ("neoforge", "neoforge"),
+        ("forge", "forge"),
+        ("fabric-loader", "fabric"),
+        ("quilt-loader", "quilt"),
+    ]
+    .into_iter()
+    .find_map(|(key, name)| dependencies.get(key).map(|v| (name, v.clone())));
+    Ok((mc_version, loader))
+}
+
+async fn download_verified(
+    client: &reqwest::Client,
+    file: &MrpackFile,
+    target: &Path,
+) -> Result<(), String> {
+    let expected = file
+        .hashes
+        .get("sha512")
+        .ok_or_else(|| format!("{} 缺少 sha512 校验值", file.path))?;
+    let mut last_error = format!("{} 没有可用的下载地址", file.path);
+    for url in &file.downloads {
+        if !allowed_download(url) {
+            last_error = format!("{} 的下载地址不在允许的域名内: {}", file.path, url);
+            continue;
+        }
+        if let Err(e) = loader_installer::download_file(client, url, target).await {
+            last_error = e;
+            continue;
+        }
+        let actual = mod_manager::hash_file_sha512(target)?;
+        if actual.eq_ignore_ascii_case(expected) {
+            return Ok(());
+        }
+        let _ = fs::remove_file(target);
+        last_error = format!("{} 校验失败，文件可能已损坏或被篡改", file.path);
+    }
+    Err(last_error)
+}
+
+/// 只允许通过 HTTPS 从规范列出的域名下载
+fn allowed_download(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| ALLOWED_HOSTS.contains(&host))
+    })
+}
+
+/// 将压缩包中 prefix 目录下的文件解压到 server_dir，已存在的文件会被覆盖；prefix 为空时解压全部
+pub fn extract_zip_dir<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    prefix: &str,
+    server_dir: &Path,
+) -> Result<(), String> {
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(relative) = entry.name().strip_prefix(prefix).map(|s| s.to_string()) else {
+            continue;
+        };
+        if relative.is_empty() || entry.is_dir() {
+            continue;
+        }
+        let target = path_guard::safe_relative_path(&relative)
+            .map(|p| server_dir.join(p))
+            .ok_or_else(|| format!("整合包包含非法路径: {}", entry.name()))?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("无法创建目录: {}", e))?;
+        }
+        let mut out = fs::File::create(&target)
+            .map_err(|e| format!("写入 {} 失败: {}", target.display(), e))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("写入 {} 失败: {}", target.display(), e))?;
+    }
+    Ok(())
+}
+
+/// 压缩包内所有文件都位于同一个顶层目录时返回 "目录名/"，否则返回空字符串
+pub fn common_root<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>) -> String {
+    let mut root: Option<String> = None;
+    for name in archive.file_names() {
+        let Some((first, _)) = name.split_once('/') else {
+            return String::new();
+        };
+        match &root {
+            Some(r) if r != first => return String::new(),
+            Some(_) => {}
+            None => root = Some(first.to_string()),
+        }
+    }
+    root.map(|r| format!("{}/", r)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_allows_spec_download_hosts() {
+        assert!(allowed_download("https://cdn.modrinth.com/data/AANobbMI/versions/x/sodium.jar"));
+        assert!(allowed_download("https://github.com/owner/repo/releases/download/v1/a.jar"));
+        assert!(allowed_download("https://raw.githubusercontent.com/owner/repo/main/a.jar"));
+        assert!(!allowed_download("http://cdn.modrinth.com/data/a.jar"));
+        assert!(!allowed_download("https://cdn.modrinth.com.evil.example/a.jar"));
+        assert!(!allowed_download("https://example.com/a.jar"));
+        assert!(!allowed_download("file:///etc/passwd"));
+    }
+
+    #[test]
+    fn loader_from_dependencies_picks_loader() {
+        let deps: HashMap<String, String> = [
+            ("minecraft".to_string(), "1.20.1".to_string()),
+            ("fabric-loader".to_string(), "0.15.11".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let (mc, loader) = loader_from_dependencies(&deps).unwrap();
+        assert_eq!(mc, "1.20.1");
+        assert_eq!(loader, Some(("fabric", "0.15.11".to_string())));
+
+        let vanilla: HashMap<String, String> = [("minecraft".to_string(), "1.21".to_string())]
+            .into_iter()
+            .collect();
+        assert_eq!(loader_from_dependencies(&vanilla).unwrap().1, None);
+        assert!(loader_from_dependencies(&HashMap::new()).is_err());
+    }
+}
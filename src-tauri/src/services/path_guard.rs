@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::global;
+
+/// 受管服务器的根目录（已规范化）
+pub fn server_root(server_id: &str) -> Result<PathBuf, String> {
+    let server = global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))?;
+    fs::canonicalize(&server.path).map_err(|e| format!("服务器目录不可用 {}: {}", server.path, e))
+}
+
+/// 将相对路径解析到服务器根目录下，拒绝任何指向根目录之外的路径
+pub fn resolve(server_id: &str, relative: &str) -> Result<PathBuf, String> {
+    resolve_in(&server_root(server_id)?, relative)
+}
+
+/// 在给定的根目录下解析相对路径；root 必须已规范化
+pub fn resolve_in(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = safe_relative_path(relative).ok_or_else(|| format!("非法路径: {}", relative))?;
+    ensure_within(root, &root.join(relative))
+}
+
+/// 只接受不含 .. 与绝对路径的相对路径，防止写出服务器目录
+pub fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!result.as_os_str().is_empty()).then_some(result)
+}
+
+/// 启动时会执行或读取的文件
+const STARTUP_FILES: &[&str] = &[
+    "start.sh",
+    "start.bat",
+    "run.sh",
+    "run.bat",
+    "preload.sh",
+    "preload.bat",
+    "user_jvm_args.txt",
+];
+
+/// 解析可按 key=value 写入的配置文件：只允许 .properties，拒绝启动文件
+pub fn properties_file(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let path = resolve_in(root, relative)?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if STARTUP_FILES.contains(&file_name.as_str()) {
+        return Err(format!("不能修改启动文件: {}", relative));
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("properties"))
+    {
+        return Err(format!("只能修改 .properties 文件: {}", relative));
+    }
+    Ok(path)
+}
+
+/// 规范化路径（跟随符号链接）并确认其位于 root 之内；路径可以尚不存在
+pub fn ensure_within(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let resolved = canonicalize_lenient(path)?;
+    if resolved.starts_with(root) {
+        Ok(resolved)
+    } else {
+        Err(format!("拒绝访问服务器目录之外的路径: {}", path.display()))
+    }
+}
+
+/// 校验导出文件的保存位置：绝对路径、扩展名匹配、所在目录已存在，且不在服务器目录之内
+pub fn export_target(server_id: &str, path: &str, extension: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(format!("导出路径必须是绝对路径: {}", path.display()));
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    {
+        return Err(format!("导出文件的扩展名必须是 .{}", extension));
+    }
+    let parent = path
+        .parent()
+        .filter(|p| p.is_dir())
+        .ok_or_else(|| format!("导出目录不存在: {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("无法解析路径: {}", path.display()))?;
+    let target = fs::canonicalize(parent)
+        .map_err(|e| format!("导出目录不可用 {}: {}", parent.display(), e))?
+        .join(file_name);
+    if target.is_dir() {
+        return Err(format!("导出路径是一个目录: {}", path.display()));
+    }
+    if target.starts_with(server_root(server_id)?) {
+        return Err("导出文件不能保存在服务器目录内".to_string());
+    }
+    Ok(target)
+}
+
+/// 去掉 Windows 规范化路径的 \\?\ 前缀，供资源管理器和界面显示使用
+pub fn display_path(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    match text.strip_prefix(r"\\?\") {
+        Some(rest) if !rest.starts_with("UNC\\") => rest.to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// 规范化已存在的最深祖先目录，再拼接其余部分
+fn canonicalize_lenient(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        match fs::canonicalize(existing) {
+            Ok(base) => {
+                return Ok(rest
+                    .iter()
+                    .rev()
+                    .fold(base, |acc: PathBuf, part| acc.join(part)));
+            }
+            Err(_) => {
+                let name = existing
+                    .file_name()
+                    .ok_or_else(|| format!("无法解析路径: {}", path.display()))?;
+                rest.push(name.to_os_string());
+                existing = existing
+                    .parent()
+                    .ok_or_else(|| format!("无法解析路径: {}", path.display()))?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_relative_path_rejects_escapes() {
+        assert_eq!(safe_relative_path("mods/a.jar"), Some(PathBuf::from("mods/a.jar")));
+        assert_eq!(safe_relative_path("./config/x.toml"), Some(PathBuf::from("config/x.toml")));
+        assert_eq!(safe_relative_path("../evil.jar"), None);
+        assert_eq!(safe_relative_path("mods/../../evil.jar"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path(""), None);
+    }
+
+    #[test]
+    fn rejects_paths_outside_root() {
+        let base = std::env::temp_dir().join(format!("sl-guard-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(base.join("server/config")).unwrap();
+        let root = fs::canonicalize(base.join("server")).unwrap();
+
+        assert!(resolve_in(&root, "config/a.toml")
+            .unwrap()
+            .starts_with(&root));
+        assert!(resolve_in(&root, "new/dir/file.txt")
+            .unwrap()
+            .starts_with(&root));
+        assert!(resolve_in(&root, "../outside.txt").is_err());
+        assert!(resolve_in(&root, "/etc/passwd").is_err());
+        assert!(ensure_within(&root, &base.join("outside.txt")).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&base, root.join("escape")).unwrap();
+            assert!(resolve_in(&root, "escape/outside.txt").is_err());
+        }
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn properties_file_refuses_other_files() {
+        let base = std::env::temp_dir().join(format!("sl-guard-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(base.join("config")).unwrap();
+        let root = fs::canonicalize(&base).unwrap();
+
+        assert!(properties_file(&root, "server.properties").is_ok());
+        assert!(properties_file(&root, "config/Paper.PROPERTIES").is_ok());
+        for file in ["start.sh", "run.bat", "preload.sh", "user_jvm_args.txt", "config/a.toml"] {
+            assert!(properties_file(&root, file).is_err(), "{}", file);
+        }
+        assert!(properties_file(&root, "../other.properties").is_err());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -1,4 +1,24 @@
+use chrono::{DateTime, Local};
+use md5::{Digest, Md5};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{config_parser, global};
+use crate::utils::http;
+
+/// 正版 UUID 查询结果的缓存时间
+const PROFILE_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 原版封禁列表使用的时间格式
+const BAN_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+/// 小写玩家名 -> (玩家, 查询时间)
+static PROFILE_CACHE: Lazy<Mutex<HashMap<String, (PlayerEntry, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerEntry {
@@ -30,6 +50,19 @@ pub struct OpEntry {
     pub bypasses_player_limit: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpBanEntry {
+    pub ip: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub expires: String,
+}
+
 pub fn read_whitelist(server_path: &str) -> Result<Vec<PlayerEntry>, String> {
     read_json_list(server_path, "whitelist.json")
 }
@@ -42,6 +75,267 @@ pub fn read_ops(server_path: &str) -> Result<Vec<OpEntry>, String> {
     read_json_list(server_path, "ops.json")
 }
 
+pub fn read_banned_ips(server_path: &str) -> Result<Vec<IpBanEntry>, String> {
+    read_json_list(server_path, "banned-ips.json")
+}
+
+// ---- 离线编辑名单文件 ----
+
+/// 加入白名单，已存在时返回 false
+pub fn add_to_whitelist(server_path: &str, player: &PlayerEntry) -> Result<bool, String> {
+    let entry = json!({ "uuid": player.uuid, "name": player.name });
+    edit_list(server_path, "whitelist.json", |list| insert_player(list, entry))
+}
+
+/// 从白名单移除，不存在时返回 false
+pub fn remove_from_whitelist(server_path: &str, name: &str) -> Result<bool, String> {
+    edit_list(server_path, "whitelist.json", |list| remove_player(list, name))
+}
+
+pub fn add_op(server_path: &str, player: &PlayerEntry, level: u32) -> Result<bool, String> {
+    let entry = json!({
+        "uuid": player.uuid,
+        "name": player.name,
+        "level": level,
+        "bypassesPlayerLimit": false,
+    });
+    edit_list(server_path, "ops.json", |list| insert_player(list, entry))
+}
+
+pub fn remove_op(server_path: &str, name: &str) -> Result<bool, String> {
+    edit_list(server_path, "ops.json", |list| remove_player(list, name))
+}
+
+/// 添加或覆盖玩家封禁
+pub fn add_ban(server_path: &str, ban: &BanEntry) -> Result<bool, String> {
+    let entry = serde_json::to_value(ban).map_err(|e| e.to_string())?;
+    edit_list(server_path, "banned-players.json", |list| {
+        let existed = remove_player(list, &ban.name);
+        list.push(entry);
+        !existed
+    })
+}
+
+pub fn remove_ban(server_path: &str, name: &str) -> Result<bool, String> {
+    edit_list(server_path, "banned-players.json", |list| remove_player(list, name))
+}
+
+/// 添加或覆盖 IP 封禁
+pub fn add_ip_ban(server_path: &str, ban: &IpBanEntry) -> Result<bool, String> {
+    let entry = serde_json::to_value(ban).map_err(|e| e.to_string())?;
+    edit_list(server_path, "banned-ips.json", |list| {
+        let existed = remove_ip(list, &ban.ip);
+        list.push(entry);
+        !existed
+    })
+}
+
+pub fn remove_ip_ban(server_path: &str, ip: &str) -> Result<bool, String> {
+    edit_list(server_path, "banned-ips.json", |list| remove_ip(list, ip))
+}
+
+/// 原版格式的封禁时间
+pub fn ban_time(time: DateTime<Local>) -> String {
+    time.format(BAN_TIME_FORMAT).to_string()
+}
+
+/// 新建一条永久封禁记录
+pub fn new_ban(player: &PlayerEntry, reason: &str, source: &str) -> BanEntry {
+    BanEntry {
+        uuid: player.uuid.clone(),
+        name: player.name.clone(),
+        reason: if reason.is_empty() {
+            "Banned by an operator.".to_string()
+        } else {
+            reason.to_string()
+        },
+        source: source.to_string(),
+        created: ban_time(Local::now()),
+        expires: "forever".to_string(),
+    }
+}
+
+/// server.properties 中的 op-permission-level，默认 4
+pub fn default_op_level(server_path: &str) -> u32 {
+    server_property(server_path, "op-permission-level")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(4)
+}
+
+// ---- UUID 解析 ----
+
+/// 离线模式 UUID：与 Java 的 UUID.nameUUIDFromBytes("OfflinePlayer:<name>") 一致
+pub fn offline_uuid(name: &str) -> String {
+    let digest = Md5::digest(format!("OfflinePlayer:{}", name).as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest);
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    hyphenate(&hex).unwrap()
+}
+
+/// 按服务器的 online-mode 将玩家名解析为 UUID；
+/// 正版模式依次查询 usercache.json、内存缓存与配置的档案接口
+pub async fn resolve_player(server_path: &str, name: &str) -> Result<PlayerEntry, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err(format!("无效的玩家名: {}", name));
+    }
+    if server_property(server_path, "online-mode").as_deref() == Some("false") {
+        return Ok(PlayerEntry {
+            uuid: offline_uuid(name),
+            name: name.to_string(),
+        });
+    }
+    if name.len() > 16 || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("无效的正版玩家名: {}", name));
+    }
+    if let Some(player) = lookup_usercache(server_path, name) {
+        return Ok(player);
+    }
+
+    let key = name.to_lowercase();
+    if let Some((player, fetched)) = PROFILE_CACHE.lock().unwrap().get(&key) {
+        if fetched.elapsed() < PROFILE_CACHE_TTL {
+            return Ok(player.clone());
+        }
+    }
+    let player = fetch_profile(name).await?;
+    PROFILE_CACHE
+        .lock()
+        .unwrap()
+        .insert(key, (player.clone(), Instant::now()));
+    Ok(player)
+}
+
+async fn fetch_profile(name: &str) -> Result<PlayerEntry, String> {
+    #[derive(Deserialize)]
+    struct Profile {
+        id: String,
+        name: String,
+    }
+
+    let base = global::settings_manager().get().profile_api_url;
+    let url = format!("{}/{}", base.trim_end_matches('/'), name);
+    let response = http::client()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("查询玩家 UUID 失败: {}", e))?;
+    let status = response.status();
+    if status.as_u16() == 204 || status.as_u16() == 404 {
+        return Err(format!("正版玩家 {} 不存在", name));
+    }
+    if !status.is_success() {
+        return Err(format!("查询玩家 UUID 失败: HTTP {}", status));
+    }
+    let profile: Profile = response
+        .json()
+        .await
+        .map_err(|e| format!("解析玩家档案失败: {}", e))?;
+    let uuid = hyphenate(&profile.id).ok_or_else(|| format!("无效的 UUID: {}", profile.id))?;
+    Ok(PlayerEntry { uuid, name: profile.name })
+}
+
+/// 服务器自身维护的玩家名缓存，只使用未过期的条目
+fn lookup_usercache(server_path: &str, name: &str) -> Option<PlayerEntry> {
+    #[derive(Deserialize)]
+    struct CacheEntry {
+        name: String,
+        uuid: String,
+        #[serde(rename = "expiresOn", default)]
+        expires_on: String,
+    }
+
+    let entries: Vec<CacheEntry> = read_json_list(server_path, "usercache.json").ok()?;
+    let now = Local::now();
+    entries
+        .into_iter()
+        .find(|e| {
+            e.name.eq_ignore_ascii_case(name)
+                && DateTime::parse_from_str(&e.expires_on, BAN_TIME_FORMAT)
+                    .is_ok_and(|expires| expires > now)
+        })
+        .map(|e| PlayerEntry { uuid: e.uuid, name: e.name })
+}
+
+/// 将 32 位十六进制 UUID 转为带连字符的形式，已带连字符的原样返回
+fn hyphenate(id: &str) -> Option<String> {
+    let hex: String = id.chars().filter(|c| *c != '-').collect();
+    if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = hex.to_lowercase();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+fn server_property(server_path: &str, key: &str) -> Option<String> {
+    config_parser::read_server_dir_properties(server_path)
+        .ok()?
+        .remove(key)
+}
+
+/// 以 JSON 值读写名单，保留条目中未识别的字段；edit 返回是否有变化
+fn edit_list(
+    server_path: &str,
+    filename: &str,
+    edit: impl FnOnce(&mut Vec<Value>) -> bool,
+) -> Result<bool, String> {
+    let mut list: Vec<Value> = read_json_list(server_path, filename)?;
+    if !edit(&mut list) {
+        return Ok(false);
+    }
+    let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+    let path = Path::new(server_path).join(filename);
+    std::fs::write(&path, json).map_err(|e| format!("写入{}失败: {}", filename, e))?;
+    Ok(true)
+}
+
+fn insert_player(list: &mut Vec<Value>, entry: Value) -> bool {
+    let name = entry["name"].as_str().unwrap_or_default().to_string();
+    let uuid = entry["uuid"].as_str().unwrap_or_default().to_string();
+    if list.iter().any(|e| {
+        e["uuid"].as_str() == Some(uuid.as_str())
+            && e["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(&name))
+    }) {
+        return false;
+    }
+    list.retain(|e| {
+        e["uuid"].as_str() != Some(uuid.as_str())
+            && !e["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(&name))
+    });
+    list.push(entry);
+    true
+}
+
+fn remove_player(list: &mut Vec<Value>, name: &str) -> bool {
+    let before = list.len();
+    list.retain(|e| {
+        !e["name"]
+            .as_str()
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+    });
+    list.len() != before
+}
+
+fn remove_ip(list: &mut Vec<Value>, ip: &str) -> bool {
+    let before = list.len();
+    list.retain(|e| e["ip"].as_str() != Some(ip));
+    list.len() != before
+}
+
 fn read_json_list<T: serde::de::DeserializeOwned>(
     server_path: &str,
     filename: &str,
@@ -58,3 +352,47 @@ fn read_json_list<T: serde::de::DeserializeOwned>(
     }
     serde_json::from_str(trimmed).map_err(|e| format!("解析{}失败: {}", filename, e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_java() {
+        assert_eq!(offline_uuid("Notch"), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+        assert_eq!(
+            hyphenate("069a79f444e94726a5befca90e38aaf5").as_deref(),
+            Some("069a79f4-44e9-4726-a5be-fca90e38aaf5")
+        );
+    }
+
+    #[test]
+    fn edits_lists_in_place() {
+        let dir = std::env::temp_dir().join(format!("sl-players-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
+        std::fs::write(
+            dir.join("ops.json"),
+            r#"[{"uuid":"u1","name":"Alex","level":4,"bypassesPlayerLimit":true}]"#,
+        )
+        .unwrap();
+
+        let steve = PlayerEntry {
+            uuid: offline_uuid("Steve"),
+            name: "Steve".to_string(),
+        };
+        assert!(add_op(&path, &steve, 2).unwrap());
+        assert!(!add_op(&path, &steve, 2).unwrap());
+        let ops = read_ops(&path).unwrap();
+        assert_eq!(ops.len(), 2);
+        assert!(ops[0].bypasses_player_limit);
+        assert!(remove_op(&path, "alex").unwrap());
+
+        assert!(add_ban(&path, &new_ban(&steve, "", "test")).unwrap());
+        assert_eq!(read_banned_players(&path).unwrap()[0].expires, "forever");
+        assert!(remove_ban(&path, "Steve").unwrap());
+        assert!(!remove_from_whitelist(&path, "Steve").unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
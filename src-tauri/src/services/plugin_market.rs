@@ -0,0 +1,270 @@
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::mod_manager::ModInfo;
+
+const HANGAR_API: &str = "https://hangar.papermc.io/api/v1";
+const SPIGET_API: &str = "https://api.spiget.org/v2";
+const SEARCH_LIMIT: &str = "20";
+
+/// 插件端核心可加载的 Modrinth 插件加载器，按优先级排列；不是插件端时返回 None
+pub fn plugin_loaders_for_core(core: &str) -> Option<&'static [&'static str]> {
+    let core = core.to_lowercase();
+    if core.contains("folia") {
+        // Folia 只能运行明确声明支持区域化多线程的插件
+        Some(&["folia"])
+    } else if core.contains("purpur") {
+        Some(&["purpur", "paper", "spigot", "bukkit"])
+    } else if core.contains("paper") {
+        Some(&["paper", "spigot", "bukkit"])
+    } else if core.contains("spigot") || core.contains("bukkit") {
+        Some(&["spigot", "bukkit"])
+    } else {
+        None
+    }
+}
+
+/// 在 Hangar 中搜索支持 PAPER 平台与该 Minecraft 版本的插件
+pub async fn search_hangar(
+    client: &Client,
+    query: &str,
+    game_version: &str,
+) -> Result<Vec<ModInfo>, String> {
+    let resp: HangarPage<HangarProject> = client
+        .get(format!("{}/projects", HANGAR_API))
+        .query(&[
+            ("q", query),
+            ("limit", SEARCH_LIMIT),
+            ("platform", "PAPER"),
+            ("version", game_version),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 具体文件在安装时再解析，避免逐个查询版本列表
+    Ok(resp
+        .result
+        .into_iter()
+        .map(|project| ModInfo {
+            id: project.namespace.slug,
+            name: project.name,
+            summary: project.description,
+            download_url: String::new(),
+            file_name: String::new(),
+            source: "hangar".to_string(),
+        })
+        .collect())
+}
+
+/// 安装时在后端解析出的插件文件
+pub struct PluginFile {
+    pub download_url: String,
+    pub file_name: String,
+    pub sha256: Option<String>,
+    pub sha512: Option<String>,
+}
+
+/// Hangar 项目中兼容该版本的最新文件；仅提供外部链接的版本返回 None
+pub async fn get_hangar_download(
+    client: &Client,
+    slug: &str,
+    game_version: &str,
+) -> Result<Option<PluginFile>, String> {
+    // slug 位于路径中，需要按路径段编码
+    let mut url =
+        reqwest::Url::parse(&format!("{}/projects", HANGAR_API)).map_err(|e| e.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| "无效的 Hangar 地址".to_string())?
+        .push(slug)
+        .push("versions");
+    let resp: HangarPage<HangarVersion> = client
+        .get(url)
+        .query(&[("limit", "1"), ("platform", "PAPER"), ("platformVersion", game_version)])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(resp
+        .result
+        .into_iter()
+        .next()
+        .and_then(|v| v.downloads.paper)
+        .and_then(|d| {
+            let file_info = d.file_info?;
+            Some(PluginFile {
+                download_url: d.download_url?,
+                file_name: file_info.name,
+                sha256: file_info.sha256_hash,
+                sha512: None,
+            })
+        }))
+}
+
+/// Spiget 资源的下载地址由资源 id 决定，文件名取自资源名称
+pub fn spiget_download(id: &str, name: &str) -> Result<PluginFile, String> {
+    let id: u64 = id
+        .parse()
+        .map_err(|_| format!("无效的 Spiget 资源 ID: {}", id))?;
+    Ok(PluginFile {
+        download_url: format!("{}/resources/{}/download", SPIGET_API, id),
+        file_name: format!("{}.jar", sanitize_file_name(name)),
+        sha256: None,
+        sha512: None,
+    })
+}
+
+/// 在 SpigotMC（通过 Spiget）中搜索插件，过滤掉付费、外部链接以及未测试该版本的资源
+pub async fn search_spiget(
+    client: &Client,
+    query: &str,
+    game_version: &str,
+) -> Result<Vec<ModInfo>, String> {
+    // 查询词位于路径中，需要按路径段编码
+    let mut url = reqwest::Url::parse(&format!("{}/search/resources", SPIGET_API))
+        .map_err(|e| e.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| "无效的 Spiget 地址".to_string())?
+        .push(query);
+    let resources: Vec<SpigetResource> = client
+        .get(url)
+        .query(&[
+            ("field", "name"),
+            ("size", SEARCH_LIMIT),
+            ("fields", "id,name,tag,file,testedVersions,premium,external"),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let minor = minor_version(game_version);
+    Ok(resources
+        .into_iter()
+        .filter(|r| !r.premium && !r.external && r.file.file_type == ".jar")
+        .filter(|r| r.tested_versions.is_empty() || r.tested_versions.contains(&minor))
+        .map(|r| ModInfo {
+            id: r.id.to_string(),
+            file_name: format!("{}.jar", sanitize_file_name(&r.name)),
+            name: r.name,
+            summary: r.tag,
+            download_url: format!("{}/resources/{}/download", SPIGET_API, r.id),
+            source: "spigot".to_string(),
+        })
+        .collect())
+}
+
+/// 1.20.4 -> 1.20，Spiget 的 testedVersions 只记录到次版本号
+fn minor_version(game_version: &str) -> String {
+    game_version
+        .split('.')
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct HangarPage<T> {
+    result: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct HangarProject {
+    name: String,
+    #[serde(default)]
+    description: String,
+    namespace: HangarNamespace,
+}
+
+#[derive(Deserialize)]
+struct HangarNamespace {
+    slug: String,
+}
+
+#[derive(Deserialize)]
+struct HangarVersion {
+    downloads: HangarDownloads,
+}
+
+#[derive(Deserialize)]
+struct HangarDownloads {
+    #[serde(rename = "PAPER")]
+    paper: Option<HangarDownload>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarDownload {
+    download_url: Option<String>,
+    file_info: Option<HangarFileInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarFileInfo {
+    name: String,
+    sha256_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpigetResource {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    tag: String,
+    file: SpigetFile,
+    #[serde(default)]
+    tested_versions: Vec<String>,
+    #[serde(default)]
+    premium: bool,
+    #[serde(default)]
+    external: bool,
+}
+
+#[derive(Deserialize)]
+struct SpigetFile {
+    #[serde(rename = "type", default)]
+    file_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_loaders_follow_core_type() {
+        assert_eq!(plugin_loaders_for_core("Paper"), Some(&["paper", "spigot", "bukkit"][..]));
+        assert_eq!(plugin_loaders_for_core("folia"), Some(&["folia"][..]));
+        assert_eq!(plugin_loaders_for_core("fabric"), None);
+        assert_eq!(minor_version("1.20.4"), "1.20");
+        assert_eq!(sanitize_file_name("Essentials X"), "Essentials_X");
+        let spiget = spiget_download("28140", "Luck Perms").unwrap();
+        assert_eq!(spiget.download_url, format!("{}/resources/28140/download", SPIGET_API));
+        assert_eq!(spiget.file_name, "Luck_Perms.jar");
+        assert!(spiget_download("https://example.com/a.jar", "a").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, TcpListener, UdpSocket};
+use std::path::Path;
+
+use super::{config_history, config_parser};
+use crate::models::server::ServerInstance;
+
+const DEFAULT_RCON_PORT: u16 = 25575;
+
+/// 服务器使用的端口：游戏与 RCON 为 TCP，Query 为 UDP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerPorts {
+    pub game: u16,
+    pub rcon: Option<u16>,
+    pub query: Option<u16>,
+}
+
+impl ServerPorts {
+    /// 游戏端口取 ServerInstance.port，RCON 与 Query 端口仅在开启时读取
+    pub fn read(server: &ServerInstance) -> Self {
+        let props = config_parser::read_server_dir_properties(&server.path).unwrap_or_default();
+        let enabled = |key: &str| props.get(key).map(|v| v.trim()) == Some("true");
+        let port = |key: &str, default: u16| {
+            props
+                .get(key)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        ServerPorts {
+            game: server.port,
+            rcon: enabled("enable-rcon").then(|| port("rcon.port", DEFAULT_RCON_PORT)),
+            query: enabled("enable-query").then(|| port("query.port", server.port)),
+        }
+    }
+
+    fn tcp(&self) -> Vec<(&'static str, u16)> {
+        let mut ports = vec![("游戏端口", self.game)];
+        ports.extend(self.rcon.map(|p| ("RCON 端口", p)));
+        ports
+    }
+
+    fn all(&self) -> impl Iterator<Item = u16> {
+        [Some(self.game), self.rcon, self.query]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// 启动前检查端口，返回所有问题；is_running 判断其他受管服务器是否在运行
+pub fn check_ports(
+    server: &ServerInstance,
+    servers: &[ServerInstance],
+    is_running: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let ports = ServerPorts::read(server);
+    let mut problems = Vec::new();
+    if ports.rcon == Some(ports.game) {
+        problems.push(format!("RCON 端口与游戏端口相同: {}", ports.game));
+    }
+
+    let mut claimed = HashSet::new();
+    for other in servers
+        .iter()
+        .filter(|s| s.id != server.id && is_running(&s.id))
+    {
+        let other_ports = ServerPorts::read(other);
+        let other_tcp: Vec<u16> = other_ports.tcp().into_iter().map(|(_, p)| p).collect();
+        for (label, port) in ports.tcp() {
+            if other_tcp.contains(&port) {
+                claimed.insert(port);
+                problems
+                    .push(format!("{} {} 已被运行中的服务器「{}」使用", label, port, other.name));
+            }
+        }
+        if let Some(port) = ports.query.filter(|p| other_ports.query == Some(*p)) {
+            claimed.insert(port);
+            problems.push(format!("Query 端口 {} 已被运行中的服务器「{}」使用", port, other.name));
+        }
+    }
+
+    for (label, port) in ports.tcp() {
+        if !claimed.contains(&port) && !is_tcp_free(port) {
+            problems.push(format!("{} {} 已被其他程序占用", label, port));
+        }
+    }
+    if let Some(port) = ports.query {
+        if !claimed.contains(&port) && !is_udp_free(port) {
+            problems.push(format!("Query 端口 {} 已被其他程序占用", port));
+        }
+    }
+    problems
+}
+
+/// 创建或修改服务器时，端口不能与其他受管服务器已配置的端口重复
+pub fn ensure_unclaimed(
+    port: u16,
+    server_id: Option<&str>,
+    servers: &[ServerInstance],
+) -> Result<(), String> {
+    if port == 0 {
+        return Err("端口不能为 0".to_string());
+    }
+    let others: Vec<ServerInstance> = servers
+        .iter()
+        .filter(|s| Some(s.id.as_str()) != server_id)
+        .cloned()
+        .collect();
+    match others
+        .iter()
+        .find(|s| ServerPorts::read(s).all().any(|p| p == port))
+    {
+        Some(owner) => Err(format!(
+            "端口 {} 已被服务器「{}」使用，可以改用 {}",
+            port,
+            owner.name,
+            next_free_port(port, &others).unwrap_or(port)
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 从 start 开始寻找未被受管服务器配置、且当前可以绑定的端口
+pub fn next_free_port(start: u16, servers: &[ServerInstance]) -> Option<u16> {
+    let taken: HashSet<u16> = servers
+        .iter()
+        .flat_map(|s| ServerPorts::read(s).all().collect::<Vec<_>>())
+        .collect();
+    (start.max(1)..=u16::MAX).find(|p| !taken.contains(p) && is_tcp_free(*p))
+}
+
+pub fn is_tcp_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).is_ok()
+}
+
+pub fn is_udp_free(port: u16) -> bool {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port)).is_ok()
+}
+
+/// server.properties 中的 server-port
+pub fn read_server_port(server_path: &str) -> Option<u16> {
+    config_parser::read_server_dir_properties(server_path)
+        .ok()?
+        .get("server-port")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 把端口写入 server.properties 的 server-port，并记录到配置历史
+pub fn write_server_port(server_id: &str, server_path: &str, port: u16) -> Result<(), String> {
+    if read_server_port(server_path) == Some(port) {
+        return Ok(());
+    }
+    let dir = Path::new(server_path);
+    let pending =
+        config_history::begin(&config_history::history_dir(server_id), dir, "server.properties");
+    let values = HashMap::from([("server-port".to_string(), port.to_string())]);
+    config_parser::write_properties(&dir.join("server.properties").to_string_lossy(), &values)?;
+    if let Err(e) = pending.commit() {
+        println!("[配置历史] 记录失败: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn server(id: &str, path: &Path, port: u16) -> ServerInstance {
+        ServerInstance {
+            port,
+            ..ServerInstance::for_test(id, path)
+        }
+    }
+
+    #[test]
+    fn detects_conflicts_and_allocates_free_ports() {
+        let root = std::env::temp_dir().join(format!("sl-ports-{}", uuid::Uuid::new_v4()));
+        let (a_dir, b_dir) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(&a_dir).unwrap();
+        fs::create_dir_all(&b_dir).unwrap();
+
+        let occupied = TcpListener::bind("0.0.0.0:0").unwrap();
+        let busy = occupied.local_addr().unwrap().port();
+        let a = server("a", &a_dir, busy);
+        let b = server("b", &b_dir, busy.wrapping_add(1));
+        fs::write(
+            b_dir.join("server.properties"),
+            format!("enable-rcon=true\nrcon.port={}\n", busy),
+        )
+        .unwrap();
+        let servers = vec![a.clone(), b.clone()];
+
+        let problems = check_ports(&a, &servers, |id| id == "b");
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("「b」"));
+        assert!(check_ports(&a, &servers, |_| false)[0].contains("其他程序占用"));
+
+        assert!(ensure_unclaimed(busy.wrapping_add(1), Some("a"), &servers).is_err());
+        assert!(ensure_unclaimed(busy.wrapping_add(1), Some("b"), &servers).is_ok());
+        let next = next_free_port(busy, &servers).unwrap();
+        assert!(next > busy.wrapping_add(1));
+
+        assert_eq!(read_server_port(&b.path), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
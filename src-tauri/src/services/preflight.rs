@@ -0,0 +1,306 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use sysinfo::{Disks, System};
+
+use super::yaml_document::YamlDocument;
+use super::{attached_server, config_parser, java_detector, port_manager};
+use crate::models::server::{CheckLevel, PreflightCheck, PreflightReport, ServerInstance};
+use crate::models::settings::AppSettings;
+
+const MB: u64 = 1024 * 1024;
+/// 剩余空间低于该值时阻止启动
+const DISK_ERROR_BYTES: u64 = 512 * MB;
+/// 剩余空间低于该值时给出警告
+const DISK_WARNING_BYTES: u64 = 2048 * MB;
+
+/// 启动前检查所需的信息
+pub struct PreflightContext<'a> {
+    pub server: &'a ServerInstance,
+    pub servers: &'a [ServerInstance],
+    /// 正在运行（非停止状态）的其他受管服务器
+    pub running: &'a [String],
+    pub settings: &'a AppSettings,
+}
+
+type Outcome = (CheckLevel, String);
+type Check = fn(&PreflightContext) -> Outcome;
+
+/// 按顺序执行的检查：标识、名称、检查函数
+const CHECKS: &[(&str, &str, Check)] = &[
+    ("java", "Java", check_java),
+    ("memory", "内存", check_memory),
+    ("port", "端口", check_port),
+    ("disk", "磁盘空间", check_disk),
+    ("startup_file", "启动文件", check_startup_file),
+    ("eula", "EULA", check_eula),
+    ("online_mode", "正版验证", check_online_mode),
+    ("session_lock", "世界存档", check_session_lock),
+];
+
+pub fn run(ctx: &PreflightContext) -> PreflightReport {
+    let checks = CHECKS
+        .iter()
+        .map(|(id, name, check)| {
+            let (level, message) = check(ctx);
+            PreflightCheck {
+                id: id.to_string(),
+                name: name.to_string(),
+                level,
+                message,
+            }
+        })
+        .collect();
+    PreflightReport { checks }
+}
+
+/// 把某一级别的检查结果拼成多行消息
+pub fn summarize(report: &PreflightReport, level: CheckLevel) -> String {
+    report
+        .with_level(level)
+        .map(|c| format!("[{}] {}", c.name, c.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn pass(message: impl Into<String>) -> Outcome {
+    (CheckLevel::Pass, message.into())
+}
+
+fn warning(message: impl Into<String>) -> Outcome {
+    (CheckLevel::Warning, message.into())
+}
+
+fn error(message: impl Into<String>) -> Outcome {
+    (CheckLevel::Error, message.into())
+}
+
+fn check_java(ctx: &PreflightContext) -> Outcome {
+    let server = ctx.server;
+    if server.java_path.trim().is_empty() {
+        return error("未设置 Java 路径，请在服务器设置中选择 Java");
+    }
+    let java = match java_detector::validate_java(&server.java_path) {
+        Ok(java) => java,
+        Err(_) => {
+            return error(format!(
+                "无法运行 Java: {}，请确认路径正确或重新选择 Java",
+                server.java_path
+            ))
+        }
+    };
+
+    let required = required_java(&server.mc_version);
+    if java.major_version < required {
+        return error(format!(
+            "Minecraft {} 需要 Java {} 或更高版本，当前为 Java {}",
+            server.mc_version, required, java.major_version
+        ));
+    }
+    if !server.startup_mode.eq_ignore_ascii_case("jar") && java.major_version < 9 {
+        return error(format!(
+            "脚本启动需要 Java 9 或更高版本（使用 @user_jvm_args.txt），当前为 Java {}",
+            java.major_version
+        ));
+    }
+    if required == 8 && java.major_version > 11 && server.core_type != "vanilla" {
+        return warning(format!(
+            "旧版本 {} 服务端在 Java {} 上可能无法启动，建议使用 Java 8",
+            server.core_type, java.major_version
+        ));
+    }
+    pass(format!("Java {} ({})", java.major_version, java.version))
+}
+
+/// 运行指定 Minecraft 版本所需的最低 Java 版本
+pub fn required_java(mc_version: &str) -> u32 {
+    let mut parts = mc_version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse::<u32>().unwrap_or(0));
+    let (first, minor, patch) =
+        (parts.next().unwrap_or(0), parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    match (first, minor, patch) {
+        // 26.1 起使用年份版本号
+        (year, _, _) if year >= 26 => 25,
+        (1, minor, patch) if minor > 20 || (minor == 20 && patch >= 5) => 21,
+        (1, minor, _) if minor >= 18 => 17,
+        (1, 17, _) => 16,
+        _ => 8,
+    }
+}
+
+fn check_memory(ctx: &PreflightContext) -> Outcome {
+    let server = ctx.server;
+    if server.min_memory > server.max_memory {
+        return error(format!(
+            "最小内存 {} MB 大于最大内存 {} MB",
+            server.min_memory, server.max_memory
+        ));
+    }
+
+    let mut sys = System::new();
+    sys.refresh_memory();
+    let total = sys.total_memory() / MB;
+    let available = sys.available_memory() / MB;
+    let max = u64::from(server.max_memory);
+    if total > 0 && max > total {
+        return error(format!(
+            "最大内存 {} MB 超过了本机物理内存 {} MB，请调低最大内存",
+            max, total
+        ));
+    }
+    if available > 0 && max > available {
+        return warning(format!(
+            "最大内存 {} MB 超过当前可用内存 {} MB，运行时可能变慢或被系统终止",
+            max, available
+        ));
+    }
+    pass(format!("最大内存 {} MB，可用 {} MB", max, available))
+}
+
+fn check_port(ctx: &PreflightContext) -> Outcome {
+    let problems = port_manager::check_ports(ctx.server, ctx.servers, |id| {
+        ctx.running.iter().any(|r| r == id)
+    });
+    if problems.is_empty() {
+        pass(format!("端口 {} 可用", ctx.server.port))
+    } else {
+        error(problems.join("；"))
+    }
+}
+
+fn check_disk(ctx: &PreflightContext) -> Outcome {
+    let Ok(dir) = fs::canonicalize(&ctx.server.path) else {
+        return error(format!("服务器目录不存在: {}", ctx.server.path));
+    };
+    let disks = Disks::new_with_refreshed_list();
+    let Some(disk) = disks
+        .list()
+        .iter()
+        .filter(|d| dir.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+    else {
+        return pass("无法获取磁盘信息，已跳过");
+    };
+
+    let free = disk.available_space();
+    let message = format!("{} 剩余 {} MB", disk.mount_point().display(), free / MB);
+    if free < DISK_ERROR_BYTES {
+        error(format!("{}，空间不足可能导致存档损坏，请先清理磁盘", message))
+    } else if free < DISK_WARNING_BYTES {
+        warning(format!("{}，建议清理磁盘", message))
+    } else {
+        pass(message)
+    }
+}
+
+fn check_startup_file(ctx: &PreflightContext) -> Outcome {
+    let server = ctx.server;
+    let path = Path::new(&server.path).join(&server.jar_path);
+    if !path.is_file() {
+        return error(format!("启动文件不存在: {}", path.display()));
+    }
+    match server.startup_mode.to_ascii_lowercase().as_str() {
+        "bat" if !cfg!(target_os = "windows") => error("BAT 启动方式仅支持 Windows"),
+        "bat" | "sh" => match fs::File::open(&path) {
+            Ok(_) => pass(format!("启动脚本 {}", path.display())),
+            Err(e) => error(format!("无法读取启动脚本 {}: {}", path.display(), e)),
+        },
+        _ => {
+            let mut magic = [0u8; 4];
+            let readable = fs::File::open(&path).and_then(|mut f| f.read_exact(&mut magic));
+            if readable.is_err() || &magic[..2] != b"PK" {
+                return error(format!(
+                    "{} 不是有效的 jar 文件，可能下载不完整，请重新下载服务端",
+                    path.display()
+                ));
+            }
+            pass(format!("服务端 {}", path.display()))
+        }
+    }
+}
+
+fn check_eula(ctx: &PreflightContext) -> Outcome {
+    let path = Path::new(&ctx.server.path).join("eula.txt");
+    let accepted = config_parser::read_properties(&path.to_string_lossy())
+        .ok()
+        .and_then(|props| {
+            props
+                .get("eula")
+                .map(|v| v.trim().eq_ignore_ascii_case("true"))
+        })
+        .unwrap_or(false);
+    if accepted {
+        pass("已同意 Minecraft EULA")
+    } else if ctx.settings.auto_accept_eula {
+        pass("启动时将自动同意 Minecraft EULA")
+    } else {
+        warning("尚未同意 Minecraft EULA，服务器会在启动后退出并请求你确认")
+    }
+}
+
+fn check_online_mode(ctx: &PreflightContext) -> Outcome {
+    let props = config_parser::read_server_dir_properties(&ctx.server.path).unwrap_or_default();
+    if props.get("online-mode").map(|v| v.trim()) != Some("false") {
+        return pass("已开启正版验证");
+    }
+    if behind_proxy(Path::new(&ctx.server.path)) {
+        return pass("已关闭正版验证，由代理服务器负责验证");
+    }
+    warning("online-mode=false 且未配置 BungeeCord/Velocity 代理，任何人都可以冒用其他玩家的名字进入服务器")
+}
+
+/// Spigot 的 BungeeCord 模式或 Paper 的 Velocity 转发
+fn behind_proxy(dir: &Path) -> bool {
+    let enabled = |file: &str, path: &[&str]| {
+        let Ok(content) = fs::read_to_string(dir.join(file)) else {
+            return false;
+        };
+        let path: Vec<String> = path.iter().map(|s| s.to_string()).collect();
+        YamlDocument::parse(&content).get(&path) == Some(Value::Bool(true))
+    };
+    enabled("spigot.yml", &["settings", "bungeecord"])
+        || enabled("config/paper-global.yml", &["proxies", "velocity", "enabled"])
+        || enabled("paper.yml", &["settings", "velocity-support", "enabled"])
+}
+
+fn check_session_lock(ctx: &PreflightContext) -> Outcome {
+    let props = config_parser::read_server_dir_properties(&ctx.server.path).unwrap_or_default();
+    let level = props
+        .get("level-name")
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .unwrap_or("world");
+    let lock: PathBuf = Path::new(&ctx.server.path).join(level).join("session.lock");
+    if !lock.exists() {
+        return pass(format!("世界 {} 未被占用", level));
+    }
+    // Minecraft 运行时持有 session.lock，同目录下存在 Java 进程即视为被占用
+    match attached_server::java_processes_in(Path::new(&ctx.server.path)).first() {
+        Some(pid) => {
+            error(format!("世界 {} 正被另一个进程（PID {}）使用，请先关闭该进程", level, pid))
+        }
+        None => pass(format!("世界 {} 未被占用", level)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_minecraft_versions_to_required_java() {
+        assert_eq!(required_java("1.12.2"), 8);
+        assert_eq!(required_java("1.16.5"), 8);
+        assert_eq!(required_java("1.17.1"), 16);
+        assert_eq!(required_java("1.18"), 17);
+        assert_eq!(required_java("1.20.4"), 17);
+        assert_eq!(required_java("1.20.5"), 21);
+        assert_eq!(required_java("1.21.1"), 21);
+        assert_eq!(required_java("26.1"), 25);
+        assert_eq!(required_java(""), 8);
+    }
+}
@@ -0,0 +1,958 @@
+use std::collections::HashMap;
+use std::fmt;
+
+pub const GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+pub const DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+pub const REGION_COMPRESSIONS: &[&str] = &["deflate", "lz4", "none"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyKind {
+    Boolean,
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// 1.14 之前 gamemode 与 difficulty 以数字保存，按选项下标同样接受
+    Select(&'static [&'static str]),
+    Text,
+    /// 40 位十六进制，可为空
+    Sha1,
+}
+
+impl PropertyKind {
+    /// 前端使用的 value_type
+    pub fn value_type(&self) -> &'static str {
+        match self {
+            PropertyKind::Boolean => "boolean",
+            PropertyKind::Integer { .. } => "number",
+            PropertyKind::Select(_) => "select",
+            PropertyKind::Text | PropertyKind::Sha1 => "string",
+        }
+    }
+}
+
+/// 原版 server.properties 中的一个配置项
+#[derive(Debug)]
+pub struct PropertySchema {
+    pub key: &'static str,
+    pub kind: PropertyKind,
+    pub default: &'static str,
+    pub category: &'static str,
+    /// 引入该配置项的 Minecraft 版本，Beta 时期已有的记为 1.0
+    pub since: &'static str,
+    /// 移除该配置项的版本
+    pub removed: Option<&'static str>,
+    /// 依次为 zh-CN、en-US、zh-TW
+    descriptions: [&'static str; 3],
+}
+
+impl PropertySchema {
+    /// 其余语言回退到英文，完整翻译由前端语言文件提供
+    pub fn description(&self, locale: &str) -> &'static str {
+        if locale.eq_ignore_ascii_case("zh-TW") {
+            self.descriptions[2]
+        } else if locale.to_lowercase().starts_with("zh") {
+            self.descriptions[0]
+        } else {
+            self.descriptions[1]
+        }
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            PropertyKind::Boolean => {
+                if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+                    Ok(())
+                } else {
+                    Err("必须为 true 或 false".to_string())
+                }
+            }
+            PropertyKind::Integer { min, max } => match value.trim().parse::<i64>() {
+                Ok(n) if n >= min && n <= max => Ok(()),
+                Ok(_) => Err(format!("必须在 {} 到 {} 之间", min, max)),
+                Err(_) => Err("必须为整数".to_string()),
+            },
+            PropertyKind::Select(options) => {
+                let legacy = value
+                    .trim()
+                    .parse::<usize>()
+                    .is_ok_and(|i| i < options.len());
+                if legacy || options.contains(&value.trim()) {
+                    Ok(())
+                } else {
+                    Err(format!("未知的取值 {}，可选值: {}", value, options.join(", ")))
+                }
+            }
+            PropertyKind::Text => Ok(()),
+            PropertyKind::Sha1 => {
+                if value.is_empty()
+                    || (value.len() == 40 && value.chars().all(|c| c.is_ascii_hexdigit()))
+                {
+                    Ok(())
+                } else {
+                    Err("必须为 40 位十六进制 SHA-1 或留空".to_string())
+                }
+            }
+        }
+    }
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyError {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+pub fn all() -> &'static [PropertySchema] {
+    PROPERTIES
+}
+
+pub fn find(key: &str) -> Option<&'static PropertySchema> {
+    PROPERTIES.iter().find(|p| p.key == key)
+}
+
+/// 校验待写入的值；模组或插件端添加的未知配置项不做检查
+pub fn validate_properties(values: &HashMap<String, String>) -> Result<(), Vec<PropertyError>> {
+    let mut errors: Vec<PropertyError> = values
+        .iter()
+        .filter_map(|(key, value)| {
+            let message = find(key)?.validate(value).err()?;
+            Some(PropertyError { key: key.clone(), message })
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    errors.sort_by(|a, b| a.key.cmp(&b.key));
+    Err(errors)
+}
+
+static PROPERTIES: &[PropertySchema] = &[
+    PropertySchema {
+        key: "accepts-transfers",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "network",
+        since: "1.20.5",
+        removed: None,
+        descriptions: ["接受传输", "Accept Transfers", "接受傳輸"],
+    },
+    PropertySchema {
+        key: "allow-flight",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "game",
+        since: "1.0",
+        removed: None,
+        descriptions: ["允许飞行", "Allow Flight", "允許飛行"],
+    },
+    PropertySchema {
+        key: "allow-nether",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["允许下界", "Allow Nether", "允許地獄"],
+    },
+    PropertySchema {
+        key: "announce-player-achievements",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "player",
+        since: "1.7.2",
+        removed: Some("1.12"),
+        descriptions: [
+            "在聊天栏公告玩家获得的成就（1.12 起改为游戏规则）",
+            "Announce Player Achievements (game rule since 1.12)",
+            "在聊天欄公告玩家獲得的成就（1.12 起改為遊戲規則）",
+        ],
+    },
+    PropertySchema {
+        key: "broadcast-console-to-ops",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "player",
+        since: "1.14",
+        removed: None,
+        descriptions: [
+            "向OP广播控制台消息",
+            "Broadcast Console Commands to Ops",
+            "向OP廣播控制台訊息",
+        ],
+    },
+    PropertySchema {
+        key: "broadcast-rcon-to-ops",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "player",
+        since: "1.14",
+        removed: None,
+        descriptions: ["向OP广播RCON消息", "Broadcast RCON Commands to Ops", "向OP廣播RCON訊息"],
+    },
+    PropertySchema {
+        key: "bug-report-link",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "display",
+        since: "1.21",
+        removed: None,
+        descriptions: ["Bug报告链接", "Bug Report Link", "錯誤回報連結"],
+    },
+    PropertySchema {
+        key: "difficulty",
+        kind: PropertyKind::Select(DIFFICULTIES),
+        default: "easy",
+        category: "game",
+        since: "1.0",
+        removed: None,
+        descriptions: ["游戏难度", "Game Difficulty", "遊戲難度"],
+    },
+    PropertySchema {
+        key: "enable-code-of-conduct",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "player",
+        since: "1.21.9",
+        removed: None,
+        descriptions: ["启用行为准则", "Enable Code of Conduct", "啟用行為準則"],
+    },
+    PropertySchema {
+        key: "enable-command-block",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "game",
+        since: "1.4.2",
+        removed: None,
+        descriptions: ["启用命令方块", "Enable Command Blocks", "啟用指令方塊"],
+    },
+    PropertySchema {
+        key: "enable-jmx-monitoring",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "performance",
+        since: "1.16",
+        removed: None,
+        descriptions: ["启用JMX监控", "Enable JMX Monitoring", "啟用JMX監控"],
+    },
+    PropertySchema {
+        key: "enable-query",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["启用Query协议", "Enable Query Protocol", "啟用Query協定"],
+    },
+    PropertySchema {
+        key: "enable-rcon",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["启用RCON远程控制", "Enable RCON Remote Control", "啟用RCON遠端控制"],
+    },
+    PropertySchema {
+        key: "enable-status",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "network",
+        since: "1.16",
+        removed: None,
+        descriptions: ["启用服务器列表状态", "Enable Server List Status", "啟用伺服器列表狀態"],
+    },
+    PropertySchema {
+        key: "enforce-secure-profile",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "player",
+        since: "1.19",
+        removed: None,
+        descriptions: ["强制安全配置文件", "Enforce Secure Profiles", "強制安全設定檔"],
+    },
+    PropertySchema {
+        key: "enforce-whitelist",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "player",
+        since: "1.13",
+        removed: None,
+        descriptions: ["强制白名单", "Enforce Whitelist", "強制白名單"],
+    },
+    PropertySchema {
+        key: "entity-broadcast-range-percentage",
+        kind: PropertyKind::Integer { min: 10, max: 1000 },
+        default: "100",
+        category: "performance",
+        since: "1.16",
+        removed: None,
+        descriptions: [
+            "实体广播范围百分比",
+            "Entity Broadcast Range Percentage",
+            "實體廣播範圍百分比",
+        ],
+    },
+    PropertySchema {
+        key: "force-gamemode",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "game",
+        since: "1.5.2",
+        removed: None,
+        descriptions: ["强制游戏模式", "Force Game Mode", "強制遊戲模式"],
+    },
+    PropertySchema {
+        key: "function-permission-level",
+        kind: PropertyKind::Integer { min: 1, max: 4 },
+        default: "2",
+        category: "game",
+        since: "1.14.4",
+        removed: None,
+        descriptions: ["函数权限级别", "Function Permission Level", "函數權限等級"],
+    },
+    PropertySchema {
+        key: "gamemode",
+        kind: PropertyKind::Select(GAMEMODES),
+        default: "survival",
+        category: "game",
+        since: "1.0",
+        removed: None,
+        descriptions: ["默认游戏模式", "Default Game Mode", "預設遊戲模式"],
+    },
+    PropertySchema {
+        key: "generate-structures",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["生成结构", "Generate Structures", "生成結構"],
+    },
+    PropertySchema {
+        key: "generator-settings",
+        kind: PropertyKind::Text,
+        default: "{}",
+        category: "world",
+        since: "1.4.2",
+        removed: None,
+        descriptions: ["生成器设置", "World Generator Settings", "生成器設定"],
+    },
+    PropertySchema {
+        key: "hardcore",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "game",
+        since: "1.0",
+        removed: None,
+        descriptions: ["极限模式", "Hardcore Mode", "極限模式"],
+    },
+    PropertySchema {
+        key: "hide-online-players",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "display",
+        since: "1.18",
+        removed: None,
+        descriptions: ["隐藏在线玩家", "Hide Online Players Count", "隱藏線上玩家"],
+    },
+    PropertySchema {
+        key: "initial-disabled-packs",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "world",
+        since: "1.19.3",
+        removed: None,
+        descriptions: ["初始禁用的数据包", "Initial Disabled Packs", "初始停用的資料包"],
+    },
+    PropertySchema {
+        key: "initial-enabled-packs",
+        kind: PropertyKind::Text,
+        default: "vanilla",
+        category: "world",
+        since: "1.19.3",
+        removed: None,
+        descriptions: ["初始启用的数据包", "Initial Enabled Packs", "初始啟用的資料包"],
+    },
+    PropertySchema {
+        key: "level-name",
+        kind: PropertyKind::Text,
+        default: "world",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["世界名称", "World Name", "世界名稱"],
+    },
+    PropertySchema {
+        key: "level-seed",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["世界种子", "World Seed", "世界種子"],
+    },
+    PropertySchema {
+        key: "level-type",
+        kind: PropertyKind::Text,
+        default: "minecraft:normal",
+        category: "world",
+        since: "1.1",
+        removed: None,
+        descriptions: ["世界类型", "World Type", "世界類型"],
+    },
+    PropertySchema {
+        key: "log-ips",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "network",
+        since: "1.20.2",
+        removed: None,
+        descriptions: [
+            "是否在服务器日志中记录玩家的 IP 地址",
+            "Log Player IP Addresses",
+            "記錄玩家IP位址",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-allowed-origins",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "管理服务器允许的资源包来源",
+            "Management Server Allowed Origins",
+            "管理伺服器允許的資源包來源",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-enabled",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: ["启用远程管理接口", "Enable Remote Management API", "啟用遠端管理介面"],
+    },
+    PropertySchema {
+        key: "management-server-host",
+        kind: PropertyKind::Text,
+        default: "localhost",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "服务端管理协议绑定的主机名或IP地址",
+            "Management Server Bind Host",
+            "管理協定綁定的IP",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-port",
+        kind: PropertyKind::Integer { min: 0, max: 65535 },
+        default: "0",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "服务端管理协议绑定的端口",
+            "Management Server Bind Port",
+            "管理協定綁定的埠",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-secret",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: ["管理服务访问密钥", "Management Service Access Key", "管理服務存取金鑰"],
+    },
+    PropertySchema {
+        key: "management-server-tls-enabled",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "是否启用管理服务端的 TLS（传输层安全性协议）加密",
+            "Enable TLS Encryption",
+            "啟用TLS加密",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-tls-keystore",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "保存服务端用于TLS加密的私钥和证书的密钥库（KeyStore）文件路径",
+            "TLS Keystore File Path",
+            "TLS金鑰庫檔案路徑",
+        ],
+    },
+    PropertySchema {
+        key: "management-server-tls-keystore-password",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "用于访问TLS密钥库（KeyStore）文件的密码",
+            "TLS Keystore Password",
+            "TLS金鑰庫密碼",
+        ],
+    },
+    PropertySchema {
+        key: "max-build-height",
+        kind: PropertyKind::Integer { min: 64, max: 256 },
+        default: "256",
+        category: "world",
+        since: "1.0",
+        removed: Some("1.17"),
+        descriptions: [
+            "最大建筑高度（1.17 起由维度类型决定）",
+            "Maximum Build Height (set by dimension type since 1.17)",
+            "最大建築高度（1.17 起由維度類型決定）",
+        ],
+    },
+    PropertySchema {
+        key: "max-chained-neighbor-updates",
+        kind: PropertyKind::Integer {
+            min: i32::MIN as i64,
+            max: i32::MAX as i64,
+        },
+        default: "1000000",
+        category: "performance",
+        since: "1.19",
+        removed: None,
+        descriptions: [
+            "最大连续连锁更新的次数",
+            "Maximum Chained Neighbor Updates",
+            "最大連鎖鄰居更新次數",
+        ],
+    },
+    PropertySchema {
+        key: "max-players",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "20",
+        category: "player",
+        since: "1.0",
+        removed: None,
+        descriptions: ["最大玩家数", "Max Players", "最大玩家數"],
+    },
+    PropertySchema {
+        key: "max-tick-time",
+        kind: PropertyKind::Integer { min: -1, max: i64::MAX },
+        default: "60000",
+        category: "performance",
+        since: "1.8",
+        removed: None,
+        descriptions: [
+            "最大tick时间(ms)，-1为禁用",
+            "Max Tick Time (ms), -1 to disable",
+            "最大tick時間(ms)，-1為停用",
+        ],
+    },
+    PropertySchema {
+        key: "max-world-size",
+        kind: PropertyKind::Integer { min: 1, max: 29999984 },
+        default: "29999984",
+        category: "world",
+        since: "1.8",
+        removed: None,
+        descriptions: [
+            "设置世界边界的最大允许半径值",
+            "Maximum World Border Radius",
+            "世界邊界最大半徑",
+        ],
+    },
+    PropertySchema {
+        key: "motd",
+        kind: PropertyKind::Text,
+        default: "A Minecraft Server",
+        category: "display",
+        since: "1.0",
+        removed: None,
+        descriptions: ["服务器描述(MOTD)", "Server Description (MOTD)", "伺服器描述(MOTD)"],
+    },
+    PropertySchema {
+        key: "network-compression-threshold",
+        kind: PropertyKind::Integer { min: -1, max: i32::MAX as i64 },
+        default: "256",
+        category: "performance",
+        since: "1.8",
+        removed: None,
+        descriptions: ["网络压缩阈值", "Network Compression Threshold", "網路壓縮閾值"],
+    },
+    PropertySchema {
+        key: "online-mode",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "player",
+        since: "1.0",
+        removed: None,
+        descriptions: ["正版验证", "Online Mode", "正版驗證"],
+    },
+    PropertySchema {
+        key: "op-permission-level",
+        kind: PropertyKind::Integer { min: 0, max: 4 },
+        default: "4",
+        category: "player",
+        since: "1.7.2",
+        removed: None,
+        descriptions: ["OP权限级别", "OP Permission Level", "OP權限等級"],
+    },
+    PropertySchema {
+        key: "pause-when-empty-seconds",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "60",
+        category: "performance",
+        since: "1.21.2",
+        removed: None,
+        descriptions: [
+            "当服务器空闲时自动暂停的秒数，0为禁用",
+            "Pause When Empty (seconds)",
+            "無人時自動暫停秒數",
+        ],
+    },
+    PropertySchema {
+        key: "player-idle-timeout",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "0",
+        category: "player",
+        since: "1.6.1",
+        removed: None,
+        descriptions: [
+            "玩家闲置超时时间（分钟），0为禁用",
+            "Player Idle Timeout (minutes), 0 to disable",
+            "玩家閒置逾時（分鐘），0停用",
+        ],
+    },
+    PropertySchema {
+        key: "prevent-proxy-connections",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "network",
+        since: "1.11",
+        removed: None,
+        descriptions: ["是否阻止代理连接", "Prevent Proxy Connections", "防止代理連線"],
+    },
+    PropertySchema {
+        key: "previews-chat",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "player",
+        since: "1.19",
+        removed: Some("1.19.3"),
+        descriptions: [
+            "启用聊天预览（仅 1.19 至 1.19.2）",
+            "Enable Chat Preview (1.19 to 1.19.2 only)",
+            "啟用聊天預覽（僅 1.19 至 1.19.2）",
+        ],
+    },
+    PropertySchema {
+        key: "pvp",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "game",
+        since: "1.0",
+        removed: None,
+        descriptions: ["允许PVP", "Allow PVP", "允許PVP"],
+    },
+    PropertySchema {
+        key: "query.port",
+        kind: PropertyKind::Integer { min: 1, max: 65535 },
+        default: "25565",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["Query协议端口", "Query Protocol Port", "Query協定埠"],
+    },
+    PropertySchema {
+        key: "rate-limit",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "0",
+        category: "network",
+        since: "1.16.2",
+        removed: None,
+        descriptions: [
+            "每个玩家每分钟允许的请求数量，0为禁用",
+            "Maximum Requests Per Minute Per Player, 0 to disable",
+            "每分鐘請求限制，0停用",
+        ],
+    },
+    PropertySchema {
+        key: "rcon.password",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["RCON密码", "RCON Password", "RCON密碼"],
+    },
+    PropertySchema {
+        key: "rcon.port",
+        kind: PropertyKind::Integer { min: 1, max: 65535 },
+        default: "25575",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["RCON端口", "RCON Port", "RCON埠"],
+    },
+    PropertySchema {
+        key: "region-file-compression",
+        kind: PropertyKind::Select(REGION_COMPRESSIONS),
+        default: "deflate",
+        category: "performance",
+        since: "1.20.5",
+        removed: None,
+        descriptions: ["区域文件压缩方式", "Region File Compression Method", "區域檔案壓縮方式"],
+    },
+    PropertySchema {
+        key: "require-resource-pack",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "display",
+        since: "1.17",
+        removed: None,
+        descriptions: ["是否强制玩家使用资源包", "Require Resource Pack", "要求資源包"],
+    },
+    PropertySchema {
+        key: "resource-pack",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "display",
+        since: "1.7.2",
+        removed: None,
+        descriptions: ["资源包URL", "Resource Pack URL", "資源包URL"],
+    },
+    PropertySchema {
+        key: "resource-pack-id",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "display",
+        since: "1.20.3",
+        removed: None,
+        descriptions: ["资源包ID", "Resource Pack ID", "資源包ID"],
+    },
+    PropertySchema {
+        key: "resource-pack-prompt",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "display",
+        since: "1.17",
+        removed: None,
+        descriptions: ["资源包提示", "Resource Pack Prompt", "資源包提示"],
+    },
+    PropertySchema {
+        key: "resource-pack-sha1",
+        kind: PropertyKind::Sha1,
+        default: "",
+        category: "display",
+        since: "1.9",
+        removed: None,
+        descriptions: ["资源包SHA1哈希", "Resource Pack SHA-1 Hash", "資源包SHA1雜湊"],
+    },
+    PropertySchema {
+        key: "server-ip",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: [
+            "服务器绑定IP，留空表示所有",
+            "Server Bind IP, leave empty for all",
+            "伺服器綁定IP，留空表示全部",
+        ],
+    },
+    PropertySchema {
+        key: "server-port",
+        kind: PropertyKind::Integer { min: 1, max: 65535 },
+        default: "25565",
+        category: "network",
+        since: "1.0",
+        removed: None,
+        descriptions: ["服务器端口", "Server Port", "伺服器埠號"],
+    },
+    PropertySchema {
+        key: "simulation-distance",
+        kind: PropertyKind::Integer { min: 3, max: 32 },
+        default: "10",
+        category: "performance",
+        since: "1.18",
+        removed: None,
+        descriptions: ["模拟距离", "Simulation Distance", "模擬距離"],
+    },
+    PropertySchema {
+        key: "snooper-enabled",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "other",
+        since: "1.3.1",
+        removed: Some("1.18"),
+        descriptions: [
+            "向 Mojang 发送匿名统计数据",
+            "Send Anonymous Statistics to Mojang",
+            "向 Mojang 傳送匿名統計資料",
+        ],
+    },
+    PropertySchema {
+        key: "spawn-animals",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "world",
+        since: "1.0",
+        removed: Some("1.21.2"),
+        descriptions: ["生成动物", "Spawn Animals", "生成動物"],
+    },
+    PropertySchema {
+        key: "spawn-monsters",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["生成怪物", "Spawn Monsters", "生成怪物"],
+    },
+    PropertySchema {
+        key: "spawn-npcs",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "world",
+        since: "1.0",
+        removed: Some("1.21.2"),
+        descriptions: ["生成NPC", "Spawn NPCs", "生成NPC"],
+    },
+    PropertySchema {
+        key: "spawn-protection",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "16",
+        category: "world",
+        since: "1.0",
+        removed: None,
+        descriptions: ["出生点保护半径", "Spawn Protection Radius", "重生點保護半徑"],
+    },
+    PropertySchema {
+        key: "status-heartbeat-interval",
+        kind: PropertyKind::Integer { min: 0, max: i32::MAX as i64 },
+        default: "0",
+        category: "network",
+        since: "1.21.9",
+        removed: None,
+        descriptions: [
+            "服务器状态心跳间隔（秒）",
+            "Status Heartbeat Interval (seconds)",
+            "狀態心跳間隔（秒）",
+        ],
+    },
+    PropertySchema {
+        key: "sync-chunk-writes",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "performance",
+        since: "1.16",
+        removed: None,
+        descriptions: ["同步区块写入", "Sync Chunk Writes", "同步區塊寫入"],
+    },
+    PropertySchema {
+        key: "text-filtering-config",
+        kind: PropertyKind::Text,
+        default: "",
+        category: "other",
+        since: "1.17",
+        removed: None,
+        descriptions: ["文本过滤配置", "Text Filtering Configuration File", "文字過濾設定"],
+    },
+    PropertySchema {
+        key: "text-filtering-version",
+        kind: PropertyKind::Integer { min: 0, max: 1 },
+        default: "0",
+        category: "other",
+        since: "1.21.2",
+        removed: None,
+        descriptions: ["文本过滤版本", "Text Filtering Version", "文字過濾版本"],
+    },
+    PropertySchema {
+        key: "use-native-transport",
+        kind: PropertyKind::Boolean,
+        default: "true",
+        category: "performance",
+        since: "1.8",
+        removed: None,
+        descriptions: [
+            "是否启用针对Linux平台的数据包收发优化",
+            "Use Linux Native Transport Optimization",
+            "啟用Linux原生傳輸最佳化",
+        ],
+    },
+    PropertySchema {
+        key: "view-distance",
+        kind: PropertyKind::Integer { min: 3, max: 32 },
+        default: "10",
+        category: "performance",
+        since: "1.0",
+        removed: None,
+        descriptions: ["视距", "View Distance", "視距"],
+    },
+    PropertySchema {
+        key: "white-list",
+        kind: PropertyKind::Boolean,
+        default: "false",
+        category: "player",
+        since: "1.0",
+        removed: None,
+        descriptions: ["启用白名单", "Enable Whitelist", "啟用白名單"],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_pass_validation() {
+        for property in all() {
+            assert!(property.validate(property.default).is_ok(), "{}", property.key);
+            assert!(property.descriptions.iter().all(|d| !d.is_empty()), "{}", property.key);
+        }
+        assert_eq!(find("view-distance").unwrap().description("en-US"), "View Distance");
+        assert_eq!(find("view-distance").unwrap().description("de-DE"), "View Distance");
+        assert_eq!(find("view-distance").unwrap().description("zh-TW"), "視距");
+    }
+
+    #[test]
+    fn reports_field_errors() {
+        let values: HashMap<String, String> = [
+            ("server-port", "abc"),
+            ("difficulty", "insane"),
+            ("view-distance", "64"),
+            ("gamemode", "1"),
+            ("online-mode", "TRUE"),
+            ("resource-pack-sha1", ""),
+            ("custom-plugin-key", "anything"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let errors = validate_properties(&values).unwrap_err();
+        let keys: Vec<&str> = errors.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["difficulty", "server-port", "view-distance"]);
+        assert_eq!(errors[2].to_string(), "view-distance: 必须在 3 到 32 之间");
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::config_parser;
+
+const SERVERDATA_AUTH: i32 = 3;
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+/// Minecraft 接受的最大命令长度
+const MAX_COMMAND_LEN: usize = 1446;
+/// 单个数据包的最大长度（不含长度字段）
+const MAX_PACKET_LEN: i32 = 4096 + 10;
+const DEFAULT_RCON_PORT: u16 = 25575;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// server.properties 中的 RCON 配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RconConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub password: String,
+}
+
+impl RconConfig {
+    /// 已启用且设置了密码
+    pub fn usable(&self) -> bool {
+        self.enabled && !self.password.is_empty()
+    }
+}
+
+/// 读取服务器目录中 server.properties 的 RCON 配置，文件不存在时返回 None
+pub fn read_config(server_path: &str) -> Option<RconConfig> {
+    let props = config_parser::read_server_dir_properties(server_path).ok()?;
+    Some(RconConfig {
+        enabled: props.get("enable-rcon").map(|v| v.trim()) == Some("true"),
+        port: props
+            .get("rcon.port")
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_RCON_PORT),
+        password: props.get("rcon.password").cloned().unwrap_or_default(),
+    })
+}
+
+/// 生成 32 位随机十六进制 RCON 密码
+pub fn generate_password() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// 通过本机 RCON 执行命令，返回服务器的响应
+pub fn exec(server_path: &str, command: &str) -> Result<String, String> {
+    let config = read_config(server_path).ok_or_else(|| "未找到 server.properties".to_string())?;
+    if !config.usable() {
+        return Err("RCON 未启用或未设置密码".to_string());
+    }
+    let mut client = RconClient::connect(("127.0.0.1", config.port), &config.password)?;
+    client.command(command)
+}
+
+/// Source RCON 协议客户端
+pub struct RconClient {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl RconClient {
+    /// 连接并认证
+    pub fn connect(addr: impl ToSocketAddrs, password: &str) -> Result<Self, String> {
+        let addr: SocketAddr = addr
+            .to_socket_addrs()
+            .map_err(|e| format!("无效的 RCON 地址: {}", e))?
+            .next()
+            .ok_or_else(|| "无效的 RCON 地址".to_string())?;
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+            .map_err(|e| format!("连接 RCON 失败: {}", e))?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+            .map_err(|e| format!("连接 RCON 失败: {}", e))?;
+
+        let mut client = RconClient { stream, next_id: 1 };
+        let id = client.send(SERVERDATA_AUTH, password)?;
+        // 部分实现会先返回一个空的 RESPONSE_VALUE，再返回认证结果
+        loop {
+            let (response_id, kind, _) = client.receive()?;
+            if kind == SERVERDATA_RESPONSE_VALUE {
+                continue;
+            }
+            if response_id == -1 {
+                return Err("RCON 密码错误".to_string());
+            }
+            if response_id == id {
+                return Ok(client);
+            }
+        }
+    }
+
+    /// 执行命令并返回完整响应（可能由多个数据包组成）
+    pub fn command(&mut self, command: &str) -> Result<String, String> {
+        if command.len() > MAX_COMMAND_LEN {
+            return Err(format!("命令过长（最多 {} 字节）", MAX_COMMAND_LEN));
+        }
+        let id = self.send(SERVERDATA_EXECCOMMAND, command)?;
+        // 随后发送一个空包作为结束标记，服务器按顺序响应，收到它的回应即表示命令响应已完整
+        let sentinel = self.send(SERVERDATA_RESPONSE_VALUE, "")?;
+
+        let mut output = String::new();
+        loop {
+            let (response_id, _, body) = self.receive()?;
+            if response_id == sentinel {
+                return Ok(output);
+            }
+            if response_id == -1 {
+                return Err("RCON 认证已失效".to_string());
+            }
+            if response_id == id {
+                output.push_str(&body);
+            }
+        }
+    }
+
+    fn send(&mut self, kind: i32, body: &str) -> Result<i32, String> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.stream
+            .write_all(&encode_packet(id, kind, body))
+            .map_err(|e| format!("RCON 发送失败: {}", e))?;
+        Ok(id)
+    }
+
+    fn receive(&mut self) -> Result<(i32, i32, String), String> {
+        read_packet(&mut self.stream).map_err(|e| format!("RCON 读取失败: {}", e))
+    }
+}
+
+/// 数据包：长度、ID、类型（均为 i32 小端）、以 \0 结尾的正文，再加一个 \0
+pub fn encode_packet(id: i32, kind: i32, body: &str) -> Vec<u8> {
+    let length = (body.len() + 10) as i32;
+    let mut packet = Vec::with_capacity(length as usize + 4);
+    packet.extend_from_slice(&length.to_le_bytes());
+    packet.extend_from_slice(&id.to_le_bytes());
+    packet.extend_from_slice(&kind.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    packet
+}
+
+pub fn read_packet(stream: &mut impl Read) -> std::io::Result<(i32, i32, String)> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    let length = i32::from_le_bytes(header);
+    if !(10..=MAX_PACKET_LEN).contains(&length) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("无效的数据包长度 {}", length),
+        ));
+    }
+    let mut payload = vec![0u8; length as usize];
+    stream.read_exact(&mut payload)?;
+    let id = i32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]);
+    let kind = i32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+    let body_end = payload[8..]
+        .iter()
+        .position(|b| *b == 0)
+        .map(|p| p + 8)
+        .unwrap_or(payload.len());
+    let body = String::from_utf8_lossy(&payload[8..body_end]).to_string();
+    Ok((id, kind, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// 模拟 Minecraft 的 RCON 实现：命令响应被拆成两个数据包，对未知类型回复 Unknown request
+    fn spawn_mock_server(password: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                while let Ok((id, kind, body)) = read_packet(&mut stream) {
+                    let reply = match kind {
+                        SERVERDATA_AUTH if body == password => encode_packet(id, 2, ""),
+                        SERVERDATA_AUTH => encode_packet(-1, 2, ""),
+                        SERVERDATA_EXECCOMMAND => {
+                            let mut reply = encode_packet(id, 0, &format!("Executed: {}", body));
+                            reply.extend(encode_packet(id, 0, " (part 2)"));
+                            reply
+                        }
+                        _ => encode_packet(id, 0, &format!("Unknown request {:x}", kind)),
+                    };
+                    stream.write_all(&reply).unwrap();
+                }
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn executes_commands_against_mock_server() {
+        let port = spawn_mock_server("secret");
+        let mut client = RconClient::connect(("127.0.0.1", port), "secret").unwrap();
+        assert_eq!(client.command("list").unwrap(), "Executed: list (part 2)");
+        assert_eq!(client.command("say hi").unwrap(), "Executed: say hi (part 2)");
+        drop(client);
+
+        let error = RconClient::connect(("127.0.0.1", port), "wrong")
+            .err()
+            .unwrap();
+        assert_eq!(error, "RCON 密码错误");
+    }
+}
@@ -1,12 +1,20 @@
 use std::collections::{HashMap, HashSet};
 use std::io::{Read, Write};
 use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
-
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::console_response::ResponsePattern;
+use super::loader_installer::{self, InstalledCore};
+use super::{
+    attached_server, crash_analyzer, curseforge, jvm_presets, mod_inventory, mrpack, port_manager,
+    preflight,
+};
 use crate::models::server::*;
 
 const DATA_FILE: &str = "sea_lantern_servers.json";
+/// 未同意 EULA 时服务端输出的提示
+const EULA_REQUIRED_MESSAGE: &str = "You need to agree to the EULA in order to run the server";
 
 #[derive(Clone, Copy, Debug)]
 enum ManagedConsoleEncoding {
@@ -38,6 +46,10 @@ pub struct ServerManager {
     pub processes: Mutex<HashMap<String, Child>>,
     pub stopping_servers: Mutex<HashSet<String>>,
     pub logs: Mutex<HashMap<String, Vec<String>>>,
+    /// 等待控制台输出的监听者，接收端释放后自动移除
+    pub console_listeners: Mutex<HashMap<String, Vec<mpsc::Sender<String>>>>,
+    /// 异常退出的服务器的诊断结果，再次启动时清除
+    pub crashes: Mutex<HashMap<String, CrashDiagnosis>>,
     pub data_dir: Mutex<String>,
 }
 
@@ -54,6 +66,8 @@ impl ServerManager {
             processes: Mutex::new(HashMap::new()),
             stopping_servers: Mutex::new(HashSet::new()),
             logs: Mutex::new(logs_map),
+            console_listeners: Mutex::new(HashMap::new()),
+            crashes: Mutex::new(HashMap::new()),
             data_dir: Mutex::new(data_dir),
         }
     }
@@ -119,6 +133,7 @@ impl ServerManager {
             format!("-Dsun.stdout.encoding={}", java_encoding),
             format!("-Dsun.stderr.encoding={}", java_encoding),
         ];
+        args.extend(self.preset_jvm_args(server));
 
         let jvm = settings.default_jvm_args.trim();
         if !jvm.is_empty() {
@@ -129,6 +144,36 @@ impl ServerManager {
         args
     }
 
+    /// 预设参数按当前 Java 生成，不支持的参数被去掉并记录到控制台
+    fn preset_jvm_args(&self, server: &ServerInstance) -> Vec<String> {
+        let Some(preset) = server.jvm_preset.as_deref() else {
+            return Vec::new();
+        };
+        let Ok(java) = super::java_detector::validate_java(&server.java_path) else {
+            return Vec::new();
+        };
+        let flags = match jvm_presets::flags(
+            preset,
+            server.max_memory,
+            java.major_version,
+            java.vendor == "GraalVM",
+        ) {
+            Ok(flags) => flags,
+            Err(e) => {
+                self.append_log(&server.id, &format!("[Sea Lantern] {}", e));
+                return Vec::new();
+            }
+        };
+        let (kept, dropped) = jvm_presets::retain_supported(&server.java_path, flags);
+        if !dropped.is_empty() {
+            self.append_log(
+                &server.id,
+                &format!("[Sea Lantern] 当前 Java 不支持以下参数，已忽略: {}", dropped.join(" ")),
+            );
+        }
+        kept
+    }
+
     fn write_user_jvm_args(
         &self,
         server: &ServerInstance,
@@ -148,6 +193,7 @@ impl ServerManager {
     }
 
     pub fn create_server(&self, req: CreateServerRequest) -> Result<ServerInstance, String> {
+        port_manager::ensure_unclaimed(req.port, None, &self.get_server_list())?;
         let id = uuid::Uuid::new_v4().to_string();
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -176,14 +222,22 @@ impl ServerManager {
             created_at: now,
             last_started_at: None,
             commands: Vec::new(),
+            mod_profiles: Vec::new(),
+            active_mod_profile: None,
+            attach: None,
+            eula: None,
+            jvm_preset: None,
+            pending_changes: None,
         };
         self.servers.lock().unwrap().push(server.clone());
         self.logs.lock().unwrap().insert(id, Vec::new());
         self.save();
+        self.write_port(&server);
         Ok(server)
     }
 
     pub fn import_server(&self, req: ImportServerRequest) -> Result<ServerInstance, String> {
+        port_manager::ensure_unclaimed(req.port, None, &self.get_server_list())?;
         let startup_mode = normalize_startup_mode(&req.startup_mode).to_string();
         let source_startup_file = std::path::Path::new(&req.jar_path);
         if !source_startup_file.exists() {
@@ -265,27 +319,36 @@ impl ServerManager {
             created_at: now,
             last_started_at: None,
             commands: Vec::new(),
+            mod_profiles: Vec::new(),
+            active_mod_profile: None,
+            attach: None,
+            eula: None,
+            jvm_preset: None,
+            pending_changes: None,
         };
 
         self.servers.lock().unwrap().push(server.clone());
         self.logs.lock().unwrap().insert(id, Vec::new());
         self.save();
+        self.write_port(&server);
         Ok(server)
     }
 
+    /// 导入整合包文件夹或服务端包 zip；自带安装器或启动脚本的服务端包会先完成安装
     pub fn import_modpack(&self, req: ImportModpackRequest) -> Result<ServerInstance, String> {
+        port_manager::ensure_unclaimed(req.port, None, &self.get_server_list())?;
         let source_path = std::path::Path::new(&req.modpack_path);
         if !source_path.exists() {
             return Err(format!("整合包文件夹不存在: {}", req.modpack_path));
         }
-        if !source_path.is_dir() {
-            return Err("所选路径不是文件夹".to_string());
+        let is_zip = source_path.is_file()
+            && source_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+        if !source_path.is_dir() && !is_zip {
+            return Err("所选路径不是文件夹或 zip 压缩包".to_string());
         }
 
-        // 查找服务端JAR文件
-        let source_jar = find_server_jar(source_path)?;
-        println!("找到服务端JAR文件: {}", source_jar);
-
         // 在软件目录下创建服务器文件夹
         let id = uuid::Uuid::new_v4().to_string();
         let data_dir = self.data_dir.lock().unwrap().clone();
@@ -295,35 +358,193 @@ impl ServerManager {
         // 创建服务器目录
         std::fs::create_dir_all(&server_dir).map_err(|e| format!("无法创建服务器目录: {}", e))?;
 
-        // 复制整合包文件夹的所有内容到服务器目录
-        println!("正在复制整合包文件: {} -> {}", source_path.display(), server_dir.display());
-        copy_dir_recursive(source_path, &server_dir)
-            .map_err(|e| format!("复制整合包文件失败: {}", e))?;
+        let core = match self.unpack_server_pack(source_path, is_zip, &server_dir, &req.java_path) {
+            Ok(core) => core,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&server_dir);
+                return Err(e);
+            }
+        };
 
-        // 获取复制后的JAR文件路径
-        let jar_filename = std::path::Path::new(&source_jar)
-            .file_name()
-            .ok_or_else(|| "无法获取JAR文件名".to_string())?;
-        let dest_jar = server_dir.join(jar_filename);
+        let server = self.register_imported(id, req, &server_dir, "unknown".into(), &core, "");
+        println!(
+            "创建服务器实例: id={}, path={}, jar_path={}",
+            server.id, server.path, server.jar_path
+        );
+        Ok(server)
+    }
 
-        if !dest_jar.exists() {
-            return Err(format!("复制后的JAR文件不存在: {}", dest_jar.display()));
+    /// 复制或解压整合包到 server_dir，并确定启动方式
+    fn unpack_server_pack(
+        &self,
+        source_path: &std::path::Path,
+        is_zip: bool,
+        server_dir: &std::path::Path,
+        java_path: &str,
+    ) -> Result<InstalledCore, String> {
+        if is_zip {
+            println!("正在解压服务端包: {} -> {}", source_path.display(), server_dir.display());
+            let file =
+                std::fs::File::open(source_path).map_err(|e| format!("无法打开压缩包: {}", e))?;
+            let mut archive =
+                zip::ZipArchive::new(file).map_err(|e| format!("无效的 zip 压缩包: {}", e))?;
+            let root = mrpack::common_root(&mut archive);
+            mrpack::extract_zip_dir(&mut archive, &root, server_dir)?;
+        } else {
+            // 复制整合包文件夹的所有内容到服务器目录
+            println!("正在复制整合包文件: {} -> {}", source_path.display(), server_dir.display());
+            copy_dir_recursive(source_path, server_dir)
+                .map_err(|e| format!("复制整合包文件失败: {}", e))?;
         }
 
-        // 创建服务器实例
+        if let Some(core) = loader_installer::prepare_server_pack(server_dir, java_path)? {
+            return Ok(core);
+        }
+
+        // 查找服务端JAR文件
+        let jar_path = find_server_jar(server_dir)?;
+        println!("找到服务端JAR文件: {}", jar_path);
+        Ok(InstalledCore {
+            core_type: "modpack".to_string(),
+            core_version: String::new(),
+            jar_path: jar_path.into(),
+            startup_mode: "jar".to_string(),
+        })
+    }
+
+    /// 从 .mrpack 文件创建服务器：下载模组、应用覆盖文件并安装对应的加载器
+    pub async fn import_mrpack(&self, req: ImportModpackRequest) -> Result<ServerInstance, String> {
+        port_manager::ensure_unclaimed(req.port, None, &self.get_server_list())?;
+        let pack_path = std::path::Path::new(&req.modpack_path);
+        if !pack_path.is_file() {
+            return Err(format!("整合包文件不存在: {}", req.modpack_path));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let data_dir = self.data_dir.lock().unwrap().clone();
+        let server_dir = std::path::Path::new(&data_dir).join("servers").join(&id);
+
+        println!("正在安装 .mrpack 整合包: {} -> {}", pack_path.display(), server_dir.display());
+        let imported = match mrpack::install_mrpack(pack_path, &server_dir, &req.java_path).await {
+            Ok(imported) => imported,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&server_dir);
+                return Err(e);
+            }
+        };
+
+        let server = self.register_imported(
+            id,
+            req,
+            &server_dir,
+            imported.mc_version,
+            &imported.core,
+            &imported.name,
+        );
+        if !imported.skipped.is_empty() {
+            self.append_log(
+                &server.id,
+                &format!(
+                    "[Sea Lantern] 已跳过 {} 个服务端不支持的文件: {}",
+                    imported.skipped.len(),
+                    imported.skipped.join(", ")
+                ),
+            );
+        }
+        Ok(server)
+    }
+
+    /// 从 CurseForge 整合包 zip 创建服务器；需要手动下载的文件会写入控制台日志
+    pub async fn import_curseforge(
+        &self,
+        req: ImportModpackRequest,
+    ) -> Result<ServerInstance, String> {
+        port_manager::ensure_unclaimed(req.port, None, &self.get_server_list())?;
+        let pack_path = std::path::Path::new(&req.modpack_path);
+        if !pack_path.is_file() {
+            return Err(format!("整合包文件不存在: {}", req.modpack_path));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let data_dir = self.data_dir.lock().unwrap().clone();
+        let server_dir = std::path::Path::new(&data_dir).join("servers").join(&id);
+
+        println!(
+            "正在安装 CurseForge 整合包: {} -> {}",
+            pack_path.display(),
+            server_dir.display()
+        );
+        let imported =
+            match curseforge::install_curseforge_pack(pack_path, &server_dir, &req.java_path).await
+            {
+                Ok(imported) => imported,
+                Err(e) => {
+                    let _ = std::fs::remove_dir_all(&server_dir);
+                    return Err(e);
+                }
+            };
+
+        let server = self.register_imported(
+            id,
+            req,
+            &server_dir,
+            imported.mc_version,
+            &imported.core,
+            &imported.name,
+        );
+        if !imported.blocked.is_empty() {
+            self.append_log(
+                &server.id,
+                &format!(
+                    "[Sea Lantern] 以下 {} 个文件的作者禁止第三方下载，请手动下载后放入 mods 目录:",
+                    imported.blocked.len()
+                ),
+            );
+            for file in &imported.blocked {
+                self.append_log(&server.id, &format!("  - {}: {}", file.file_name, file.url));
+            }
+        }
+        if !imported.skipped.is_empty() {
+            self.append_log(
+                &server.id,
+                &format!(
+                    "[Sea Lantern] 已跳过 {} 个可选或非模组文件: {}",
+                    imported.skipped.len(),
+                    imported.skipped.join(", ")
+                ),
+            );
+        }
+        Ok(server)
+    }
+
+    /// 保存导入完成的服务器，请求中的名称为空时使用整合包名称
+    fn register_imported(
+        &self,
+        id: String,
+        req: ImportModpackRequest,
+        server_dir: &std::path::Path,
+        mc_version: String,
+        core: &InstalledCore,
+        pack_name: &str,
+    ) -> ServerInstance {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
+        let name = if req.name.trim().is_empty() {
+            pack_name.to_string()
+        } else {
+            req.name
+        };
         let server = ServerInstance {
             id: id.clone(),
-            name: req.name,
-            core_type: "modpack".into(),
-            core_version: String::new(),
-            mc_version: "unknown".into(),
+            name,
+            core_type: core.core_type.clone(),
+            core_version: core.core_version.clone(),
+            mc_version,
             path: server_dir.to_string_lossy().to_string(),
-            jar_path: dest_jar.to_string_lossy().to_string(),
-            startup_mode: "jar".to_string(),
+            jar_path: core.jar_path.to_string_lossy().to_string(),
+            startup_mode: core.startup_mode.clone(),
             java_path: req.java_path,
             max_memory: req.max_memory,
             min_memory: req.min_memory,
@@ -332,21 +553,23 @@ impl ServerManager {
             created_at: now,
             last_started_at: None,
             commands: Vec::new(),
+            mod_profiles: Vec::new(),
+            active_mod_profile: None,
+            attach: None,
+            eula: None,
+            jvm_preset: None,
+            pending_changes: None,
         };
 
-        println!(
-            "创建服务器实例: id={}, path={}, jar_path={}",
-            server.id, server.path, server.jar_path
-        );
-
         self.servers.lock().unwrap().push(server.clone());
         self.logs.lock().unwrap().insert(id, Vec::new());
         self.save();
-        Ok(server)
+        self.write_port(&server);
+        server
     }
 
     pub fn start_server(&self, id: &str) -> Result<(), String> {
-        let server = {
+        let mut server = {
             let servers = self.servers.lock().unwrap();
             servers
                 .iter()
@@ -355,31 +578,57 @@ impl ServerManager {
                 .clone()
         };
 
+        if let Some(attach) = &server.attach {
+            return attached_server::start(&server, attach);
+        }
+
         println!(
             "准备启动服务器: id={}, name={}, startup_mode={}, startup_path={}, java_path={}",
             server.id, server.name, server.startup_mode, server.jar_path, server.java_path
         );
 
         // Check if already running
-        {
-            let mut procs = self.processes.lock().unwrap();
-            if let Some(child) = procs.get_mut(id) {
-                match child.try_wait() {
-                    Ok(Some(_)) => {
-                        procs.remove(id);
-                    } // Dead process, clean up
-                    Ok(None) => return Err("服务器已在运行中".to_string()),
-                    Err(_) => {
-                        procs.remove(id);
-                    }
-                }
-            }
+        if self.reap_process(id, self.is_stopping(id)) {
+            return Err("服务器已在运行中".to_string());
+        }
+
+        if let Some(updated) = self.apply_pending_changes(id)? {
+            server = updated;
         }
 
+        // server.properties 可能在外部被修改，以文件中的端口为准
+        if let Some(port) = self.sync_port_from_properties(id) {
+            server.port = port;
+        }
+        self.crashes.lock().unwrap().remove(id);
         let settings = self.get_app_settings();
-        if settings.auto_accept_eula {
-            let eula = std::path::Path::new(&server.path).join("eula.txt");
-            let _ = std::fs::write(&eula, "# Auto-accepted by Sea Lantern\neula=true\n");
+        let report = self.run_preflight(&server, &settings);
+        if report.has_errors() {
+            return Err(format!(
+                "启动前检查未通过：\n{}",
+                preflight::summarize(&report, CheckLevel::Error)
+            ));
+        }
+        for check in report.with_level(CheckLevel::Warning) {
+            self.append_log(
+                id,
+                &format!("[Sea Lantern] 启动前检查（{}）: {}", check.name, check.message),
+            );
+        }
+        if !eula_accepted(&server.path) {
+            if apply_stored_eula(&server)? {
+                self.append_log(id, "[Sea Lantern] 已根据记录的同意重新写入 eula.txt");
+            } else if settings.auto_accept_eula {
+                self.record_eula_acceptance(id, true)?;
+            }
+        }
+
+        // 停止期间到期的临时封禁在启动前解除
+        for record in super::ban_manager::expire_due(id) {
+            self.append_log(
+                id,
+                &format!("[Sea Lantern] 临时封禁已到期，已解除: {}", record.target),
+            );
         }
 
         //预处理脚本
@@ -527,11 +776,9 @@ impl ServerManager {
         self.append_log(id, "[Sea Lantern] 服务器启动中...");
 
         // 启动日志读取线程
-        let logs_ref = &self.logs as *const Mutex<HashMap<String, Vec<String>>>;
         let procs_ref = &self.processes as *const Mutex<HashMap<String, Child>>;
         let max_lines = settings.max_log_lines as usize;
         let lid = id.to_string();
-        let ptr = logs_ref as usize;
         let p_ptr = procs_ref as usize;
         let ml = max_lines;
         let log_path = log_file.clone();
@@ -563,24 +810,14 @@ impl ServerManager {
 
                         if len > last_size {
                             if file.seek(std::io::SeekFrom::Start(pos)).is_ok() {
-                                let m = unsafe {
-                                    &*(ptr as *const Mutex<HashMap<String, Vec<String>>>)
-                                };
                                 let mut buffer = Vec::new();
 
                                 if file.read_to_end(&mut buffer).is_ok() {
                                     let content = decode_console_bytes(&buffer);
+                                    let manager = super::global::server_manager();
                                     for line in content.lines() {
                                         if !line.trim().is_empty() {
-                                            if let Ok(mut l) = m.lock() {
-                                                if let Some(v) = l.get_mut(&lid) {
-                                                    v.push(line.to_string());
-                                                    if v.len() > ml {
-                                                        let d = v.len() - ml;
-                                                        v.drain(0..d);
-                                                    }
-                                                }
-                                            }
+                                            manager.push_console_line(&lid, line, ml);
                                         }
                                     }
                                     pos = len;
@@ -597,27 +834,15 @@ impl ServerManager {
     }
 
     pub fn stop_server(&self, id: &str) -> Result<(), String> {
-        // Check if actually running first
-        let is_running = {
-            let mut procs = self.processes.lock().unwrap();
-            if let Some(child) = procs.get_mut(id) {
-                match child.try_wait() {
-                    Ok(Some(_)) => {
-                        procs.remove(id);
-                        false
-                    }
-                    Ok(None) => true,
-                    Err(_) => {
-                        procs.remove(id);
-                        false
-                    }
-                }
-            } else {
-                false
-            }
-        };
+        if let Some((server, attach)) = self.attached(id) {
+            let result = attached_server::stop(&server, &attach);
+            self.clear_stopping(id);
+            return result;
+        }
 
-        if !is_running {
+        // Check if actually running first
+        // 发送停止命令之前就已退出的进程不是由 Sea Lantern 停止的，按意外退出处理
+        if !self.reap_process(id, false) {
             self.clear_stopping(id);
             self.append_log(id, "[Sea Lantern] 服务器未运行");
             return Ok(());
@@ -744,6 +969,9 @@ impl ServerManager {
     }
 
     pub fn send_command(&self, id: &str, command: &str) -> Result<(), String> {
+        if let Some((server, _)) = self.attached(id) {
+            return attached_server::send_command(&server, command);
+        }
         let mut procs = self.processes.lock().unwrap();
         let child = procs
             .get_mut(id)
@@ -755,33 +983,85 @@ impl ServerManager {
         Ok(())
     }
 
+    /// 发送命令并收集之后的控制台输出，直到匹配成功或失败模式，或超时
+    pub fn send_command_and_wait(
+        &self,
+        id: &str,
+        command: &str,
+        pattern: &ResponsePattern,
+        timeout: Duration,
+    ) -> Result<CommandResponse, String> {
+        // 先注册监听再发送，避免错过快速返回的输出
+        let (tx, rx) = mpsc::channel();
+        self.console_listeners
+            .lock()
+            .unwrap()
+            .entry(id.to_string())
+            .or_default()
+            .push(tx);
+        self.send_command(id, command)?;
+
+        let deadline = Instant::now() + timeout;
+        let mut lines = Vec::new();
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let Ok(line) = rx.recv_timeout(remaining) else {
+                break;
+            };
+            let verdict = pattern.classify(&line);
+            lines.push(line);
+            if verdict.is_some() {
+                return Ok(CommandResponse { accepted: verdict, lines });
+            }
+        }
+        Ok(CommandResponse { accepted: None, lines })
+    }
+
+    /// 追加一行控制台输出，并通知等待命令响应的监听者
+    pub fn push_console_line(&self, id: &str, line: &str, max_lines: usize) {
+        if let Ok(mut logs) = self.logs.lock() {
+            if let Some(v) = logs.get_mut(id) {
+                v.push(line.to_string());
+                if v.len() > max_lines {
+                    let d = v.len() - max_lines;
+                    v.drain(0..d);
+                }
+            }
+        }
+        self.notify_console_line(id, line);
+    }
+
+    fn notify_console_line(&self, id: &str, line: &str) {
+        if line.contains(EULA_REQUIRED_MESSAGE) {
+            self.request_eula(id);
+        }
+        if let Ok(mut listeners) = self.console_listeners.lock() {
+            if let Some(senders) = listeners.get_mut(id) {
+                senders.retain(|tx| tx.send(line.to_string()).is_ok());
+            }
+        }
+    }
+
     pub fn get_server_list(&self) -> Vec<ServerInstance> {
         self.servers.lock().unwrap().clone()
     }
 
     pub fn get_server_status(&self, id: &str) -> ServerStatusInfo {
-        let mut procs = self.processes.lock().unwrap();
-        let is_running = if let Some(child) = procs.get_mut(id) {
-            match child.try_wait() {
-                Ok(Some(_)) => {
-                    procs.remove(id);
-                    false
-                }
-                Ok(None) => true,
-                Err(_) => {
-                    procs.remove(id);
-                    false
-                }
+        if let Some((server, attach)) = self.attached(id) {
+            let mut info = attached_server::status(&server, &attach);
+            if self.is_stopping(id) {
+                info.status = ServerStatus::Stopping;
             }
-        } else {
-            false
-        };
+            return info;
+        }
+        let is_running = self.reap_process(id, self.is_stopping(id));
         ServerStatusInfo {
             id: id.to_string(),
             status: if self.is_stopping(id) {
                 ServerStatus::Stopping
             } else if is_running {
                 ServerStatus::Running
+            } else if self.crashes.lock().unwrap().contains_key(id) {
+                ServerStatus::Error
             } else {
                 ServerStatus::Stopped
             },
@@ -802,6 +1082,8 @@ impl ServerManager {
         self.servers.lock().unwrap().retain(|s| s.id != id);
         self.logs.lock().unwrap().remove(id);
         self.save();
+        super::config_history::remove_history(id);
+        super::ban_manager::remove_history(id);
         Ok(())
     }
 
@@ -818,7 +1100,7 @@ impl ServerManager {
         }
     }
 
-    fn append_log(&self, id: &str, msg: &str) {
+    pub fn append_log(&self, id: &str, msg: &str) {
         if let Ok(mut logs) = self.logs.lock() {
             if let Some(v) = logs.get_mut(id) {
                 v.push(msg.to_string());
@@ -907,10 +1189,49 @@ impl ServerManager {
         }
     }
 
-    pub fn update_server_name(&self, id: &str, name: &str) -> Result<(), String> {
+    /// 以 mods 目录当前启用的 jar 保存为配置档，同名配置档会被覆盖
+    pub fn save_mod_profile(&self, server_id: &str, name: &str) -> Result<ModProfile, String> {
+        let server_path = self.get_server_path(server_id)?;
+        let mods_dir = mod_inventory::content_dir(&server_path, "mods")?;
+        let enabled_jars = mod_inventory::enabled_jars(&mods_dir)?;
+
         let mut servers = self.servers.lock().unwrap();
-        if let Some(server) = servers.iter_mut().find(|s| s.id == id) {
-            server.name = name.to_string();
+        let server = servers
+            .iter_mut()
+            .find(|s| s.id == server_id)
+            .ok_or_else(|| "未找到服务器".to_string())?;
+        let profile = match server.mod_profiles.iter_mut().find(|p| p.name == name) {
+            Some(existing) => {
+                existing.enabled_jars = enabled_jars;
+                existing.clone()
+            }
+            None => {
+                let profile = ModProfile {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    enabled_jars,
+                };
+                server.mod_profiles.push(profile.clone());
+                profile
+            }
+        };
+        server.active_mod_profile = Some(profile.id.clone());
+        drop(servers);
+        self.save();
+        Ok(profile)
+    }
+
+    pub fn delete_mod_profile(&self, server_id: &str, profile_id: &str) -> Result<(), String> {
+        let mut servers = self.servers.lock().unwrap();
+        if let Some(server) = servers.iter_mut().find(|s| s.id == server_id) {
+            let initial_len = server.mod_profiles.len();
+            server.mod_profiles.retain(|p| p.id != profile_id);
+            if initial_len == server.mod_profiles.len() {
+                return Err("未找到模组配置档".to_string());
+            }
+            if server.active_mod_profile.as_deref() == Some(profile_id) {
+                server.active_mod_profile = None;
+            }
             drop(servers);
             self.save();
             Ok(())
@@ -918,6 +1239,409 @@ impl ServerManager {
             Err("未找到服务器".to_string())
         }
     }
+
+    /// 切换到指定模组配置档，仅允许在服务器停止时进行，返回提示信息
+    pub fn apply_mod_profile(
+        &self,
+        server_id: &str,
+        profile_id: &str,
+    ) -> Result<Vec<String>, String> {
+        if self.get_server_status(server_id).status.is_active() {
+            return Err("请先停止服务器再切换模组配置档".to_string());
+        }
+
+        let (server_path, profile) = {
+            let servers = self.servers.lock().unwrap();
+            let server = servers
+                .iter()
+                .find(|s| s.id == server_id)
+                .ok_or_else(|| "未找到服务器".to_string())?;
+            let profile = server
+                .mod_profiles
+                .iter()
+                .find(|p| p.id == profile_id)
+                .cloned()
+                .ok_or_else(|| "未找到模组配置档".to_string())?;
+            (server.path.clone(), profile)
+        };
+
+        let mods_dir = mod_inventory::content_dir(&server_path, "mods")?;
+        let warnings = mod_inventory::apply_enabled_set(&mods_dir, &profile.enabled_jars)?;
+
+        if let Some(server) = self
+            .servers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|s| s.id == server_id)
+        {
+            server.active_mod_profile = Some(profile.id.clone());
+        }
+        self.save();
+        Ok(warnings)
+    }
+
+    /// 登记一个在外部运行的服务器，不复制任何文件
+    pub fn attach_server(&self, req: AttachServerRequest) -> Result<ServerInstance, String> {
+        let path = std::path::Path::new(&req.path);
+        if !path.is_dir() {
+            return Err(format!("服务器目录不存在: {}", req.path));
+        }
+        let port = req
+            .port
+            .or_else(|| port_manager::read_server_port(&req.path))
+            .unwrap_or(25565);
+        port_manager::ensure_unclaimed(port, None, &self.get_server_list())?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let server = ServerInstance {
+            id: id.clone(),
+            name: req.name,
+            core_type: "unknown".into(),
+            core_version: String::new(),
+            mc_version: "unknown".into(),
+            path: req.path,
+            jar_path: String::new(),
+            startup_mode: "jar".into(),
+            java_path: String::new(),
+            max_memory: 0,
+            min_memory: 0,
+            jvm_args: Vec::new(),
+            port,
+            created_at: now,
+            last_started_at: None,
+            commands: Vec::new(),
+            mod_profiles: Vec::new(),
+            active_mod_profile: None,
+            attach: Some(req.attach),
+            eula: None,
+            jvm_preset: None,
+            pending_changes: None,
+        };
+        self.servers.lock().unwrap().push(server.clone());
+        self.logs.lock().unwrap().insert(id.clone(), Vec::new());
+        self.save();
+        attached_server::ensure_log_tail(&id);
+        Ok(server)
+    }
+
+    /// 修改附加模式配置；设为 None 时改由 Sea Lantern 管理进程
+    pub fn update_attach_config(
+        &self,
+        id: &str,
+        attach: Option<AttachConfig>,
+    ) -> Result<(), String> {
+        if self.processes.lock().unwrap().contains_key(id) {
+            return Err("请先停止服务器".to_string());
+        }
+        let attached = attach.is_some();
+        {
+            let mut servers = self.servers.lock().unwrap();
+            let server = servers
+                .iter_mut()
+                .find(|s| s.id == id)
+                .ok_or_else(|| "未找到服务器".to_string())?;
+            server.attach = attach;
+        }
+        self.save();
+        if attached {
+            attached_server::ensure_log_tail(id);
+        }
+        Ok(())
+    }
+
+    /// 用户同意 Minecraft EULA：记录同意信息，并把 eula.txt 中的 eula 设为 true
+    pub fn accept_eula(&self, id: &str) -> Result<(), String> {
+        self.record_eula_acceptance(id, false)
+    }
+
+    fn record_eula_acceptance(&self, id: &str, automatic: bool) -> Result<(), String> {
+        let acceptance = EulaAcceptance {
+            accepted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            accepted_by: current_user(),
+            automatic,
+        };
+        let path = {
+            let mut servers = self.servers.lock().unwrap();
+            let server = servers
+                .iter_mut()
+                .find(|s| s.id == id)
+                .ok_or_else(|| "未找到服务器".to_string())?;
+            server.eula = Some(acceptance);
+            server.path.clone()
+        };
+        self.save();
+        write_eula(&path)?;
+        self.append_log(
+            id,
+            if automatic {
+                "[Sea Lantern] 已根据设置自动同意 Minecraft EULA"
+            } else {
+                "[Sea Lantern] 已同意 Minecraft EULA"
+            },
+        );
+        Ok(())
+    }
+
+    /// 服务器因未同意 EULA 退出时，提示用户并通知前端请求同意
+    fn request_eula(&self, id: &str) {
+        let Some(server) = self.get_server_list().into_iter().find(|s| s.id == id) else {
+            return;
+        };
+        if server.attach.is_some() {
+            return;
+        }
+        self.append_log(
+            id,
+            "[Sea Lantern] 服务器需要同意 Minecraft EULA (https://aka.ms/MinecraftEULA) 才能运行",
+        );
+        super::global::emit(
+            "server-eula-required",
+            EulaRequiredEvent {
+                server_id: server.id,
+                server_name: server.name,
+            },
+        );
+    }
+
+    /// 受管进程是否仍在运行；已退出的进程从表中移除，stopping 为 false 时按意外退出处理
+    fn reap_process(&self, id: &str, stopping: bool) -> bool {
+        let exited = {
+            let mut procs = self.processes.lock().unwrap();
+            let Some(child) = procs.get_mut(id) else {
+                return false;
+            };
+            let exited = match child.try_wait() {
+                Ok(None) => return true,
+                Ok(Some(exit)) => Some(exit),
+                Err(_) => None,
+            };
+            procs.remove(id);
+            exited
+        };
+        if let Some(exit) = exited {
+            if !stopping {
+                self.on_unexpected_exit(id, exit);
+            }
+        }
+        false
+    }
+
+    /// 服务器并非由 Sea Lantern 停止而退出：非零退出码或本次启动后出现崩溃报告时视为崩溃
+    fn on_unexpected_exit(&self, id: &str, exit: std::process::ExitStatus) {
+        let Some(server) = self.get_server_list().into_iter().find(|s| s.id == id) else {
+            return;
+        };
+        let diagnosis =
+            crash_analyzer::analyze(id, &server.path, server.last_started_at, exit.code());
+        let crashed = !exit.success()
+            || diagnosis.crash_report.is_some()
+            || diagnosis.jvm_error_log.is_some();
+        if !crashed {
+            return;
+        }
+
+        match exit.code() {
+            Some(code) => {
+                self.append_log(id, &format!("[Sea Lantern] 服务器异常退出，退出码 {}", code))
+            }
+            None => self.append_log(id, "[Sea Lantern] 服务器异常退出"),
+        }
+        if diagnosis.findings.is_empty() {
+            self.append_log(id, "[Sea Lantern] 未能识别崩溃原因，请查看崩溃报告或控制台输出");
+        }
+        for finding in &diagnosis.findings {
+            self.append_log(
+                id,
+                &format!(
+                    "[Sea Lantern] {}: {}。建议：{}",
+                    finding.title, finding.detail, finding.suggestion
+                ),
+            );
+        }
+        super::global::emit("server-crashed", diagnosis.clone());
+        self.crashes
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), diagnosis);
+    }
+
+    pub fn get_crash_diagnosis(&self, id: &str) -> Option<CrashDiagnosis> {
+        self.crashes.lock().unwrap().get(id).cloned()
+    }
+
+    /// 手动分析最近一次运行，不改变服务器状态
+    pub fn analyze_crash(&self, id: &str) -> Result<CrashDiagnosis, String> {
+        let server = self
+            .get_server_list()
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| "未找到服务器".to_string())?;
+        Ok(crash_analyzer::analyze(id, &server.path, server.last_started_at, None))
+    }
+
+    /// 对已停止的服务器执行启动前检查
+    pub fn preflight(&self, id: &str) -> Result<PreflightReport, String> {
+        let server = self
+            .get_server_list()
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| "未找到服务器".to_string())?;
+        if server.attach.is_some() {
+            return Err("外部服务器不支持启动前检查".to_string());
+        }
+        if self.get_server_status(id).status.is_active() {
+            return Err("服务器已在运行中".to_string());
+        }
+        Ok(self.run_preflight(&server, &self.get_app_settings()))
+    }
+
+    fn run_preflight(
+        &self,
+        server: &ServerInstance,
+        settings: &crate::models::settings::AppSettings,
+    ) -> PreflightReport {
+        let servers = self.get_server_list();
+        let running: Vec<String> = servers
+            .iter()
+            .filter(|s| s.id != server.id)
+            .filter(|s| self.get_server_status(&s.id).status.is_active())
+            .map(|s| s.id.clone())
+            .collect();
+        preflight::run(&preflight::PreflightContext {
+            server,
+            servers: &servers,
+            running: &running,
+            settings,
+        })
+    }
+
+    /// 修改内存、Java、JVM 参数与启动方式
+    pub fn update_server_settings(
+        &self,
+        id: &str,
+        req: ServerSettingsRequest,
+    ) -> Result<ServerUpdateResult, String> {
+        self.update_server(id, req.into())
+    }
+
+    /// 修改端口并同步到 server.properties
+    pub fn update_server_port(&self, id: &str, port: u16) -> Result<ServerUpdateResult, String> {
+        self.update_server(id, ServerPatch { port: Some(port), ..Default::default() })
+    }
+
+    /// 部分更新服务器。服务器运行中时名称立即生效，其余修改校验后暂存，下次启动前应用
+    pub fn update_server(
+        &self,
+        id: &str,
+        patch: ServerPatch,
+    ) -> Result<ServerUpdateResult, String> {
+        let running = self.get_server_status(id).status.is_active();
+        let patch = resolve_java_path(patch)?;
+        let (previous_port, updated) = {
+            let mut servers = self.servers.lock().unwrap();
+            let index = servers
+                .iter()
+                .position(|s| s.id == id)
+                .ok_or_else(|| "未找到服务器".to_string())?;
+            let updated = stage_patch(&servers[index], patch, running, &servers)?;
+            let previous_port = servers[index].port;
+            servers[index] = updated.clone();
+            (previous_port, updated)
+        };
+        self.finish_update(previous_port, &updated);
+        Ok(ServerUpdateResult {
+            deferred: updated.pending_changes.is_some(),
+            server: updated,
+        })
+    }
+
+    /// 启动前应用暂存的修改
+    fn apply_pending_changes(&self, id: &str) -> Result<Option<ServerInstance>, String> {
+        let (previous_port, updated) = {
+            let mut servers = self.servers.lock().unwrap();
+            let Some(index) = servers.iter().position(|s| s.id == id) else {
+                return Ok(None);
+            };
+            let Some(updated) = apply_pending(&servers[index], &servers)
+                .map_err(|e| format!("暂存的服务器设置无效: {}", e))?
+            else {
+                return Ok(None);
+            };
+            let previous_port = servers[index].port;
+            servers[index] = updated.clone();
+            (previous_port, updated)
+        };
+        self.finish_update(previous_port, &updated);
+        self.append_log(id, "[Sea Lantern] 已应用运行期间修改的服务器设置");
+        Ok(Some(updated))
+    }
+
+    /// 保存已更新的服务器，端口变化时同步到 server.properties，并通知前端
+    fn finish_update(&self, previous_port: u16, updated: &ServerInstance) {
+        self.save();
+        if updated.port != previous_port {
+            self.write_port(updated);
+        }
+        super::global::emit("server-changed", updated.clone());
+    }
+
+    /// server.properties 中的 server-port 与记录不一致时更新记录，返回新的端口
+    pub fn sync_port_from_properties(&self, id: &str) -> Option<u16> {
+        let path = self.get_server_path(id).ok()?;
+        let port = port_manager::read_server_port(&path)?;
+        {
+            let mut servers = self.servers.lock().unwrap();
+            let server = servers.iter_mut().find(|s| s.id == id)?;
+            if server.port == port {
+                return None;
+            }
+            server.port = port;
+        }
+        self.save();
+        Some(port)
+    }
+
+    /// 新建服务器后把端口写入 server.properties
+    fn write_port(&self, server: &ServerInstance) {
+        if let Err(e) = port_manager::write_server_port(&server.id, &server.path, server.port) {
+            println!("写入 server-port 失败: {}", e);
+        }
+    }
+
+    fn attached(&self, id: &str) -> Option<(ServerInstance, AttachConfig)> {
+        let servers = self.servers.lock().unwrap();
+        let server = servers.iter().find(|s| s.id == id)?;
+        let attach = server.attach.clone()?;
+        Some((server.clone(), attach))
+    }
+
+    fn get_server_path(&self, id: &str) -> Result<String, String> {
+        self.servers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.path.clone())
+            .ok_or_else(|| "未找到服务器".to_string())
+    }
+
+    pub fn update_server_name(&self, id: &str, name: &str) -> Result<(), String> {
+        self.update_server(
+            id,
+            ServerPatch {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+        )
+        .map(|_| ())
+    }
 }
 
 fn get_data_dir() -> String {
@@ -935,6 +1659,219 @@ fn get_data_dir() -> String {
     ".".to_string()
 }
 
+/// 校验 Java 路径较慢，在持有服务器列表的锁之前完成，并换成检测到的可执行文件路径
+fn resolve_java_path(mut patch: ServerPatch) -> Result<ServerPatch, String> {
+    if let Some(java_path) = &patch.java_path {
+        patch.java_path = Some(super::java_detector::validate_java(java_path.trim())?.path);
+    }
+    Ok(patch)
+}
+
+/// 合并暂存的修改并返回更新后的服务器。运行中时只有名称立即生效，其余修改记入 pending_changes
+fn stage_patch(
+    current: &ServerInstance,
+    patch: ServerPatch,
+    running: bool,
+    servers: &[ServerInstance],
+) -> Result<ServerInstance, String> {
+    let pending = current.pending_changes.clone().unwrap_or_default();
+    let (immediate, deferred) = if running {
+        let name = ServerPatch {
+            name: patch.name.clone(),
+            ..Default::default()
+        };
+        (name, pending.merge(ServerPatch { name: None, ..patch }))
+    } else {
+        (pending.merge(patch), ServerPatch::default())
+    };
+    // 暂存的修改也在此时校验，避免到启动时才发现错误
+    let validated = apply_patch(current, &immediate.clone().merge(deferred.clone()), servers)?;
+    let mut updated = if running {
+        apply_patch(current, &immediate, servers)?
+    } else {
+        validated
+    };
+    updated.pending_changes = (!deferred.is_empty()).then_some(deferred);
+    Ok(updated)
+}
+
+/// 应用暂存的修改，没有暂存修改时返回 None
+fn apply_pending(
+    current: &ServerInstance,
+    servers: &[ServerInstance],
+) -> Result<Option<ServerInstance>, String> {
+    let Some(pending) = &current.pending_changes else {
+        return Ok(None);
+    };
+    let mut updated = apply_patch(current, pending, servers)?;
+    updated.pending_changes = None;
+    Ok(Some(updated))
+}
+
+/// 校验修改并返回修改后的服务器，不保存。java_path 须已由 resolve_java_path 校验
+fn apply_patch(
+    current: &ServerInstance,
+    patch: &ServerPatch,
+    servers: &[ServerInstance],
+) -> Result<ServerInstance, String> {
+    let mut server = current.clone();
+    let required = |value: &str, field: &str| {
+        let value = value.trim();
+        if value.is_empty() {
+            Err(format!("{}不能为空", field))
+        } else {
+            Ok(value.to_string())
+        }
+    };
+
+    if let Some(name) = &patch.name {
+        server.name = required(name, "服务器名称")?;
+    }
+    if let Some(core_type) = &patch.core_type {
+        server.core_type = required(core_type, "核心类型")?;
+    }
+    if let Some(core_version) = &patch.core_version {
+        server.core_version = core_version.trim().to_string();
+    }
+    if let Some(mc_version) = &patch.mc_version {
+        server.mc_version = required(mc_version, "游戏版本")?;
+    }
+    if let Some(port) = patch.port {
+        if port != current.port {
+            port_manager::ensure_unclaimed(port, Some(&current.id), servers)?;
+        }
+        server.port = port;
+    }
+
+    let launch_changed = patch.max_memory.is_some()
+        || patch.min_memory.is_some()
+        || patch.java_path.is_some()
+        || patch.jvm_args.is_some()
+        || patch.jvm_preset.is_some()
+        || patch.startup_mode.is_some()
+        || patch.jar_path.is_some();
+    if !launch_changed {
+        return Ok(server);
+    }
+    if server.attach.is_some() {
+        return Err("外部服务器的启动设置由其启动命令决定".to_string());
+    }
+
+    server.max_memory = patch.max_memory.unwrap_or(server.max_memory);
+    server.min_memory = patch.min_memory.unwrap_or(server.min_memory);
+    if server.max_memory == 0 {
+        return Err("最大内存不能为 0".to_string());
+    }
+    if server.min_memory > server.max_memory {
+        return Err(format!(
+            "最小内存 {} MB 不能大于最大内存 {} MB",
+            server.min_memory, server.max_memory
+        ));
+    }
+
+    if let Some(java_path) = &patch.java_path {
+        server.java_path = java_path.clone();
+    }
+
+    if let Some(preset) = &patch.jvm_preset {
+        let preset = preset.trim();
+        if !preset.is_empty() && !jvm_presets::exists(preset) {
+            return Err(format!("未知的 JVM 预设: {}", preset));
+        }
+        server.jvm_preset = (!preset.is_empty()).then(|| preset.to_string());
+    }
+
+    if let Some(jvm_args) = &patch.jvm_args {
+        let jvm_args: Vec<String> = jvm_args
+            .iter()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if let Some(arg) = jvm_args
+            .iter()
+            .find(|a| a.starts_with("-Xmx") || a.starts_with("-Xms"))
+        {
+            return Err(format!("{} 由内存设置决定，请修改最大/最小内存", arg));
+        }
+        server.jvm_args = jvm_args;
+    }
+
+    if patch.startup_mode.is_some() || patch.jar_path.is_some() {
+        if let Some(mode) = &patch.startup_mode {
+            server.startup_mode = normalize_startup_mode(mode).to_string();
+        }
+        if let Some(jar_path) = &patch.jar_path {
+            server.jar_path = required(jar_path, "启动文件")?;
+        }
+        if server.startup_mode == "bat" && !cfg!(target_os = "windows") {
+            return Err("BAT 启动方式仅支持 Windows".to_string());
+        }
+        let startup_file = std::path::Path::new(&server.jar_path);
+        if !startup_file.is_file() {
+            return Err(format!("启动文件不存在: {}", server.jar_path));
+        }
+        let extension = startup_file
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let matches_mode = match server.startup_mode.as_str() {
+            "bat" => extension == "bat" || extension == "cmd",
+            "sh" => extension == "sh",
+            _ => extension == "jar",
+        };
+        if !matches_mode {
+            return Err(format!(
+                "启动文件 {} 与启动方式 {} 不匹配",
+                server.jar_path, server.startup_mode
+            ));
+        }
+    }
+    Ok(server)
+}
+
+/// eula.txt 中已设置 eula=true
+fn eula_accepted(server_path: &str) -> bool {
+    let path = std::path::Path::new(server_path).join("eula.txt");
+    super::config_parser::read_properties(&path.to_string_lossy())
+        .ok()
+        .and_then(|props| {
+            props
+                .get("eula")
+                .map(|v| v.trim().eq_ignore_ascii_case("true"))
+        })
+        .unwrap_or(false)
+}
+
+/// 只修改 eula 一项，保留已有 eula.txt 的其他内容
+fn write_eula(server_path: &str) -> Result<(), String> {
+    let path = std::path::Path::new(server_path).join("eula.txt");
+    if !path.exists() {
+        std::fs::write(
+            &path,
+            "#By changing the setting below to TRUE you are indicating your agreement to our EULA (https://aka.ms/MinecraftEULA).\n",
+        )
+        .map_err(|e| format!("写入 eula.txt 失败: {}", e))?;
+    }
+    let values = HashMap::from([("eula".to_string(), "true".to_string())]);
+    super::config_parser::write_properties(&path.to_string_lossy(), &values)
+        .map_err(|e| format!("写入 eula.txt 失败: {}", e))
+}
+
+/// 已记录过同意时把 eula.txt 中的 eula 设为 true，返回是否写入
+fn apply_stored_eula(server: &ServerInstance) -> Result<bool, String> {
+    if server.eula.is_none() || eula_accepted(&server.path) {
+        return Ok(false);
+    }
+    write_eula(&server.path)?;
+    Ok(true)
+}
+
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
 fn normalize_startup_mode(mode: &str) -> &str {
     match mode.to_ascii_lowercase().as_str() {
         "bat" => "bat",
@@ -1125,3 +2062,169 @@ fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> std::io::
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn write_eula_keeps_existing_lines() {
+        let dir = std::env::temp_dir().join(format!("sl-eula-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("eula.txt");
+        fs::write(
+            &path,
+            "#By changing the setting below\n#Mon Jan 01 00:00:00 UTC 2024\neula=false\nextra=1\n",
+        )
+        .unwrap();
+
+        write_eula(&dir.to_string_lossy()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "#By changing the setting below\n#Mon Jan 01 00:00:00 UTC 2024\neula=true\nextra=1\n"
+        );
+        assert!(eula_accepted(&dir.to_string_lossy()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stored_consent_is_applied_only_when_recorded() {
+        let dir = std::env::temp_dir().join(format!("sl-eula-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("eula.txt"), "#comment\neula=false\n").unwrap();
+
+        assert!(!apply_stored_eula(&ServerInstance::for_test("eula", &dir)).unwrap());
+        assert!(!eula_accepted(&dir.to_string_lossy()));
+
+        let acceptance = EulaAcceptance {
+            accepted_at: 1,
+            accepted_by: "tester".into(),
+            automatic: false,
+        };
+        let accepted = ServerInstance {
+            eula: Some(acceptance),
+            ..ServerInstance::for_test("eula", &dir)
+        };
+        assert!(apply_stored_eula(&accepted).unwrap());
+        assert_eq!(fs::read_to_string(dir.join("eula.txt")).unwrap(), "#comment\neula=true\n");
+        assert!(!apply_stored_eula(&accepted).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn patch_rejects_invalid_fields() {
+        let dir = std::env::temp_dir().join(format!("sl-patch-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let jar = dir.join("server.jar");
+        fs::write(&jar, b"").unwrap();
+        let current = ServerInstance::for_test("a", &dir);
+        let other = ServerInstance {
+            port: 25566,
+            ..ServerInstance::for_test("b", &dir.join("b"))
+        };
+        let servers = vec![current.clone(), other];
+
+        let rejected = [
+            ServerPatch {
+                name: Some("  ".into()),
+                ..Default::default()
+            },
+            ServerPatch {
+                min_memory: Some(4096),
+                ..Default::default()
+            },
+            ServerPatch {
+                jvm_args: Some(vec!["-Xmx4G".into()]),
+                ..Default::default()
+            },
+            ServerPatch {
+                jvm_preset: Some("unknown".into()),
+                ..Default::default()
+            },
+            ServerPatch { port: Some(25566), ..Default::default() },
+            ServerPatch {
+                jar_path: Some(dir.join("missing.jar").to_string_lossy().to_string()),
+                ..Default::default()
+            },
+            ServerPatch {
+                startup_mode: Some("sh".into()),
+                jar_path: Some(jar.to_string_lossy().to_string()),
+                ..Default::default()
+            },
+        ];
+        for patch in &rejected {
+            assert!(apply_patch(&current, patch, &servers).is_err(), "{:?}", patch);
+        }
+        let attached = ServerInstance {
+            attach: Some(AttachConfig::default()),
+            ..current.clone()
+        };
+        let memory = ServerPatch {
+            max_memory: Some(4096),
+            ..Default::default()
+        };
+        assert!(apply_patch(&attached, &memory, &servers).is_err());
+
+        let patch = ServerPatch {
+            name: Some(" Survival ".into()),
+            max_memory: Some(4096),
+            jvm_args: Some(vec![" -XX:+UseG1GC ".into(), String::new()]),
+            jar_path: Some(jar.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let updated = apply_patch(&current, &patch, &servers).unwrap();
+        assert_eq!(updated.name, "Survival");
+        assert_eq!(updated.max_memory, 4096);
+        assert_eq!(updated.jvm_args, vec!["-XX:+UseG1GC".to_string()]);
+        assert_eq!(updated.port, current.port);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn running_changes_are_deferred_until_applied() {
+        let dir = std::env::temp_dir().join(format!("sl-patch-{}", uuid::Uuid::new_v4()));
+        let current = ServerInstance::for_test("a", &dir);
+        let servers = vec![current.clone()];
+
+        let patch = ServerPatch {
+            name: Some("Renamed".into()),
+            max_memory: Some(4096),
+            ..Default::default()
+        };
+        let staged = stage_patch(&current, patch, true, &servers).unwrap();
+        assert_eq!(staged.name, "Renamed");
+        assert_eq!(staged.max_memory, current.max_memory);
+        let pending = staged.pending_changes.clone().unwrap();
+        assert_eq!(pending.max_memory, Some(4096));
+        assert!(pending.name.is_none());
+
+        // 后续修改与已暂存的修改合并后整体校验
+        let min = ServerPatch {
+            min_memory: Some(3072),
+            ..Default::default()
+        };
+        let staged = stage_patch(&staged, min, true, &servers).unwrap();
+        let pending = staged.pending_changes.clone().unwrap();
+        assert_eq!((pending.max_memory, pending.min_memory), (Some(4096), Some(3072)));
+        let too_large = ServerPatch {
+            min_memory: Some(8192),
+            ..Default::default()
+        };
+        assert!(stage_patch(&staged, too_large, true, &servers).is_err());
+
+        let applied = apply_pending(&staged, &servers).unwrap().unwrap();
+        assert_eq!((applied.max_memory, applied.min_memory), (4096, 3072));
+        assert_eq!(applied.name, "Renamed");
+        assert!(applied.pending_changes.is_none());
+        assert!(apply_pending(&applied, &servers).unwrap().is_none());
+
+        // 停止时暂存的修改与新的修改一起立即生效
+        let stopped = stage_patch(&staged, ServerPatch::default(), false, &servers).unwrap();
+        assert_eq!((stopped.max_memory, stopped.min_memory), (4096, 3072));
+        assert!(stopped.pending_changes.is_none());
+    }
+}
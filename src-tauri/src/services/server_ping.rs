@@ -0,0 +1,507 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use super::config_parser;
+use crate::models::server::{PingPlayer, QueryStatus, ServerPingStatus};
+
+/// 状态查询不校验协议版本，-1 表示客户端未指定
+const STATUS_PROTOCOL: i32 = -1;
+/// 状态响应（含 favicon）的长度上限
+const MAX_PACKET_LEN: usize = 1 << 21;
+/// 1.6 旧版 Ping 中声明的协议版本
+const LEGACY_PROTOCOL: u8 = 74;
+const QUERY_MAGIC: [u8; 2] = [0xFE, 0xFD];
+const QUERY_HANDSHAKE: u8 = 0x09;
+const QUERY_STAT: u8 = 0x00;
+
+/// 先尝试 1.7+ 的 Server List Ping，失败时回退到 1.6 旧版 Ping
+pub fn ping(host: &str, port: u16, timeout: Duration) -> Result<ServerPingStatus, String> {
+    status(host, port, timeout).or_else(|e| legacy_status(host, port, timeout).map_err(|_| e))
+}
+
+/// 通过 Server List Ping 查询服务器状态（1.7+）
+pub fn status(host: &str, port: u16, timeout: Duration) -> Result<ServerPingStatus, String> {
+    let mut stream = connect(host, port, timeout)?;
+    let started = Instant::now();
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, STATUS_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    send_packet(&mut stream, &handshake)?;
+    send_packet(&mut stream, &[0x00])?;
+
+    let response = read_packet(&mut stream)?;
+    let mut cursor = response.as_slice();
+    if read_varint(&mut cursor)? != 0x00 {
+        return Err("无效的状态响应".to_string());
+    }
+    let json = read_string(&mut cursor)?;
+    let latency = started.elapsed().as_millis() as u64;
+    parse_status(&json, latency)
+}
+
+/// 解析状态响应中的 JSON
+pub fn parse_status(json: &str, latency_ms: u64) -> Result<ServerPingStatus, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("无法解析状态响应: {}", e))?;
+    let players = &value["players"];
+    Ok(ServerPingStatus {
+        version: value["version"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        protocol: value["version"]["protocol"].as_i64().unwrap_or(-1) as i32,
+        online: players["online"].as_u64().unwrap_or(0) as u32,
+        max: players["max"].as_u64().unwrap_or(0) as u32,
+        sample: players["sample"]
+            .as_array()
+            .map(|sample| {
+                sample
+                    .iter()
+                    .map(|p| PingPlayer {
+                        name: p["name"].as_str().unwrap_or_default().to_string(),
+                        id: p["id"].as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        motd: motd_text(&value["description"]),
+        description: value.get("description").cloned().unwrap_or_default(),
+        favicon: value["favicon"].as_str().map(str::to_string),
+        latency_ms,
+    })
+}
+
+/// 1.6 旧版 Ping（0xFE 0x01 + MC|PingHost），适用于 1.4 - 1.6 的服务器
+pub fn legacy_status(host: &str, port: u16, timeout: Duration) -> Result<ServerPingStatus, String> {
+    let mut stream = connect(host, port, timeout)?;
+    let started = Instant::now();
+
+    let mut payload = vec![LEGACY_PROTOCOL];
+    write_utf16(&mut payload, host);
+    payload.extend_from_slice(&(port as i32).to_be_bytes());
+    let mut request = vec![0xFE, 0x01, 0xFA];
+    write_utf16(&mut request, "MC|PingHost");
+    request.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    request.extend_from_slice(&payload);
+    stream
+        .write_all(&request)
+        .map_err(|e| format!("发送失败: {}", e))?;
+
+    let mut header = [0u8; 3];
+    stream
+        .read_exact(&mut header)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    if header[0] != 0xFF {
+        return Err("无效的旧版 Ping 响应".to_string());
+    }
+    let mut body = vec![0u8; u16::from_be_bytes([header[1], header[2]]) as usize * 2];
+    stream
+        .read_exact(&mut body)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    let units: Vec<u16> = body
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect();
+    parse_legacy(&String::from_utf16_lossy(&units), started.elapsed().as_millis() as u64)
+}
+
+/// 1.4+ 的响应为 "§1\0协议\0版本\0MOTD\0在线\0上限"，更早的版本为 "MOTD§在线§上限"
+pub fn parse_legacy(text: &str, latency_ms: u64) -> Result<ServerPingStatus, String> {
+    let invalid = || "无效的旧版 Ping 响应".to_string();
+    let (protocol, version, motd, online, max) = match text.strip_prefix("§1\0") {
+        Some(rest) => {
+            let parts: Vec<&str> = rest.split('\0').collect();
+            if parts.len() < 5 {
+                return Err(invalid());
+            }
+            (parts[0].parse().unwrap_or(-1), parts[1], parts[2], parts[3], parts[4])
+        }
+        None => {
+            let mut parts = text.rsplitn(3, '§');
+            let max = parts.next().ok_or_else(invalid)?;
+            let online = parts.next().ok_or_else(invalid)?;
+            let motd = parts.next().ok_or_else(invalid)?;
+            (-1, "", motd, online, max)
+        }
+    };
+    Ok(ServerPingStatus {
+        version: version.to_string(),
+        protocol,
+        online: online.trim().parse().map_err(|_| invalid())?,
+        max: max.trim().parse().map_err(|_| invalid())?,
+        sample: Vec::new(),
+        description: Value::String(motd.to_string()),
+        motd: strip_formatting(motd),
+        favicon: None,
+        latency_ms,
+    })
+}
+
+/// 通过 GameSpy4 Query（UDP）获取完整玩家列表与插件，需要服务器开启 enable-query
+pub fn query(host: &str, port: u16, timeout: Duration) -> Result<QueryStatus, String> {
+    let addr = resolve(host, port)?;
+    let bind_addr = if addr.is_ipv6() {
+        "[::]:0"
+    } else {
+        "0.0.0.0:0"
+    };
+    let socket = UdpSocket::bind(bind_addr).map_err(|e| format!("创建 UDP 套接字失败: {}", e))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| socket.connect(addr))
+        .map_err(|e| format!("连接 {}:{} 失败: {}", host, port, e))?;
+
+    // 会话 ID 每个字节只使用低 4 位
+    let session = (uuid::Uuid::new_v4().as_u128() as i32) & 0x0F0F_0F0F;
+    let mut buf = vec![0u8; 65535];
+
+    let mut request = QUERY_MAGIC.to_vec();
+    request.push(QUERY_HANDSHAKE);
+    request.extend_from_slice(&session.to_be_bytes());
+    let response = exchange(&socket, &request, &mut buf)?;
+    let challenge = check_query_header(response, QUERY_HANDSHAKE, session)?;
+    let challenge: i32 = String::from_utf8_lossy(challenge)
+        .trim_end_matches('\0')
+        .trim()
+        .parse()
+        .map_err(|_| "无效的 Query 握手响应".to_string())?;
+
+    let mut request = QUERY_MAGIC.to_vec();
+    request.push(QUERY_STAT);
+    request.extend_from_slice(&session.to_be_bytes());
+    request.extend_from_slice(&challenge.to_be_bytes());
+    // 附加 4 字节填充请求完整状态
+    request.extend_from_slice(&[0, 0, 0, 0]);
+    let response = exchange(&socket, &request, &mut buf)?;
+    parse_full_stat(check_query_header(response, QUERY_STAT, session)?)
+}
+
+/// 解析完整状态的正文：11 字节填充、以空键结尾的键值对、10 字节填充、玩家列表
+pub fn parse_full_stat(data: &[u8]) -> Result<QueryStatus, String> {
+    let data = data
+        .get(11..)
+        .ok_or_else(|| "无效的 Query 响应".to_string())?;
+    let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).to_string();
+    let mut fields = data.split(|b| *b == 0);
+    let mut values = HashMap::new();
+    while let Some(key) = fields.next().filter(|k| !k.is_empty()) {
+        values.insert(text(key), text(fields.next().unwrap_or_default()));
+    }
+    let players = fields
+        .skip_while(|f| *f != b"\x01player_")
+        .skip(2)
+        .take_while(|f| !f.is_empty())
+        .map(text)
+        .collect();
+
+    let value = |key: &str| values.get(key).cloned().unwrap_or_default();
+    let plugins = value("plugins");
+    let (server_mod, plugins) = match plugins.split_once(": ") {
+        Some((server_mod, list)) => {
+            (server_mod.to_string(), list.split("; ").map(str::to_string).collect())
+        }
+        None => (plugins, Vec::new()),
+    };
+    Ok(QueryStatus {
+        motd: strip_formatting(&value("hostname")),
+        game_type: value("gametype"),
+        version: value("version"),
+        server_mod,
+        plugins,
+        map: value("map"),
+        online: value("numplayers").parse().unwrap_or(0),
+        max: value("maxplayers").parse().unwrap_or(0),
+        host_ip: value("hostip"),
+        host_port: value("hostport").parse().unwrap_or(0),
+        players,
+    })
+}
+
+/// 读取服务器目录中的 Query 端口；未开启 enable-query 时返回错误
+pub fn query_port(server_path: &str, server_port: u16) -> Result<u16, String> {
+    let props = config_parser::read_server_dir_properties(server_path)?;
+    if props.get("enable-query").map(|v| v.trim()) != Some("true") {
+        return Err("服务器未开启 Query（enable-query=false）".to_string());
+    }
+    Ok(props
+        .get("query.port")
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(server_port))
+}
+
+/// 提取 MOTD 的纯文本：支持字符串与带 extra 的聊天组件，并去掉 § 格式代码
+pub fn motd_text(description: &Value) -> String {
+    fn collect(value: &Value, out: &mut String) {
+        match value {
+            Value::String(text) => out.push_str(text),
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(map) => {
+                if let Some(Value::String(text)) = map.get("text") {
+                    out.push_str(text);
+                }
+                if let Some(extra) = map.get("extra") {
+                    collect(extra, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = String::new();
+    collect(description, &mut out);
+    strip_formatting(&out)
+}
+
+fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn exchange<'a>(socket: &UdpSocket, request: &[u8], buf: &'a mut [u8]) -> Result<&'a [u8], String> {
+    socket
+        .send(request)
+        .map_err(|e| format!("发送失败: {}", e))?;
+    let len = socket
+        .recv(buf)
+        .map_err(|e| format!("Query 无响应: {}", e))?;
+    Ok(&buf[..len])
+}
+
+fn check_query_header(response: &[u8], kind: u8, session: i32) -> Result<&[u8], String> {
+    if response.len() < 5 || response[0] != kind || response[1..5] != session.to_be_bytes() {
+        return Err("无效的 Query 响应".to_string());
+    }
+    Ok(&response[5..])
+}
+
+fn resolve(host: &str, port: u16) -> Result<SocketAddr, String> {
+    (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("无法解析地址 {}: {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("无法解析地址 {}", host))
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, String> {
+    let addr = resolve(host, port)?;
+    let stream = TcpStream::connect_timeout(&addr, timeout)
+        .map_err(|e| format!("连接 {}:{} 失败: {}", host, port, e))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| format!("连接 {}:{} 失败: {}", host, port, e))?;
+    Ok(stream)
+}
+
+fn send_packet(stream: &mut TcpStream, payload: &[u8]) -> Result<(), String> {
+    let mut packet = Vec::with_capacity(payload.len() + 5);
+    write_varint(&mut packet, payload.len() as i32);
+    packet.extend_from_slice(payload);
+    stream
+        .write_all(&packet)
+        .map_err(|e| format!("发送失败: {}", e))
+}
+
+fn read_packet(stream: &mut impl Read) -> Result<Vec<u8>, String> {
+    let length = read_varint(stream)?;
+    if length <= 0 || length as usize > MAX_PACKET_LEN {
+        return Err(format!("无效的数据包长度 {}", length));
+    }
+    let mut payload = vec![0u8; length as usize];
+    stream
+        .read_exact(&mut payload)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    Ok(payload)
+}
+
+pub fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+pub fn read_varint(reader: &mut impl Read) -> Result<i32, String> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let mut byte = [0u8; 1];
+        reader
+            .read_exact(&mut byte)
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+        value |= ((byte[0] & 0x7f) as u32) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err("VarInt 过长".to_string())
+}
+
+/// 旧版协议的字符串：u16 字符数 + UTF-16BE
+fn write_utf16(buf: &mut Vec<u8>, value: &str) {
+    let units: Vec<u16> = value.encode_utf16().collect();
+    buf.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        buf.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String, String> {
+    let length = read_varint(reader)?;
+    if length < 0 || length as usize > MAX_PACKET_LEN {
+        return Err(format!("无效的字符串长度 {}", length));
+    }
+    let mut bytes = vec![0u8; length as usize];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "状态响应不是有效的 UTF-8".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn pings_fake_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let handshake = read_packet(&mut stream).unwrap();
+            assert_eq!(*handshake.last().unwrap(), 1);
+            assert_eq!(read_packet(&mut stream).unwrap(), vec![0x00]);
+
+            let mut payload = Vec::new();
+            write_varint(&mut payload, 0x00);
+            write_string(
+                &mut payload,
+                r#"{"version":{"name":"1.21.1","protocol":767},
+                    "players":{"max":20,"online":1,"sample":[{"name":"Steve","id":"8667ba71-b85a-4004-af54-457a9734eed7"}]},
+                    "description":{"text":"§aA ","extra":[{"text":"Minecraft"}," Server"]}}"#,
+            );
+            send_packet(&mut stream, &payload).unwrap();
+        });
+
+        let status = status("127.0.0.1", port, Duration::from_secs(2)).unwrap();
+        assert_eq!(status.version, "1.21.1");
+        assert_eq!(status.protocol, 767);
+        assert_eq!((status.online, status.max), (1, 20));
+        assert_eq!(status.sample[0].name, "Steve");
+        assert_eq!(status.motd, "A Minecraft Server");
+        assert!(status.favicon.is_none());
+    }
+
+    #[test]
+    fn falls_back_to_legacy_ping() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            // 第一个连接是现代握手，旧版服务器直接断开
+            drop(listener.accept().unwrap());
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut prefix = [0u8; 3];
+            stream.read_exact(&mut prefix).unwrap();
+            assert_eq!(prefix, [0xFE, 0x01, 0xFA]);
+
+            let units: Vec<u16> = ["§1", "74", "1.6.4", "§eOld Server", "3", "20"]
+                .join("\0")
+                .encode_utf16()
+                .collect();
+            let mut reply = vec![0xFF];
+            reply.extend_from_slice(&(units.len() as u16).to_be_bytes());
+            units
+                .iter()
+                .for_each(|u| reply.extend_from_slice(&u.to_be_bytes()));
+            stream.write_all(&reply).unwrap();
+        });
+
+        let status = ping("127.0.0.1", port, Duration::from_secs(2)).unwrap();
+        assert_eq!((status.protocol, status.version.as_str()), (74, "1.6.4"));
+        assert_eq!(status.motd, "Old Server");
+        assert_eq!((status.online, status.max), (3, 20));
+        assert_eq!(parse_legacy("Beta Server§1§10", 0).unwrap().max, 10);
+    }
+
+    #[test]
+    fn queries_fake_server() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = socket.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 1024];
+            let (_, from) = socket.recv_from(&mut buf).unwrap();
+            assert_eq!(&buf[..3], &[0xFE, 0xFD, QUERY_HANDSHAKE]);
+            let session = buf[3..7].to_vec();
+            let mut reply = vec![QUERY_HANDSHAKE];
+            reply.extend_from_slice(&session);
+            reply.extend_from_slice(b"9513307\0");
+            socket.send_to(&reply, from).unwrap();
+
+            let (len, from) = socket.recv_from(&mut buf).unwrap();
+            assert_eq!(len, 15);
+            assert_eq!(&buf[7..11], &9513307i32.to_be_bytes());
+            let mut reply = vec![QUERY_STAT];
+            reply.extend_from_slice(&session);
+            reply.extend_from_slice(b"splitnum\0\x80\0");
+            for field in [
+                "hostname",
+                "A Server",
+                "gametype",
+                "SMP",
+                "game_id",
+                "MINECRAFT",
+                "version",
+                "1.21.1",
+                "plugins",
+                "Paper on Bukkit 1.21.1: WorldEdit 7.3; LuckPerms 5.4",
+                "map",
+                "world",
+                "numplayers",
+                "2",
+                "maxplayers",
+                "20",
+                "hostport",
+                "25565",
+                "hostip",
+                "127.0.0.1",
+                "",
+            ] {
+                reply.extend_from_slice(field.as_bytes());
+                reply.push(0);
+            }
+            reply.extend_from_slice(b"\x01player_\0\0Steve\0Alex\0\0");
+            socket.send_to(&reply, from).unwrap();
+        });
+
+        let status = query("127.0.0.1", port, Duration::from_secs(2)).unwrap();
+        assert_eq!(status.motd, "A Server");
+        assert_eq!(status.server_mod, "Paper on Bukkit 1.21.1");
+        assert_eq!(status.plugins, vec!["WorldEdit 7.3", "LuckPerms 5.4"]);
+        assert_eq!((status.online, status.max, status.host_port), (2, 20, 25565));
+        assert_eq!(status.players, vec!["Steve", "Alex"]);
+    }
+}
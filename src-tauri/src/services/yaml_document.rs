@@ -0,0 +1,611 @@
+use serde_json::{Map, Value};
+
+use crate::models::config::ConfigNode;
+
+/// 基于行的 YAML 编辑器，只改写被修改的行，注释与格式保持不变。
+/// 支持 Bukkit/Spigot/Paper/Purpur 配置使用的块状映射、块/行内列表与标量
+#[derive(Debug, Clone)]
+pub struct YamlDocument {
+    lines: Vec<String>,
+    line_ending: &'static str,
+    trailing_newline: bool,
+}
+
+#[derive(Debug, Clone)]
+struct YamlEntry {
+    path: Vec<String>,
+    line: usize,
+    indent: usize,
+    kind: EntryKind,
+    /// 该键值块最后一个内容行之后的行号
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    /// `key: value`，value 位于该行 [start, end)
+    Inline { start: usize, end: usize },
+    /// `key: |` 或 `key: >`
+    BlockScalar { folded: bool },
+    /// `key:` 后跟子映射、块列表或为空
+    Block,
+}
+
+impl YamlDocument {
+    pub fn parse(content: &str) -> Self {
+        let line_ending = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+        let lines = content.lines().map(|l| l.to_string()).collect();
+        Self { lines, line_ending, trailing_newline }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut output = self.lines.join(self.line_ending);
+        if self.trailing_newline && !self.lines.is_empty() {
+            output.push_str(self.line_ending);
+        }
+        output
+    }
+
+    pub fn get(&self, path: &[String]) -> Option<Value> {
+        let entries = self.entries();
+        if path.is_empty() {
+            return Some(self.object_value(&entries, &[]));
+        }
+        let entry = entries.iter().find(|e| e.path == path)?;
+        Some(self.entry_value(&entries, entry))
+    }
+
+    /// 所有叶子节点（标量与列表），带有键上方的注释
+    pub fn list(&self) -> Vec<ConfigNode> {
+        let entries = self.entries();
+        entries
+            .iter()
+            .filter(|e| e.kind != EntryKind::Block || !has_children(&entries, e))
+            .map(|e| ConfigNode {
+                path: e.path.clone(),
+                value: self.entry_value(&entries, e),
+                comment: self.comment_above(e.line),
+            })
+            .collect()
+    }
+
+    pub fn set(&mut self, path: &[String], value: &Value) -> Result<(), String> {
+        if path.is_empty() {
+            return Err("配置路径不能为空".to_string());
+        }
+        if value.is_object() {
+            return Err("不支持直接替换整个节点，请逐项修改".to_string());
+        }
+        let entries = self.entries();
+        if let Some(entry) = entries.iter().find(|e| e.path == path) {
+            if entry.kind == EntryKind::Block && has_children(&entries, entry) {
+                return Err(format!("{} 是一个节点，不能直接赋值", path.join(".")));
+            }
+            return self.replace(entry, value);
+        }
+
+        // 找到最深的已存在祖先，在其末尾插入缺失的键
+        let mut depth = path.len() - 1;
+        let parent = loop {
+            if depth == 0 {
+                break None;
+            }
+            if let Some(entry) = entries.iter().find(|e| e.path == path[..depth]) {
+                break Some(entry);
+            }
+            depth -= 1;
+        };
+        let (insert_at, indent) = match parent {
+            Some(parent) => {
+                if parent.kind != EntryKind::Block || self.has_sequence(parent) {
+                    return Err(format!("{} 不是映射，无法添加子项", path[..depth].join(".")));
+                }
+                let child_indent = entries
+                    .iter()
+                    .find(|e| e.path.len() == depth + 1 && e.path.starts_with(&parent.path))
+                    .map(|e| e.indent)
+                    .unwrap_or(parent.indent + 2);
+                (parent.end, child_indent)
+            }
+            None => (self.content_end(), 0),
+        };
+
+        let mut new_lines = Vec::new();
+        for (i, key) in path[depth..].iter().enumerate() {
+            let pad = " ".repeat(indent + i * 2);
+            if depth + i + 1 < path.len() {
+                new_lines.push(format!("{}{}:", pad, format_key(key)));
+            } else {
+                new_lines.extend(format_entry(&pad, key, value));
+            }
+        }
+        self.lines.splice(insert_at..insert_at, new_lines);
+        Ok(())
+    }
+
+    fn replace(&mut self, entry: &YamlEntry, value: &Value) -> Result<(), String> {
+        let line = &self.lines[entry.line];
+        match entry.kind {
+            EntryKind::Inline { start, end } => {
+                let formatted = match value {
+                    Value::Array(items) => format_flow(items),
+                    other => format_scalar(other, false),
+                };
+                self.lines[entry.line] = format!("{}{}{}", &line[..start], formatted, &line[end..]);
+            }
+            EntryKind::Block if value.is_array() && self.has_sequence(entry) => {
+                let item_indent = self.lines[entry.line + 1..entry.end]
+                    .iter()
+                    .find(|l| l.trim_start().starts_with('-'))
+                    .map(|l| l.len() - l.trim_start().len())
+                    .unwrap_or(entry.indent + 2);
+                let items = sequence_lines(&" ".repeat(item_indent), value);
+                self.lines.splice(entry.line + 1..entry.end, items);
+            }
+            EntryKind::Block | EntryKind::BlockScalar { .. } => {
+                let key_end = key_end(line).unwrap_or(line.len());
+                let pad = &line[..entry.indent];
+                let key = line[entry.indent..key_end].to_string();
+                let replacement = match value {
+                    Value::Array(items) if !items.is_empty() => {
+                        let mut lines = vec![format!("{}{}:", pad, key)];
+                        lines.extend(sequence_lines(&format!("{}  ", pad), value));
+                        lines
+                    }
+                    Value::Array(_) => vec![format!("{}{}: []", pad, key)],
+                    other => vec![format!("{}{}: {}", pad, key, format_scalar(other, false))],
+                };
+                let end = entry.end.max(entry.line + 1);
+                self.lines.splice(entry.line..end, replacement);
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Vec<YamlEntry> {
+        let mut entries: Vec<YamlEntry> = Vec::new();
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        let mut skip_deeper_than: Option<usize> = None;
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let trimmed = line.trim_start_matches(' ');
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - trimmed.len();
+            if let Some(limit) = skip_deeper_than {
+                if indent > limit {
+                    for &(_, owner) in &stack {
+                        entries[owner].end = i + 1;
+                    }
+                    continue;
+                }
+                skip_deeper_than = None;
+            }
+            if trimmed == "---" || trimmed == "..." {
+                continue;
+            }
+            if trimmed == "-" || trimmed.starts_with("- ") {
+                // 列表项整体视为父键的值，项内的映射不再单独索引
+                while stack.last().is_some_and(|&(ind, _)| ind > indent) {
+                    stack.pop();
+                }
+                for &(_, owner) in &stack {
+                    entries[owner].end = i + 1;
+                }
+                skip_deeper_than = Some(indent);
+                continue;
+            }
+            let Some(colon) = key_end(line) else {
+                continue;
+            };
+
+            while stack.last().is_some_and(|&(ind, _)| ind >= indent) {
+                stack.pop();
+            }
+            let mut path = stack
+                .last()
+                .map(|&(_, parent)| entries[parent].path.clone())
+                .unwrap_or_default();
+            path.push(unquote_key(&line[indent..colon]));
+
+            let (value_start, value_end) = value_span(line, colon + 1);
+            let value_text = &line[value_start..value_end];
+            let kind = if value_text.is_empty() {
+                EntryKind::Block
+            } else if value_text.starts_with('|') || value_text.starts_with('>') {
+                skip_deeper_than = Some(indent);
+                EntryKind::BlockScalar { folded: value_text.starts_with('>') }
+            } else {
+                EntryKind::Inline { start: value_start, end: value_end }
+            };
+
+            for &(_, ancestor) in &stack {
+                entries[ancestor].end = i + 1;
+            }
+            stack.push((indent, entries.len()));
+            entries.push(YamlEntry { path, line: i, indent, kind, end: i + 1 });
+        }
+        entries
+    }
+
+    fn entry_value(&self, entries: &[YamlEntry], entry: &YamlEntry) -> Value {
+        let line = &self.lines[entry.line];
+        match entry.kind {
+            EntryKind::Inline { start, end } => parse_value(&line[start..end]),
+            EntryKind::BlockScalar { folded } => {
+                let body: Vec<&str> = self.lines[entry.line + 1..entry.end]
+                    .iter()
+                    .map(|l| l.trim())
+                    .collect();
+                let separator = if folded { " " } else { "\n" };
+                Value::String(body.join(separator))
+            }
+            EntryKind::Block if has_children(entries, entry) => {
+                self.object_value(entries, &entry.path)
+            }
+            EntryKind::Block if self.has_sequence(entry) => {
+                let items = self.lines[entry.line + 1..entry.end]
+                    .iter()
+                    .map(|l| l.trim_start_matches(' '))
+                    .filter(|l| *l == "-" || l.starts_with("- "))
+                    .map(|l| parse_value(strip_comment(l[1..].trim())))
+                    .collect();
+                Value::Array(items)
+            }
+            EntryKind::Block => Value::Null,
+        }
+    }
+
+    fn object_value(&self, entries: &[YamlEntry], path: &[String]) -> Value {
+        let mut map = Map::new();
+        for child in entries
+            .iter()
+            .filter(|e| e.path.len() == path.len() + 1 && e.path.starts_with(path))
+        {
+            let key = child.path.last().cloned().unwrap_or_default();
+            map.insert(key, self.entry_value(entries, child));
+        }
+        Value::Object(map)
+    }
+
+    fn has_sequence(&self, entry: &YamlEntry) -> bool {
+        self.lines[entry.line + 1..entry.end].iter().any(|l| {
+            let t = l.trim_start_matches(' ');
+            t == "-" || t.starts_with("- ")
+        })
+    }
+
+    /// 键上方紧邻的 `#` 注释
+    fn comment_above(&self, line: usize) -> Option<String> {
+        let mut comments = Vec::new();
+        for l in self.lines[..line].iter().rev() {
+            let t = l.trim();
+            match t.strip_prefix('#') {
+                Some(text) => comments.push(text.trim().to_string()),
+                None => break,
+            }
+        }
+        if comments.is_empty() {
+            let line = &self.lines[line];
+            let colon = key_end(line)?;
+            let (_, end) = value_span(line, colon + 1);
+            let trailing = line[end..].trim().strip_prefix('#')?.trim();
+            return (!trailing.is_empty()).then(|| trailing.to_string());
+        }
+        comments.reverse();
+        Some(comments.join("\n"))
+    }
+
+    fn content_end(&self) -> usize {
+        self.lines
+            .iter()
+            .rposition(|l| !l.trim().is_empty())
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+}
+
+fn has_children(entries: &[YamlEntry], entry: &YamlEntry) -> bool {
+    entries
+        .iter()
+        .any(|e| e.path.len() == entry.path.len() + 1 && e.path.starts_with(&entry.path))
+}
+
+/// 映射键之后冒号的位置；冒号后必须是空白或行尾
+fn key_end(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start_matches(' ');
+    let offset = line.len() - trimmed.len();
+    let bytes = trimmed.as_bytes();
+    let mut i = 0;
+    if let Some(&quote) = bytes.first().filter(|b| **b == b'"' || **b == b'\'') {
+        i = 1;
+        while i < bytes.len() && bytes[i] != quote {
+            i += if bytes[i] == b'\\' && quote == b'"' {
+                2
+            } else {
+                1
+            };
+        }
+        i += 1;
+        return (bytes.get(i) == Some(&b':')).then_some(offset + i);
+    }
+    if matches!(bytes.first(), Some(b'[' | b'{' | b'#')) {
+        return None;
+    }
+    while i < bytes.len() {
+        if bytes[i] == b':' && matches!(bytes.get(i + 1), None | Some(b' ' | b'\t')) {
+            return Some(offset + i);
+        }
+        if bytes[i] == b'#' && i > 0 && bytes[i - 1] == b' ' {
+            return None;
+        }
+        i += 1;
+    }
+    None
+}
+
+fn unquote_key(key: &str) -> String {
+    let key = key.trim();
+    match parse_value(key) {
+        Value::String(s) if key.starts_with(['"', '\'']) => s,
+        _ => key.to_string(),
+    }
+}
+
+/// 冒号之后的值范围，不含行尾注释与空白
+fn value_span(line: &str, from: usize) -> (usize, usize) {
+    let rest = &line[from..];
+    let start = from + (rest.len() - rest.trim_start().len());
+    let value = strip_comment(&line[start..]);
+    (start, start + value.len())
+}
+
+/// 去掉行尾 ` #` 注释（引号内除外）
+fn strip_comment(text: &str) -> &str {
+    let bytes = text.as_bytes();
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) if b == b'\\' && q == b'"' => i += 1,
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if (b == b'"' || b == b'\'')
+                && (i == 0
+                    || bytes[i - 1] == b' '
+                    || bytes[i - 1] == b'['
+                    || bytes[i - 1] == b',') =>
+            {
+                quote = Some(b)
+            }
+            None if b == b'#' && (i == 0 || bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') => {
+                return text[..i].trim_end();
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    text.trim_end()
+}
+
+/// 解析标量或行内列表/映射，遵循 SnakeYAML 使用的 YAML 1.1 规则
+fn parse_value(text: &str) -> Value {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        return Value::Array(split_flow(inner).into_iter().map(parse_value).collect());
+    }
+    if let Some(inner) = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+        let mut map = Map::new();
+        for item in split_flow(inner) {
+            if let Some((k, v)) = item.split_once(':') {
+                map.insert(unquote_key(k), parse_value(v));
+            }
+        }
+        return Value::Object(map);
+    }
+    if let Some(inner) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        return Value::String(inner.replace("''", "'"));
+    }
+    if text.starts_with('"') && text.ends_with('"') && text.len() >= 2 {
+        return serde_json::from_str(text)
+            .unwrap_or_else(|_| Value::String(text[1..text.len() - 1].to_string()));
+    }
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" | "yes" | "Yes" | "YES" | "on" | "On" | "ON" => {
+            return Value::Bool(true)
+        }
+        "false" | "False" | "FALSE" | "no" | "No" | "NO" | "off" | "Off" | "OFF" => {
+            return Value::Bool(false)
+        }
+        _ => {}
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        return Value::from(n);
+    }
+    let looks_numeric = text
+        .trim_start_matches(['-', '+'])
+        .starts_with(|c: char| c.is_ascii_digit() || c == '.');
+    if looks_numeric {
+        if let Some(n) = text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+        {
+            return Value::Number(n);
+        }
+    }
+    Value::String(text.to_string())
+}
+
+/// 按顶层逗号拆分行内集合
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    items
+}
+
+fn format_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && !key.contains(": ")
+        && !key.ends_with(':')
+        && !key.starts_with(|c: char| "-?[]{}#&*!|>'\"%@` ".contains(c));
+    if plain {
+        key.to_string()
+    } else {
+        format_scalar(&Value::String(key.to_string()), false)
+    }
+}
+
+/// 格式化标量；会被解析成其它类型或含特殊字符的字符串加引号
+fn format_scalar(value: &Value, in_flow: bool) -> String {
+    match value {
+        Value::String(s) => {
+            if s.contains(|c: char| c.is_control()) {
+                return serde_json::to_string(s).unwrap_or_default();
+            }
+            let special = s.is_empty()
+                || s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@` ".contains(c))
+                || s.ends_with([' ', ':'])
+                || s.contains(": ")
+                || s.contains(" #")
+                || (in_flow && s.contains([',', '[', ']', '{', '}']))
+                || parse_value(s) != Value::String(s.clone());
+            if special {
+                format!("'{}'", s.replace('\'', "''"))
+            } else {
+                s.clone()
+            }
+        }
+        Value::Array(items) => format_flow(items),
+        Value::Object(_) => serde_json::to_string(value).unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+fn format_flow(items: &[Value]) -> String {
+    let items: Vec<String> = items.iter().map(|v| format_scalar(v, true)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn sequence_lines(pad: &str, value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|v| format!("{}- {}", pad, format_scalar(v, false)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn format_entry(pad: &str, key: &str, value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            let mut lines = vec![format!("{}{}:", pad, format_key(key))];
+            lines.extend(sequence_lines(&format!("{}  ", pad), value));
+            lines
+        }
+        other => vec![format!("{}{}: {}", pad, format_key(key), format_scalar(other, false))],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SAMPLE: &str = "# Paper global config\n\
+        _version: 29\n\
+        chunk-loading:\n\
+        \x20 # Max chunks sent per second\n\
+        \x20 player-max-chunk-send-rate: 75.0 # per player\n\
+        \x20 enable-frustum-priority: false\n\
+        messages:\n\
+        \x20 kick:\n\
+        \x20   flying-player: '<lang:multiplayer.disconnect.flying>'\n\
+        \x20 no-permission: \"<red>I'm sorry: no\"\n\
+        disabled-worlds:\n\
+        - world_nether # comment\n\
+        - 'the end'\n\
+        aliases: []\n";
+
+    fn path(p: &str) -> Vec<String> {
+        p.split('.').map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_paths_and_comments() {
+        let doc = YamlDocument::parse(SAMPLE);
+        assert_eq!(doc.get(&path("_version")), Some(json!(29)));
+        assert_eq!(doc.get(&path("chunk-loading.player-max-chunk-send-rate")), Some(json!(75.0)));
+        assert_eq!(
+            doc.get(&path("messages.kick.flying-player")),
+            Some(json!("<lang:multiplayer.disconnect.flying>"))
+        );
+        assert_eq!(doc.get(&path("messages.no-permission")), Some(json!("<red>I'm sorry: no")));
+        assert_eq!(doc.get(&path("disabled-worlds")), Some(json!(["world_nether", "the end"])));
+        assert_eq!(doc.get(&path("aliases")), Some(json!([])));
+
+        let nodes = doc.list();
+        assert_eq!(nodes.len(), 7);
+        let rate = nodes
+            .iter()
+            .find(|n| n.path == path("chunk-loading.player-max-chunk-send-rate"))
+            .unwrap();
+        assert_eq!(rate.comment.as_deref(), Some("Max chunks sent per second"));
+        assert_eq!(doc.to_text(), SAMPLE);
+    }
+
+    #[test]
+    fn writes_preserving_layout() {
+        let mut doc = YamlDocument::parse(SAMPLE);
+        doc.set(&path("chunk-loading.player-max-chunk-send-rate"), &json!(100.5))
+            .unwrap();
+        doc.set(&path("disabled-worlds"), &json!(["a", "yes"]))
+            .unwrap();
+        doc.set(&path("messages.kick.new-key"), &json!("on"))
+            .unwrap();
+        doc.set(&path("scheduling.async.threads"), &json!(4))
+            .unwrap();
+        assert!(doc.set(&path("messages"), &json!(1)).is_err());
+
+        let text = doc.to_text();
+        assert!(text.contains("  player-max-chunk-send-rate: 100.5 # per player\n"));
+        assert!(text.contains("disabled-worlds:\n- a\n- 'yes'\naliases: []\n"));
+        assert!(text.contains(
+            "    flying-player: '<lang:multiplayer.disconnect.flying>'\n    new-key: 'on'\n"
+        ));
+        assert!(text.ends_with("scheduling:\n  async:\n    threads: 4\n"));
+
+        let reparsed = YamlDocument::parse(&text);
+        assert_eq!(reparsed.get(&path("messages.kick.new-key")), Some(json!("on")));
+        assert_eq!(reparsed.get(&path("scheduling.async.threads")), Some(json!(4)));
+    }
+}
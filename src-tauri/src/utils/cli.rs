@@ -1,5 +1,6 @@
-use crate::services::global;
+use crate::services::{ban_manager, global};
 use std::io::{self, Write};
+use std::time::Duration;
 
 pub fn handle_cli() {
     let args: Vec<String> = std::env::args().collect();
@@ -75,6 +76,30 @@ pub fn handle_cli() {
             }
             std::process::exit(0);
         }
+        "ban" | "ban-ip" => {
+            if args.len() > 3 {
+                ban(&args[2], command == "ban-ip", &args[3..]);
+            } else {
+                println!("用法: {} <服务器ID> <目标> [--duration 时长] [原因]", command);
+            }
+            std::process::exit(0);
+        }
+        "pardon" | "pardon-ip" => {
+            if args.len() > 3 {
+                pardon(&args[2], command == "pardon-ip", &args[3]);
+            } else {
+                println!("用法: {} <服务器ID> <目标>", command);
+            }
+            std::process::exit(0);
+        }
+        "ban-history" => {
+            if args.len() > 2 {
+                ban_history(&args[2]);
+            } else {
+                println!("用法: ban-history <服务器ID>");
+            }
+            std::process::exit(0);
+        }
         "help" | "--help" | "-h" => {
             print_help();
             std::process::exit(0);
@@ -100,6 +125,11 @@ fn print_help() {
     println!("  create-id <ID> <名称> <地址> [端口]  创建服务器 ID");
     println!("  list-ids         列出所有服务器 ID");
     println!("  resolve-id <ID>  解析服务器 ID 到地址");
+    println!("  ban <ID> <玩家> [--duration 时长] [原因]  封禁玩家，时长如 30m、2h、7d");
+    println!("  ban-ip <ID> <IP> [--duration 时长] [原因] 封禁 IP");
+    println!("  pardon <ID> <玩家>               解除玩家封禁");
+    println!("  pardon-ip <ID> <IP>              解除 IP 封禁");
+    println!("  ban-history <ID>                 查看封禁历史");
     println!("  help             显示帮助信息");
 }
 
@@ -133,6 +163,84 @@ fn stop_server(id: &str) {
     }
 }
 
+/// args 为 <目标> [--duration 时长] [原因...]
+fn ban(server_id: &str, ip: bool, args: &[String]) {
+    let target = &args[0];
+    let (duration, reason) = match parse_ban_options(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let origin = ban_manager::BanOrigin::local("cli");
+
+    let result = if ip {
+        ban_manager::ban_ip(server_id, target, &reason, duration, &origin)
+    } else {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(ban_manager::ban_player(server_id, target, &reason, duration, &origin))
+    };
+    match result {
+        Ok(record) => match record.expires {
+            Some(expires) => println!("已封禁 {}，到期时间: {}", record.target, expires),
+            None => println!("已永久封禁 {}", record.target),
+        },
+        Err(e) => println!("封禁失败: {}", e),
+    }
+}
+
+/// 时长只能通过 --duration 指定，其余参数都是原因，避免把数字原因当成时长
+fn parse_ban_options(args: &[String]) -> Result<(Option<Duration>, String), String> {
+    let mut duration = None;
+    let mut reason = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--duration" {
+            let text = iter
+                .next()
+                .ok_or("--duration 需要一个时长，如 30m、2h、7d")?;
+            duration = Some(
+                ban_manager::parse_duration(text).ok_or_else(|| format!("无效的时长: {}", text))?,
+            );
+        } else {
+            reason.push(arg.as_str());
+        }
+    }
+    Ok((duration, reason.join(" ")))
+}
+
+fn pardon(server_id: &str, ip: bool, target: &str) {
+    let kind = if ip {
+        ban_manager::KIND_IP
+    } else {
+        ban_manager::KIND_PLAYER
+    };
+    match ban_manager::pardon(server_id, kind, target, "", &ban_manager::BanOrigin::local("cli")) {
+        Ok(record) => println!("已解除封禁: {}", record.target),
+        Err(e) => println!("解除封禁失败: {}", e),
+    }
+}
+
+fn ban_history(server_id: &str) {
+    let records = ban_manager::history(server_id);
+    if records.is_empty() {
+        println!("暂无封禁记录。");
+        return;
+    }
+    println!(
+        "{:<26} {:<8} {:<20} {:<10} {:<6} 原因",
+        "时间", "操作", "目标", "操作者", "来源"
+    );
+    println!("{}", "-".repeat(90));
+    for r in records {
+        println!(
+            "{:<26} {:<8} {:<20} {:<10} {:<6} {}",
+            r.timestamp, r.action, r.target, r.issuer, r.channel, r.reason
+        );
+    }
+}
+
 fn search_mods(query: &str, version: &str, loader: &str) {
     println!("正在搜索 Modrinth: {} (版本: {}, 加载器: {})...", query, version, loader);
     let rt = tokio::runtime::Runtime::new().unwrap();
@@ -293,3 +401,20 @@ fn run_interactive_cli() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_requires_explicit_flag() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(parse_ban_options(&args(&["5"])).unwrap(), (None, "5".to_string()));
+        assert_eq!(
+            parse_ban_options(&args(&["--duration", "2h", "griefing", "spawn"])).unwrap(),
+            (Some(Duration::from_secs(7200)), "griefing spawn".to_string())
+        );
+        assert!(parse_ban_options(&args(&["--duration"])).is_err());
+        assert!(parse_ban_options(&args(&["--duration", "soon"])).is_err());
+    }
+}
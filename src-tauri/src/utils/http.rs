@@ -0,0 +1,8 @@
+use reqwest::Client;
+
+const USER_AGENT: &str = "SeaLantern/0.5.0 (contact@manus.im)";
+
+/// 访问 Modrinth、Mojang 等外部接口共用的 HTTP 客户端配置
+pub fn client() -> Client {
+    Client::builder().user_agent(USER_AGENT).build().unwrap()
+}
@@ -7,6 +7,11 @@ export interface ConfigEntry {
   value_type: string;
   default_value: string;
   category: string;
+  options: string[];
+  min: number | null;
+  max: number | null;
+  since: string | null;
+  removed: string | null;
 }
 
 export interface ServerProperties {
@@ -14,25 +19,99 @@ export interface ServerProperties {
   raw: Record<string, string>;
 }
 
+export interface ConfigFileInfo {
+  path: string;
+  format: "yaml" | "toml" | "json5";
+}
+
+export interface ConfigNode {
+  path: string[];
+  value: unknown;
+  comment: string | null;
+}
+
+export interface ConfigKeyChange {
+  key: string;
+  old: string | null;
+  new: string | null;
+}
+
+export interface ConfigChange {
+  id: string;
+  file: string;
+  timestamp: string;
+  changes: ConfigKeyChange[];
+  created: boolean;
+  restored_from: string | null;
+}
+
+export interface RconStatus {
+  enabled: boolean;
+  port: number;
+  password_set: boolean;
+}
+
 export const configApi = {
-  async readServerProperties(serverPath: string): Promise<ServerProperties> {
+  async readServerProperties(serverId: string): Promise<ServerProperties> {
     return tauriInvoke("read_server_properties", {
-      serverPath,
+      serverId,
     });
   },
 
-  async writeServerProperties(serverPath: string, values: Record<string, string>): Promise<void> {
+  async writeServerProperties(serverId: string, values: Record<string, string>): Promise<void> {
     return tauriInvoke("write_server_properties", {
-      serverPath,
+      serverId,
       values,
     });
   },
 
-  async readConfig(path: string): Promise<Record<string, string>> {
-    return tauriInvoke("read_config", { path });
+  // file 为相对服务器目录的路径
+  async readConfig(serverId: string, file: string): Promise<Record<string, string>> {
+    return tauriInvoke("read_config", { serverId, file });
+  },
+
+  async writeConfig(serverId: string, file: string, values: Record<string, string>): Promise<void> {
+    return tauriInvoke("write_config", { serverId, file, values });
+  },
+
+  async listConfigFiles(serverId: string): Promise<ConfigFileInfo[]> {
+    return tauriInvoke("list_config_files", { serverId });
+  },
+
+  async readConfigFile(serverId: string, file: string): Promise<ConfigNode[]> {
+    return tauriInvoke("read_config_file", { serverId, file });
+  },
+
+  async getConfigValue(serverId: string, file: string, path: string[]): Promise<unknown> {
+    return tauriInvoke("get_config_value", { serverId, file, path });
+  },
+
+  async setConfigValue(
+    serverId: string,
+    file: string,
+    path: string[],
+    value: unknown,
+  ): Promise<void> {
+    return tauriInvoke("set_config_value", { serverId, file, path, value });
+  },
+  async listConfigHistory(serverId: string): Promise<ConfigChange[]> {
+    return tauriInvoke("list_config_history", { serverId });
+  },
+
+  async diffConfigHistory(serverId: string, entryId: string): Promise<ConfigKeyChange[]> {
+    return tauriInvoke("diff_config_history", { serverId, entryId });
+  },
+
+  async restoreConfigHistory(serverId: string, entryId: string): Promise<ConfigChange | null> {
+    return tauriInvoke("restore_config_history", { serverId, entryId });
+  },
+
+  async getRconStatus(serverId: string): Promise<RconStatus> {
+    return tauriInvoke("get_rcon_status", { serverId });
   },
 
-  async writeConfig(path: string, values: Record<string, string>): Promise<void> {
-    return tauriInvoke("write_config", { path, values });
+  // 返回新密码，服务器重启后生效
+  async generateRconPassword(serverId: string): Promise<string> {
+    return tauriInvoke("generate_rcon_password", { serverId });
   },
 };
@@ -14,6 +14,27 @@ export interface BanEntry {
   expires: string;
 }
 
+export interface IpBanEntry {
+  ip: string;
+  reason: string;
+  source: string;
+  created: string;
+  expires: string;
+}
+
+export interface BanRecord {
+  id: string;
+  kind: "player" | "ip";
+  target: string;
+  uuid: string | null;
+  action: "ban" | "pardon" | "expire";
+  reason: string;
+  issuer: string;
+  channel: string;
+  timestamp: string;
+  expires: string | null;
+}
+
 export interface OpEntry {
   uuid: string;
   name: string;
@@ -23,29 +44,57 @@ export interface OpEntry {
 
 export const playerApi = {
   // Read (from files, works anytime)
-  async getWhitelist(serverPath: string): Promise<PlayerEntry[]> {
-    return tauriInvoke("get_whitelist", { serverPath });
+  async getWhitelist(serverId: string): Promise<PlayerEntry[]> {
+    return tauriInvoke("get_whitelist", { serverId });
   },
-  async getBannedPlayers(serverPath: string): Promise<BanEntry[]> {
-    return tauriInvoke("get_banned_players", { serverPath });
+  async getBannedPlayers(serverId: string): Promise<BanEntry[]> {
+    return tauriInvoke("get_banned_players", { serverId });
   },
-  async getOps(serverPath: string): Promise<OpEntry[]> {
-    return tauriInvoke("get_ops", { serverPath });
+  async getBannedIps(serverId: string): Promise<IpBanEntry[]> {
+    return tauriInvoke("get_banned_ips", { serverId });
+  },
+  async getOps(serverId: string): Promise<OpEntry[]> {
+    return tauriInvoke("get_ops", { serverId });
+  },
+  async getBanHistory(serverId: string): Promise<BanRecord[]> {
+    return tauriInvoke("get_ban_history", { serverId });
+  },
+
+  // Online-mode UUIDs come from usercache.json or the profile API; offline-mode ones are computed
+  async resolvePlayerUuid(serverId: string, name: string): Promise<PlayerEntry> {
+    return tauriInvoke("resolve_player_uuid", { serverId, name });
   },
 
-  // Modify (sends commands to running server)
+  // Modify (sent as console commands while the server runs; the list files are edited only while it is stopped)
   async addToWhitelist(serverId: string, name: string): Promise<string> {
     return tauriInvoke("add_to_whitelist", { serverId, name });
   },
   async removeFromWhitelist(serverId: string, name: string): Promise<string> {
     return tauriInvoke("remove_from_whitelist", { serverId, name });
   },
-  async banPlayer(serverId: string, name: string, reason: string = ""): Promise<string> {
-    return tauriInvoke("ban_player", { serverId, name, reason });
+  // durationSecs 为 null 时永久封禁
+  async banPlayer(
+    serverId: string,
+    name: string,
+    reason: string = "",
+    durationSecs: number | null = null,
+  ): Promise<BanRecord> {
+    return tauriInvoke("ban_player", { serverId, name, reason, durationSecs });
   },
-  async unbanPlayer(serverId: string, name: string): Promise<string> {
+  async unbanPlayer(serverId: string, name: string): Promise<BanRecord> {
     return tauriInvoke("unban_player", { serverId, name });
   },
+  async banIp(
+    serverId: string,
+    ip: string,
+    reason: string = "",
+    durationSecs: number | null = null,
+  ): Promise<BanRecord> {
+    return tauriInvoke("ban_ip", { serverId, ip, reason, durationSecs });
+  },
+  async unbanIp(serverId: string, ip: string): Promise<BanRecord> {
+    return tauriInvoke("unban_ip", { serverId, ip });
+  },
   async addOp(serverId: string, name: string): Promise<string> {
     return tauriInvoke("add_op", { serverId, name });
   },
@@ -55,8 +104,4 @@ export const playerApi = {
   async kickPlayer(serverId: string, name: string, reason: string = ""): Promise<string> {
     return tauriInvoke("kick_player", { serverId, name, reason });
   },
-
-  async exportLogs(logs: string[], savePath: string): Promise<void> {
-    return tauriInvoke("export_logs", { logs, savePath });
-  },
 };
@@ -1,5 +1,12 @@
 import { tauriInvoke } from "./tauri";
-import type { ServerInstance } from "../types/server";
+import type {
+  AttachConfig,
+  JvmPresetInfo,
+  ServerInstance,
+  ServerPatch,
+  ServerSettings,
+  ServerUpdateResult,
+} from "../types/server";
 
 export interface ServerStatusInfo {
   id: string;
@@ -8,6 +15,67 @@ export interface ServerStatusInfo {
   uptime: number | null;
 }
 
+export interface ServerPingStatus {
+  version: string;
+  protocol: number;
+  online: number;
+  max: number;
+  sample: { name: string; id: string }[];
+  description: unknown;
+  motd: string;
+  favicon: string | null;
+  latency_ms: number;
+}
+
+export interface QueryStatus {
+  motd: string;
+  game_type: string;
+  version: string;
+  server_mod: string;
+  plugins: string[];
+  map: string;
+  online: number;
+  max: number;
+  host_ip: string;
+  host_port: number;
+  players: string[];
+}
+
+export interface PreflightCheck {
+  id: string;
+  name: string;
+  level: "Pass" | "Warning" | "Error";
+  message: string;
+}
+
+export interface PreflightReport {
+  checks: PreflightCheck[];
+}
+
+export interface CrashFinding {
+  rule: string;
+  title: string;
+  detail: string;
+  suggestion: string;
+  source: string;
+}
+
+export interface CrashDiagnosis {
+  server_id: string;
+  detected_at: number;
+  exit_code: number | null;
+  findings: CrashFinding[];
+  crash_report: string | null;
+  jvm_error_log: string | null;
+  log_tail: string[];
+}
+
+export interface ModpackExportResult {
+  path: string;
+  downloads: string[];
+  bundled: string[];
+}
+
 export const serverApi = {
   async create(params: {
     name: string;
@@ -62,6 +130,7 @@ export const serverApi = {
     maxMemory: number;
     minMemory: number;
     port: number;
+    disableClientMods?: boolean;
   }): Promise<ServerInstance> {
     return tauriInvoke("import_modpack", {
       name: params.name,
@@ -70,9 +139,57 @@ export const serverApi = {
       maxMemory: params.maxMemory,
       minMemory: params.minMemory,
       port: params.port,
+      disableClientMods: params.disableClientMods ?? false,
+    });
+  },
+
+  // port 为空时读取 server.properties 中的 server-port
+  async attachServer(params: {
+    name: string;
+    path: string;
+    port?: number;
+    attach: AttachConfig;
+  }): Promise<ServerInstance> {
+    return tauriInvoke("attach_server", {
+      name: params.name,
+      path: params.path,
+      port: params.port ?? null,
+      attach: params.attach,
     });
   },
 
+  async updateAttachConfig(id: string, attach: AttachConfig | null): Promise<void> {
+    return tauriInvoke("update_attach_config", { id, attach });
+  },
+
+  async listJvmPresets(): Promise<JvmPresetInfo[]> {
+    return tauriInvoke("list_jvm_presets");
+  },
+
+  async previewJvmPreset(id: string, preset: string): Promise<string[]> {
+    return tauriInvoke("preview_jvm_preset", { id, preset });
+  },
+
+  async update(id: string, patch: ServerPatch): Promise<ServerUpdateResult> {
+    return tauriInvoke("update_server", { id, patch });
+  },
+
+  async updateSettings(id: string, settings: ServerSettings): Promise<ServerUpdateResult> {
+    return tauriInvoke("update_server_settings", { id, settings });
+  },
+
+  async getCrashDiagnosis(id: string): Promise<CrashDiagnosis | null> {
+    return tauriInvoke("get_crash_diagnosis", { id });
+  },
+
+  async analyzeCrash(id: string): Promise<CrashDiagnosis> {
+    return tauriInvoke("analyze_crash", { id });
+  },
+
+  async acceptEula(id: string): Promise<void> {
+    return tauriInvoke("accept_eula", { id });
+  },
+
   async start(id: string): Promise<void> {
     return tauriInvoke("start_server", { id });
   },
@@ -89,6 +206,27 @@ export const serverApi = {
     return tauriInvoke("send_command", { id, command });
   },
 
+  async sendRconCommand(id: string, command: string): Promise<string> {
+    return tauriInvoke("send_rcon_command", { id, command });
+  },
+
+  // 失败表示服务器未在响应
+  async ping(id: string): Promise<ServerPingStatus> {
+    return tauriInvoke("ping_server", { id });
+  },
+
+  async preflight(id: string): Promise<PreflightReport> {
+    return tauriInvoke("preflight_check", { id });
+  },
+
+  async query(id: string): Promise<QueryStatus> {
+    return tauriInvoke("query_server", { id });
+  },
+
+  async pingAddress(host: string, port: number): Promise<ServerPingStatus> {
+    return tauriInvoke("ping_address", { host, port });
+  },
+
   async getList(): Promise<ServerInstance[]> {
     return tauriInvoke("get_server_list");
   },
@@ -125,4 +263,24 @@ export const serverApi = {
   async updateServerName(id: string, name: string): Promise<void> {
     return tauriInvoke("update_server_name", { id, name });
   },
+
+  async suggestPort(preferred?: number): Promise<number> {
+    return tauriInvoke("suggest_port", { preferred });
+  },
+
+  async updatePort(id: string, port: number): Promise<ServerUpdateResult> {
+    return tauriInvoke("update_server_port", { id, port });
+  },
+
+  async exportMrpack(
+    id: string,
+    targetPath: string,
+    versionId?: string,
+  ): Promise<ModpackExportResult> {
+    return tauriInvoke("export_server_mrpack", { id, targetPath, versionId: versionId ?? null });
+  },
+
+  async exportArchive(id: string, targetPath: string): Promise<string> {
+    return tauriInvoke("export_server_archive", { id, targetPath });
+  },
 };
@@ -26,6 +26,9 @@ export interface AppSettings {
   locales_base_url?: string;
   developer_mode: boolean;
   close_action: string; // ask, minimize, close
+  curseforge_api_url: string;
+  curseforge_api_key: string;
+  profile_api_url: string;
   bg_color?: string;
   bg_secondary_color?: string;
   bg_tertiary_color?: string;
@@ -91,7 +91,8 @@ export const systemApi = {
     return tauriInvoke("pick_image_file");
   },
 
-  async openFolder(path: string): Promise<void> {
-    return tauriInvoke("open_folder", { path });
+  // path 为相对服务器目录的子目录，省略时打开服务器根目录
+  async openFolder(serverId: string, path?: string): Promise<void> {
+    return tauriInvoke("open_folder", { serverId, path: path ?? null });
   },
 };
@@ -55,11 +55,16 @@ function isSupportedLocale(locale: string): locale is LocaleCode {
 
 function resolveNestedValue(source: TranslationNode, keys: string[]): string | undefined {
   let current: string | TranslationNode | undefined = source;
-  for (const key of keys) {
+  for (let i = 0; i < keys.length; i++) {
     if (!current || typeof current === "string") {
       return undefined;
     }
-    current = current[key];
+    // 叶子键本身可能带点，例如 server.properties 中的 rcon.port
+    const dotted: string | TranslationNode | undefined = current[keys.slice(i).join(".")];
+    if (i < keys.length - 1 && typeof dotted === "string") {
+      return dotted;
+    }
+    current = current[keys[i]];
   }
 
   return typeof current === "string" ? current : undefined;
@@ -106,6 +111,15 @@ class I18n {
     return interpolateVariables(resolved, options);
   }
 
+  /** 当前语言或回退语言中是否存在该翻译 */
+  te(key: string): boolean {
+    const keys = key.split(".");
+    return (
+      resolveNestedValue(translations[this.currentLocale.value], keys) !== undefined ||
+      resolveNestedValue(translations[this.fallbackLocale], keys) !== undefined
+    );
+  }
+
   getTranslations() {
     return translations as Record<string, LanguageFile>;
   }
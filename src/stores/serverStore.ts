@@ -45,6 +45,14 @@ export const useServerStore = defineStore("server", () => {
     currentServerId.value = id;
   }
 
+  /** 后端发出 server-changed 事件时替换列表中的服务器 */
+  function applyServerChange(server: ServerInstance) {
+    const index = servers.value.findIndex((s) => s.id === server.id);
+    if (index >= 0) {
+      servers.value[index] = server;
+    }
+  }
+
   return {
     servers,
     currentServerId,
@@ -55,5 +63,6 @@ export const useServerStore = defineStore("server", () => {
     refreshList,
     refreshStatus,
     setCurrentServer,
+    applyServerChange,
   };
 });
@@ -61,6 +61,9 @@ const defaultSettings: AppSettings = {
   language: "zh-CN",
   developer_mode: false,
   close_action: "ask",
+  curseforge_api_url: "https://api.curseforge.com/v1",
+  curseforge_api_key: "",
+  profile_api_url: "https://api.mojang.com/users/profiles/minecraft",
 };
 
 export const useSettingsStore = defineStore("settings", () => {
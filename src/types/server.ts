@@ -4,6 +4,19 @@ export interface ServerCommand {
   command: string;
 }
 
+export interface ModProfile {
+  id: string;
+  name: string;
+  enabled_jars: string[];
+}
+
+// 附加模式：服务器在外部运行，由用户定义的命令启动和停止
+export interface AttachConfig {
+  pid_file: string | null;
+  start_command: string | null;
+  stop_command: string | null;
+}
+
 export interface ServerInstance {
   id: string;
   name: string;
@@ -21,6 +34,55 @@ export interface ServerInstance {
   created_at: number;
   last_started_at: number | null;
   commands: ServerCommand[];
+  mod_profiles: ModProfile[];
+  active_mod_profile: string | null;
+  attach: AttachConfig | null;
+  eula: EulaAcceptance | null;
+  jvm_preset: string | null;
+  pending_changes: ServerPatch | null;
+}
+
+/** 部分更新，未设置的字段保持不变；jvm_preset 为空字符串表示取消预设 */
+export interface ServerPatch {
+  name?: string | null;
+  core_type?: string | null;
+  core_version?: string | null;
+  mc_version?: string | null;
+  jar_path?: string | null;
+  startup_mode?: "jar" | "bat" | "sh" | null;
+  java_path?: string | null;
+  max_memory?: number | null;
+  min_memory?: number | null;
+  jvm_args?: string[] | null;
+  jvm_preset?: string | null;
+  port?: number | null;
+}
+
+export interface ServerUpdateResult {
+  server: ServerInstance;
+  deferred: boolean;
+}
+
+export interface JvmPresetInfo {
+  id: string;
+  name: string;
+  description: string;
+}
+
+export interface ServerSettings {
+  max_memory: number;
+  min_memory: number;
+  java_path: string;
+  jvm_args: string[];
+  jvm_preset: string | null;
+  startup_mode: "jar" | "bat" | "sh";
+  jar_path?: string | null;
+}
+
+export interface EulaAcceptance {
+  accepted_at: number;
+  accepted_by: string;
+  automatic: boolean;
 }
 
 export type ServerStatus = "Stopped" | "Starting" | "Running" | "Stopping" | "Error";